@@ -346,3 +346,52 @@ fn generics() {
         },
     );
 }
+
+#[test]
+fn array() {
+    #[derive(FromSql, ToSql, Debug, PartialEq)]
+    #[postgres(name = "pair")]
+    struct Pair {
+        name: String,
+        value: i32,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.batch_execute("CREATE TYPE pg_temp.pair AS (name TEXT, value INT)")
+        .unwrap();
+
+    test_type(
+        &mut conn,
+        "pair[]",
+        &[
+            (
+                vec![
+                    Pair {
+                        name: "a".to_owned(),
+                        value: 1,
+                    },
+                    Pair {
+                        name: "b".to_owned(),
+                        value: 2,
+                    },
+                ],
+                "ARRAY[ROW('a', 1), ROW('b', 2)]",
+            ),
+            (vec![], "'{}'"),
+        ],
+    );
+    test_type(
+        &mut conn,
+        "pair[]",
+        &[(
+            vec![
+                Some(Pair {
+                    name: "a".to_owned(),
+                    value: 1,
+                }),
+                None,
+            ],
+            "ARRAY[ROW('a', 1)::pair, NULL]",
+        )],
+    );
+}
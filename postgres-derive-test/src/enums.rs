@@ -201,3 +201,28 @@ fn wrong_name_and_allow_mismatch() {
     let err = conn.query_one("SELECT $1::foo", &[&Foo::Bar]).unwrap_err();
     assert!(err.source().unwrap().is::<WrongType>());
 }
+
+#[test]
+fn array() {
+    #[derive(Debug, ToSql, FromSql, PartialEq)]
+    #[postgres(name = "mood")]
+    enum Mood {
+        #[postgres(name = "sad")]
+        Sad,
+        #[postgres(name = "happy")]
+        Happy,
+    }
+
+    let mut conn = Client::connect("user=postgres host=localhost port=5433", NoTls).unwrap();
+    conn.execute("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy')", &[])
+        .unwrap();
+
+    test_type(
+        &mut conn,
+        "mood[]",
+        &[
+            (vec![Mood::Happy, Mood::Sad], "ARRAY['happy', 'sad']"),
+            (vec![], "'{}'"),
+        ],
+    );
+}
@@ -51,14 +51,42 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! The connector can also be created from the `sslrootcert`, `sslcert`, and `sslkey` settings of a `Config`:
+//!
+//! ```no_run
+//! # #[cfg(feature = "runtime")]
+//! use postgres_native_tls::MakeTlsConnector;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+//! # #[cfg(feature = "runtime")] {
+//! let config = "host=localhost user=postgres sslmode=require sslrootcert=database_cert.pem"
+//!     .parse::<tokio_postgres::Config>()?;
+//! let connector = MakeTlsConnector::from_config(&config)?;
+//!
+//! let connect_future = config.connect(connector);
+//! # }
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
 use native_tls::TlsConnectorBuilder;
+#[cfg(feature = "runtime")]
+use native_tls::{Certificate, Identity};
+#[cfg(feature = "runtime")]
+use std::error::Error;
+#[cfg(feature = "runtime")]
+use std::fs;
 use std::future::Future;
 use std::io;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
+#[cfg(feature = "runtime")]
+use tokio_postgres::config::{Config, SslNegotiation};
 use tokio_postgres::tls;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
@@ -80,6 +108,36 @@ impl MakeTlsConnector {
     pub fn new(connector: native_tls::TlsConnector) -> MakeTlsConnector {
         MakeTlsConnector(connector)
     }
+
+    /// Creates a new connector from the TLS settings of a `Config`.
+    ///
+    /// The certificates in the `sslrootcert` file are trusted in addition to the system's default certificate
+    /// authorities when verifying the server's certificate. The `sslcert` and `sslkey` files are used as the client's
+    /// certificate chain and private key, respectively. They must be PEM encoded, and the key must be in PKCS #8 format.
+    /// ALPN is configured if `sslnegotiation` is `direct`.
+    ///
+    /// An error is returned if only one of `sslcert` and `sslkey` is set.
+    pub fn from_config(config: &Config) -> Result<MakeTlsConnector, Box<dyn Error + Sync + Send>> {
+        let mut builder = native_tls::TlsConnector::builder();
+        if let Some(ssl_root_cert) = config.get_ssl_root_cert() {
+            for cert in Certificate::stack_from_pem(&fs::read(ssl_root_cert)?)? {
+                builder.add_root_certificate(cert);
+            }
+        }
+        match (config.get_ssl_cert(), config.get_ssl_key()) {
+            (Some(ssl_cert), Some(ssl_key)) => {
+                let identity = Identity::from_pkcs8(&fs::read(ssl_cert)?, &fs::read(ssl_key)?)?;
+                builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => return Err("sslcert and sslkey must be set together".into()),
+        }
+        if config.get_ssl_negotiation() == SslNegotiation::Direct {
+            set_postgresql_alpn(&mut builder);
+        }
+
+        Ok(MakeTlsConnector::new(builder.build()?))
+    }
 }
 
 #[cfg(feature = "runtime")]
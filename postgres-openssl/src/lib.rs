@@ -45,15 +45,35 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! The connector can also be created from the `sslrootcert`, `sslcert`, and `sslkey` settings of a `Config`:
+//!
+//! ```no_run
+//! # #[cfg(feature = "runtime")]
+//! use postgres_openssl::MakeTlsConnector;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # #[cfg(feature = "runtime")] {
+//! let config = "host=localhost user=postgres sslmode=require sslrootcert=database_cert.pem"
+//!     .parse::<tokio_postgres::Config>()?;
+//! let connector = MakeTlsConnector::from_config(&config)?;
+//!
+//! let connect_future = config.connect(connector);
+//! # }
+//!
+//! // ...
+//! # Ok(())
+//! # }
+//! ```
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
 #[cfg(feature = "runtime")]
 use openssl::error::ErrorStack;
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
-#[cfg(feature = "runtime")]
-use openssl::ssl::SslConnector;
 use openssl::ssl::{self, ConnectConfiguration, SslConnectorBuilder, SslRef};
+#[cfg(feature = "runtime")]
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod};
 use openssl::x509::X509VerifyResult;
 use std::error::Error;
 use std::fmt::{self, Debug};
@@ -65,6 +85,8 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite, BufReader, ReadBuf};
 use tokio_openssl::SslStream;
+#[cfg(feature = "runtime")]
+use tokio_postgres::config::{Config, SslNegotiation};
 use tokio_postgres::tls;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
@@ -96,6 +118,37 @@ impl MakeTlsConnector {
         }
     }
 
+    /// Creates a new connector from the TLS settings of a `Config`.
+    ///
+    /// The certificates in the `sslrootcert` file are trusted in addition to the system's default certificate
+    /// authorities when verifying the server's certificate. The `sslcert` and `sslkey` files are used as the client's
+    /// certificate chain and private key, respectively, and must be PEM encoded. ALPN is configured if `sslnegotiation`
+    /// is `direct`.
+    ///
+    /// As with connectors created by `new`, the server's certificate and hostname are always verified.
+    ///
+    /// An error is returned if only one of `sslcert` and `sslkey` is set.
+    pub fn from_config(config: &Config) -> Result<MakeTlsConnector, Box<dyn Error + Sync + Send>> {
+        let mut builder = SslConnector::builder(SslMethod::tls())?;
+        if let Some(ssl_root_cert) = config.get_ssl_root_cert() {
+            builder.set_ca_file(ssl_root_cert)?;
+        }
+        match (config.get_ssl_cert(), config.get_ssl_key()) {
+            (Some(ssl_cert), Some(ssl_key)) => {
+                builder.set_certificate_chain_file(ssl_cert)?;
+                builder.set_private_key_file(ssl_key, SslFiletype::PEM)?;
+                builder.check_private_key()?;
+            }
+            (None, None) => {}
+            _ => return Err("sslcert and sslkey must be set together".into()),
+        }
+        if config.get_ssl_negotiation() == SslNegotiation::Direct {
+            set_postgresql_alpn(&mut builder)?;
+        }
+
+        Ok(MakeTlsConnector::new(builder.build()))
+    }
+
     /// Sets a callback used to apply per-connection configuration.
     ///
     /// The the callback is provided the domain name along with the `ConnectConfiguration`.
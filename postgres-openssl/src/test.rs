@@ -122,3 +122,35 @@ async fn runtime() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].get::<_, i32>(0), 1);
 }
+
+#[tokio::test]
+#[cfg(feature = "runtime")]
+async fn from_config() {
+    let config = "host=localhost port=5433 user=ssl_user dbname=postgres sslmode=require \
+                  sslrootcert=../test/server.crt"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let connector = MakeTlsConnector::from_config(&config).unwrap();
+
+    let (client, connection) = config.connect(connector).await.unwrap();
+    let connection = connection.map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    let rows = client.query("SELECT 1::INT4", &[]).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[test]
+#[cfg(feature = "runtime")]
+fn from_config_errors() {
+    let config = "host=localhost sslrootcert=../test/missing.crt"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    assert!(MakeTlsConnector::from_config(&config).is_err());
+
+    let config = "host=localhost sslkey=../test/server.crt"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let err = MakeTlsConnector::from_config(&config).err().unwrap();
+    assert_eq!(err.to_string(), "sslcert and sslkey must be set together");
+}
@@ -130,6 +130,7 @@ enum State {
 pub struct ScramSha256 {
     message: String,
     state: State,
+    iteration_count: Option<u32>,
 }
 
 impl ScramSha256 {
@@ -158,6 +159,7 @@ impl ScramSha256 {
                 password: normalize(password),
                 channel_binding,
             },
+            iteration_count: None,
         }
     }
 
@@ -198,6 +200,7 @@ impl ScramSha256 {
         };
 
         let salted_password = hi(&password, &salt, parsed.iteration_count);
+        self.iteration_count = Some(parsed.iteration_count);
 
         let mut hmac = Hmac::<Sha256>::new_from_slice(&salted_password)
             .expect("HMAC is able to accept all key sizes");
@@ -242,6 +245,13 @@ impl ScramSha256 {
         Ok(())
     }
 
+    /// Returns the PBKDF2 iteration count requested by the backend.
+    ///
+    /// This is `None` until `update()` has successfully processed the backend's first message.
+    pub fn iteration_count(&self) -> Option<u32> {
+        self.iteration_count
+    }
+
     /// Finalizes the authentication process.
     ///
     /// This should be called when the backend sends an `AuthenticationSASLFinal` message.
@@ -469,8 +479,11 @@ mod test {
         );
         assert_eq!(str::from_utf8(scram.message()).unwrap(), client_first);
 
+        assert_eq!(scram.iteration_count(), None);
+
         scram.update(server_first.as_bytes()).unwrap();
         assert_eq!(str::from_utf8(scram.message()).unwrap(), client_final);
+        assert_eq!(scram.iteration_count(), Some(4096));
 
         scram.finish(server_final.as_bytes()).unwrap();
     }
@@ -550,6 +550,18 @@ impl DataRowBody {
         &self.storage
     }
 
+    /// Returns a copy of the body which owns its own buffer.
+    ///
+    /// A body parsed from a batch of messages shares that batch's buffer, so retaining it keeps the whole batch alive.
+    /// The copy only holds the bytes of this row.
+    #[inline]
+    pub fn detach(&self) -> DataRowBody {
+        DataRowBody {
+            storage: Bytes::copy_from_slice(&self.storage),
+            len: self.len,
+        }
+    }
+
     #[inline]
     pub unsafe fn clean(&mut self) {
         self.storage.clear();
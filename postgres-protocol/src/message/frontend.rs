@@ -245,6 +245,15 @@ pub fn sasl_response(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
     })
 }
 
+#[inline]
+pub fn gss_response(data: &[u8], buf: &mut BytesMut) -> io::Result<()> {
+    buf.put_u8(b'p');
+    write_body(buf, |buf| {
+        buf.put_slice(data);
+        Ok(())
+    })
+}
+
 #[inline]
 pub fn ssl_request(buf: &mut BytesMut) {
     write_body(buf, |buf| {
@@ -0,0 +1,266 @@
+//! Geometric types.
+
+use bytes::{BufMut, BytesMut};
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use std::convert::TryFrom;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `POINT` type.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PgPoint {
+    /// The x coordinate.
+    pub x: f64,
+    /// The y coordinate.
+    pub y: f64,
+}
+
+impl PgPoint {
+    /// Creates a new point.
+    pub fn new(x: f64, y: f64) -> PgPoint {
+        PgPoint { x, y }
+    }
+}
+
+impl<'a> FromSql<'a> for PgPoint {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let point = types::point_from_sql(raw)?;
+        Ok(PgPoint::new(point.x(), point.y()))
+    }
+
+    accepts!(POINT);
+}
+
+impl ToSql for PgPoint {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::point_to_sql(self.x, self.y, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(POINT);
+    to_sql_checked!();
+}
+
+/// Postgres `BOX` type.
+///
+/// The server normalizes boxes so that `high` is the upper right corner and `low` is the lower left corner, so the
+/// corners of a box which is read back may be swapped relative to the ones which were written.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PgBox {
+    /// The upper right corner.
+    pub high: PgPoint,
+    /// The lower left corner.
+    pub low: PgPoint,
+}
+
+impl<'a> FromSql<'a> for PgBox {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let b = types::box_from_sql(raw)?;
+        Ok(PgBox {
+            high: PgPoint::new(b.upper_right().x(), b.upper_right().y()),
+            low: PgPoint::new(b.lower_left().x(), b.lower_left().y()),
+        })
+    }
+
+    accepts!(BOX);
+}
+
+impl ToSql for PgBox {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::box_to_sql(self.high.x, self.high.y, self.low.x, self.low.y, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(BOX);
+    to_sql_checked!();
+}
+
+/// Postgres `LINE` type.
+///
+/// The line is represented by the coefficients of the equation `a*x + b*y + c = 0`, of which `a` and `b` cannot
+/// both be zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PgLine {
+    /// The coefficient of `x`.
+    pub a: f64,
+    /// The coefficient of `y`.
+    pub b: f64,
+    /// The constant term.
+    pub c: f64,
+}
+
+impl<'a> FromSql<'a> for PgLine {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let [a, b, c] = read_f64s(raw)?;
+        Ok(PgLine { a, b, c })
+    }
+
+    accepts!(LINE);
+}
+
+impl ToSql for PgLine {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        out.put_f64(self.a);
+        out.put_f64(self.b);
+        out.put_f64(self.c);
+        Ok(IsNull::No)
+    }
+
+    accepts!(LINE);
+    to_sql_checked!();
+}
+
+/// Postgres `LSEG` type.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PgLseg {
+    /// The first end point.
+    pub start: PgPoint,
+    /// The second end point.
+    pub end: PgPoint,
+}
+
+impl<'a> FromSql<'a> for PgLseg {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let [x1, y1, x2, y2] = read_f64s(raw)?;
+        Ok(PgLseg {
+            start: PgPoint::new(x1, y1),
+            end: PgPoint::new(x2, y2),
+        })
+    }
+
+    accepts!(LSEG);
+}
+
+impl ToSql for PgLseg {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::point_to_sql(self.start.x, self.start.y, out);
+        types::point_to_sql(self.end.x, self.end.y, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(LSEG);
+    to_sql_checked!();
+}
+
+/// Postgres `PATH` type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PgPath {
+    /// Whether the path is closed, connecting the last point back to the first.
+    pub closed: bool,
+    /// The points of the path.
+    pub points: Vec<PgPoint>,
+}
+
+impl<'a> FromSql<'a> for PgPath {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let path = types::path_from_sql(raw)?;
+        let points = path
+            .points()
+            .map(|p| Ok(PgPoint::new(p.x(), p.y())))
+            .collect()?;
+        Ok(PgPath {
+            closed: path.closed(),
+            points,
+        })
+    }
+
+    accepts!(PATH);
+}
+
+impl ToSql for PgPath {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::path_to_sql(self.closed, self.points.iter().map(|p| (p.x, p.y)), out)?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(PATH);
+    to_sql_checked!();
+}
+
+/// Postgres `POLYGON` type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PgPolygon {
+    /// The vertices of the polygon.
+    pub points: Vec<PgPoint>,
+}
+
+impl<'a> FromSql<'a> for PgPolygon {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        if raw.len() < 4 {
+            return Err("invalid buffer size".into());
+        }
+        let (count, raw) = raw.split_at(4);
+        let count = types::int4_from_sql(count)?;
+        if count < 0 || raw.len() != count as usize * 16 {
+            return Err("invalid buffer size".into());
+        }
+
+        let points = raw
+            .chunks_exact(16)
+            .map(|p| types::point_from_sql(p).map(|p| PgPoint::new(p.x(), p.y())))
+            .collect::<Result<_, _>>()?;
+        Ok(PgPolygon { points })
+    }
+
+    accepts!(POLYGON);
+}
+
+impl ToSql for PgPolygon {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let count = i32::try_from(self.points.len()).map_err(|_| "too many points in polygon")?;
+        types::int4_to_sql(count, out);
+        for point in &self.points {
+            types::point_to_sql(point.x, point.y, out);
+        }
+        Ok(IsNull::No)
+    }
+
+    accepts!(POLYGON);
+    to_sql_checked!();
+}
+
+/// Postgres `CIRCLE` type.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PgCircle {
+    /// The center of the circle.
+    pub center: PgPoint,
+    /// The radius of the circle.
+    pub radius: f64,
+}
+
+impl<'a> FromSql<'a> for PgCircle {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let [x, y, radius] = read_f64s(raw)?;
+        Ok(PgCircle {
+            center: PgPoint::new(x, y),
+            radius,
+        })
+    }
+
+    accepts!(CIRCLE);
+}
+
+impl ToSql for PgCircle {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::point_to_sql(self.center.x, self.center.y, out);
+        out.put_f64(self.radius);
+        Ok(IsNull::No)
+    }
+
+    accepts!(CIRCLE);
+    to_sql_checked!();
+}
+
+fn read_f64s<const N: usize>(raw: &[u8]) -> Result<[f64; N], Box<dyn Error + Sync + Send>> {
+    if raw.len() != N * 8 {
+        return Err("invalid buffer size".into());
+    }
+
+    let mut values = [0.; N];
+    for (value, chunk) in values.iter_mut().zip(raw.chunks_exact(8)) {
+        *value = types::float8_from_sql(chunk)?;
+    }
+    Ok(values)
+}
@@ -205,7 +205,16 @@ use crate::type_gen::{Inner, Other};
 pub use postgres_protocol::Oid;
 
 #[doc(inline)]
-pub use pg_lsn::PgLsn;
+pub use geometric::{PgBox, PgCircle, PgLine, PgLseg, PgPath, PgPoint, PgPolygon};
+
+#[doc(inline)]
+pub use pg_jsonpath::PgJsonPath;
+
+#[doc(inline)]
+pub use pg_lsn::{ParseLsnError, PgLsn};
+
+#[doc(inline)]
+pub use pg_money::PgMoney;
 
 pub use crate::special::{Date, Timestamp};
 use bytes::BytesMut;
@@ -299,7 +308,10 @@ mod uuid_1;
 #[cfg(feature = "with-time-0_2")]
 extern crate time_02 as time;
 
+mod geometric;
+mod pg_jsonpath;
 mod pg_lsn;
+mod pg_money;
 #[doc(hidden)]
 pub mod private;
 mod special;
@@ -364,6 +376,14 @@ impl Type {
     pub fn name(&self) -> &str {
         self.0.name()
     }
+
+    /// Returns the name of this type qualified by its schema, such as `myschema.mytype`.
+    ///
+    /// Unlike the `Display` implementation, this always includes the schema, even for types in `public` and
+    /// `pg_catalog`. The names are not quoted.
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema(), self.name())
+    }
 }
 
 /// Represents the kind of a Postgres type.
@@ -431,15 +451,23 @@ impl Error for WasNull {}
 pub struct WrongType {
     postgres: Type,
     rust: &'static str,
+    column: Option<String>,
 }
 
 impl fmt::Display for WrongType {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            fmt,
-            "cannot convert between the Rust type `{}` and the Postgres type `{}`",
-            self.rust, self.postgres,
-        )
+        match &self.column {
+            Some(column) => write!(
+                fmt,
+                "cannot convert column \"{}\" of type {} to {}",
+                column, self.postgres, self.rust,
+            ),
+            None => write!(
+                fmt,
+                "cannot convert between the Rust type `{}` and the Postgres type `{}`",
+                self.rust, self.postgres,
+            ),
+        }
     }
 }
 
@@ -451,8 +479,30 @@ impl WrongType {
         WrongType {
             postgres: ty,
             rust: type_name::<T>(),
+            column: None,
         }
     }
+
+    /// Records the name of the column whose value could not be converted, which is included in the error message.
+    pub fn with_column(mut self, column: impl Into<String>) -> WrongType {
+        self.column = Some(column.into());
+        self
+    }
+
+    /// Returns the Postgres type involved in the conversion.
+    pub fn postgres_type(&self) -> &Type {
+        &self.postgres
+    }
+
+    /// Returns the name of the Rust type involved in the conversion.
+    pub fn rust_type(&self) -> &'static str {
+        self.rust
+    }
+
+    /// Returns the name of the column whose value could not be converted, if known.
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
 }
 
 /// A trait for types that can be created from a Postgres value.
@@ -466,14 +516,15 @@ impl WrongType {
 /// |-----------------------------------|-----------------------------------------------|
 /// | `bool`                            | BOOL                                          |
 /// | `i8`                              | "char"                                        |
+/// | `char`                            | "char"                                        |
 /// | `i16`                             | SMALLINT, SMALLSERIAL                         |
 /// | `i32`                             | INT, SERIAL                                   |
 /// | `u32`                             | OID                                           |
 /// | `i64`                             | BIGINT, BIGSERIAL                             |
 /// | `f32`                             | REAL                                          |
 /// | `f64`                             | DOUBLE PRECISION                              |
-/// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME, UNKNOWN |
-/// |                                   | LTREE, LQUERY, LTXTQUERY                      |
+/// | `&str`/`String`/`Cow<str>`        | VARCHAR, CHAR(n), TEXT, CITEXT, NAME, UNKNOWN |
+/// |                                   | LTREE, LQUERY, LTXTQUERY, REFCURSOR           |
 /// | `&[u8]`/`Vec<u8>`                 | BYTEA                                         |
 /// | `HashMap<String, Option<String>>` | HSTORE                                        |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE           |
@@ -527,6 +578,8 @@ impl WrongType {
 ///
 /// `FromSql` is implemented for `Vec<T>`, `Box<[T]>` and `[T; N]` where `T`
 /// implements `FromSql`, and corresponds to one-dimensional Postgres arrays.
+/// This includes the `int2vector` and `oidvector` types used by the system
+/// catalogs, which decode into `Vec<i16>` and `Vec<Oid>` respectively.
 ///
 /// **Note:** the impl for arrays only exist when the Cargo feature `array-impls`
 /// is enabled.
@@ -702,6 +755,16 @@ impl<'a> FromSql<'a> for Box<str> {
     }
 }
 
+impl<'a> FromSql<'a> for Cow<'a, str> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Cow<'a, str>, Box<dyn Error + Sync + Send>> {
+        <&str as FromSql>::from_sql(ty, raw).map(Cow::Borrowed)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&str as FromSql>::accepts(ty)
+    }
+}
+
 impl<'a> FromSql<'a> for &'a str {
     fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<&'a str, Box<dyn Error + Sync + Send>> {
         match *ty {
@@ -714,7 +777,12 @@ impl<'a> FromSql<'a> for &'a str {
 
     fn accepts(ty: &Type) -> bool {
         match *ty {
-            Type::VARCHAR | Type::TEXT | Type::BPCHAR | Type::NAME | Type::UNKNOWN => true,
+            Type::VARCHAR
+            | Type::TEXT
+            | Type::BPCHAR
+            | Type::NAME
+            | Type::UNKNOWN
+            | Type::REFCURSOR => true,
             ref ty
                 if (ty.name() == "citext"
                     || ty.name() == "ltree"
@@ -749,6 +817,21 @@ simple_from!(i64, int8_from_sql, INT8);
 simple_from!(f32, float4_from_sql, FLOAT4);
 simple_from!(f64, float8_from_sql, FLOAT8);
 
+// The single-byte "char" type, as opposed to CHAR(n), which is BPCHAR and decodes as a string. Only ASCII values are
+// accepted since a lone byte above 0x7f isn't a meaningful character in a UTF-8 database.
+impl<'a> FromSql<'a> for char {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<char, Box<dyn Error + Sync + Send>> {
+        let v = types::char_from_sql(raw)? as u8;
+        if v.is_ascii() {
+            Ok(char::from(v))
+        } else {
+            Err("\"char\" value is not ASCII".into())
+        }
+    }
+
+    accepts!(CHAR);
+}
+
 impl<'a, S> FromSql<'a> for HashMap<String, Option<String>, S>
 where
     S: Default + BuildHasher,
@@ -780,12 +863,12 @@ impl<'a> FromSql<'a> for SystemTime {
         let offset = Duration::new(secs, nsec as u32);
 
         let time = if negative {
-            epoch - offset
+            epoch.checked_sub(offset)
         } else {
-            epoch + offset
+            epoch.checked_add(offset)
         };
 
-        Ok(time)
+        time.ok_or_else(|| "value too large to decode as a SystemTime".into())
     }
 
     accepts!(TIMESTAMP, TIMESTAMPTZ);
@@ -819,6 +902,7 @@ pub enum IsNull {
 /// |-----------------------------------|--------------------------------------|
 /// | `bool`                            | BOOL                                 |
 /// | `i8`                              | "char"                               |
+/// | `char`                            | "char"                               |
 /// | `i16`                             | SMALLINT, SMALLSERIAL                |
 /// | `i32`                             | INT, SERIAL                          |
 /// | `u32`                             | OID                                  |
@@ -826,7 +910,7 @@ pub enum IsNull {
 /// | `f32`                             | REAL                                 |
 /// | `f64`                             | DOUBLE PRECISION                     |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME |
-/// |                                   | LTREE, LQUERY, LTXTQUERY             |
+/// |                                   | LTREE, LQUERY, LTXTQUERY, REFCURSOR  |
 /// | `&[u8]`/`Vec<u8>`/`[u8; N]`       | BYTEA                                |
 /// | `HashMap<String, Option<String>>` | HSTORE                               |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE  |
@@ -1110,7 +1194,12 @@ impl ToSql for &str {
     fn accepts(ty: &Type) -> bool {
         matches!(
             *ty,
-            Type::VARCHAR | Type::TEXT | Type::BPCHAR | Type::NAME | Type::UNKNOWN
+            Type::VARCHAR
+                | Type::TEXT
+                | Type::BPCHAR
+                | Type::NAME
+                | Type::UNKNOWN
+                | Type::REFCURSOR
         ) || matches!(ty.name(), "citext" | "ltree" | "lquery" | "ltxtquery")
     }
 
@@ -1180,6 +1269,20 @@ simple_to!(i64, int8_to_sql, INT8);
 simple_to!(f32, float4_to_sql, FLOAT4);
 simple_to!(f64, float8_to_sql, FLOAT8);
 
+impl ToSql for char {
+    fn to_sql(&self, _: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if !self.is_ascii() {
+            return Err("only ASCII characters can be encoded as \"char\"".into());
+        }
+        types::char_to_sql(*self as i8, w);
+        Ok(IsNull::No)
+    }
+
+    accepts!(CHAR);
+
+    to_sql_checked!();
+}
+
 impl<H> ToSql for HashMap<String, Option<String>, H>
 where
     H: BuildHasher,
@@ -1203,13 +1306,19 @@ impl ToSql for SystemTime {
     fn to_sql(&self, _: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
         let epoch = UNIX_EPOCH + Duration::from_secs(TIME_SEC_CONVERSION);
 
-        let to_usec =
-            |d: Duration| d.as_secs() * USEC_PER_SEC + u64::from(d.subsec_nanos()) / NSEC_PER_USEC;
+        let to_usec = |d: Duration| {
+            d.as_secs()
+                .checked_mul(USEC_PER_SEC)
+                .and_then(|usec| usec.checked_add(u64::from(d.subsec_nanos()) / NSEC_PER_USEC))
+                .filter(|usec| *usec <= i64::MAX as u64)
+                .map(|usec| usec as i64)
+        };
 
         let time = match self.duration_since(epoch) {
-            Ok(duration) => to_usec(duration) as i64,
-            Err(e) => -(to_usec(e.duration()) as i64),
+            Ok(duration) => to_usec(duration),
+            Err(e) => to_usec(e.duration()).map(|usec| -usec),
         };
+        let time = time.ok_or("value too large to transmit")?;
 
         types::timestamp_to_sql(time, w);
         Ok(IsNull::No)
@@ -0,0 +1,63 @@
+//! JSON path type.
+
+use bytes::{BufMut, BytesMut};
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `JSONPATH` type.
+///
+/// The value is the path's canonical text representation, as produced by the server, such as `$."a"[*]?(@ > 1)`.
+/// This may differ in spacing and quoting from the text the path was originally written with. The path is not
+/// parsed or validated on the client; the server rejects invalid paths when they are sent.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgJsonPath(pub String);
+
+const JSONPATH_VERSION: u8 = 1;
+
+impl From<String> for PgJsonPath {
+    fn from(path: String) -> Self {
+        PgJsonPath(path)
+    }
+}
+
+impl From<PgJsonPath> for String {
+    fn from(path: PgJsonPath) -> String {
+        path.0
+    }
+}
+
+impl fmt::Display for PgJsonPath {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(&self.0)
+    }
+}
+
+impl<'a> FromSql<'a> for PgJsonPath {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        // The binary format is a version byte followed by the text representation.
+        match raw.split_first() {
+            Some((&JSONPATH_VERSION, path)) => {
+                types::text_from_sql(path).map(|path| PgJsonPath(path.to_string()))
+            }
+            Some(_) => Err("unsupported JSONPATH encoding version".into()),
+            None => Err("invalid buffer size".into()),
+        }
+    }
+
+    accepts!(JSONPATH);
+}
+
+impl ToSql for PgJsonPath {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        out.put_u8(JSONPATH_VERSION);
+        types::text_to_sql(&self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(JSONPATH);
+
+    to_sql_checked!();
+}
@@ -10,6 +10,9 @@ use std::str::FromStr;
 use crate::{FromSql, IsNull, ToSql, Type};
 
 /// Postgres `PG_LSN` type.
+///
+/// The `Display` and `FromStr` implementations use Postgres's text format, which is made up of the high and low 32
+/// bits of the position in hexadecimal, separated by a `/`, such as `16/B374D848`.
 #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
 pub struct PgLsn(u64);
 
@@ -17,6 +20,14 @@ pub struct PgLsn(u64);
 #[derive(Debug)]
 pub struct ParseLsnError(());
 
+impl fmt::Display for ParseLsnError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("invalid LSN")
+    }
+}
+
+impl Error for ParseLsnError {}
+
 impl From<u64> for PgLsn {
     fn from(lsn_u64: u64) -> Self {
         PgLsn(lsn_u64)
@@ -36,11 +47,12 @@ impl FromStr for PgLsn {
         let Some((split_hi, split_lo)) = lsn_str.split_once('/') else {
             return Err(ParseLsnError(()));
         };
+        // Each half must fit in 32 bits.
         let (hi, lo) = (
-            u64::from_str_radix(split_hi, 16).map_err(|_| ParseLsnError(()))?,
-            u64::from_str_radix(split_lo, 16).map_err(|_| ParseLsnError(()))?,
+            u32::from_str_radix(split_hi, 16).map_err(|_| ParseLsnError(()))?,
+            u32::from_str_radix(split_lo, 16).map_err(|_| ParseLsnError(()))?,
         );
-        Ok(PgLsn((hi << 32) | lo))
+        Ok(PgLsn((u64::from(hi) << 32) | u64::from(lo)))
     }
 }
 
@@ -0,0 +1,46 @@
+//! Monetary amount type.
+
+use bytes::BytesMut;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::{FromSql, IsNull, ToSql, Type};
+
+/// Postgres `MONEY` type.
+///
+/// The value is the raw amount in the smallest unit of the currency, such as cents. The number of fractional digits,
+/// and so the scale of this value, is determined by the server's `lc_monetary` setting, and cannot be reliably
+/// determined by the client. Formatting the amount for display is left to the caller.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgMoney(pub i64);
+
+impl From<i64> for PgMoney {
+    fn from(amount: i64) -> Self {
+        PgMoney(amount)
+    }
+}
+
+impl From<PgMoney> for i64 {
+    fn from(money: PgMoney) -> i64 {
+        money.0
+    }
+}
+
+impl<'a> FromSql<'a> for PgMoney {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        types::int8_from_sql(raw).map(PgMoney)
+    }
+
+    accepts!(MONEY);
+}
+
+impl ToSql for PgMoney {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::int8_to_sql(self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(MONEY);
+
+    to_sql_checked!();
+}
@@ -32,20 +32,35 @@ impl<'a> BinaryCopyInWriter<'a> {
         }
     }
 
-    /// Writes a single row.
+    /// Creates a new writer which will write rows of the provided columns.
     ///
-    /// # Panics
+    /// See `tokio_postgres::binary_copy::BinaryCopyInWriter::with_columns` for details.
+    pub fn with_columns(
+        writer: CopyInWriter<'a>,
+        columns: &[(&str, Type)],
+    ) -> BinaryCopyInWriter<'a> {
+        let stream = writer
+            .sink
+            .into_unpinned()
+            .expect("writer has already been written to");
+
+        BinaryCopyInWriter {
+            connection: writer.connection,
+            sink: Box::pin(binary_copy::BinaryCopyInWriter::with_columns(
+                stream, columns,
+            )),
+        }
+    }
+
+    /// Writes a single row.
     ///
-    /// Panics if the number of values provided does not match the number expected.
+    /// Returns an error if the number of values provided does not match the number of columns, or if a value cannot
+    /// be converted to its column's type. The row is not written in that case, and the writer can continue to be used.
     pub fn write(&mut self, values: &[&(dyn ToSql + Sync)]) -> Result<(), Error> {
         self.connection.block_on(self.sink.as_mut().write(values))
     }
 
     /// A maximally-flexible version of `write`.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of values provided does not match the number expected.
     pub fn write_raw<P, I>(&mut self, values: I) -> Result<(), Error>
     where
         P: BorrowToSql,
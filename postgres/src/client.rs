@@ -1,13 +1,15 @@
 use crate::connection::Connection;
 use crate::{
-    CancelToken, Config, CopyInWriter, CopyOutReader, Notifications, RowIter, Statement,
+    CancelToken, Column, Config, CopyInWriter, CopyOutReader, Notifications, RowIter, Statement,
     ToStatement, Transaction, TransactionBuilder,
 };
+use std::collections::HashMap;
 use std::task::Poll;
 use std::time::Duration;
+use tokio_postgres::error::DbError;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use tokio_postgres::types::{BorrowToSql, ToSql, Type};
-use tokio_postgres::{Error, Row, SimpleQueryMessage, Socket};
+use tokio_postgres::types::{BorrowToSql, FromSqlOwned, Oid, ToSql, Type};
+use tokio_postgres::{AuthInfo, Error, ResultSet, Row, SimpleQueryMessage, Socket};
 
 /// A synchronous PostgreSQL client.
 pub struct Client {
@@ -115,6 +117,53 @@ impl Client {
         self.connection.block_on(self.client.query(query, params))
     }
 
+    /// Like `query`, but also returns information about the columns of the rows.
+    ///
+    /// See `tokio_postgres::Client::query_with_schema` for details.
+    pub fn query_with_schema<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Column>, Vec<Row>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection
+            .block_on(self.client.query_with_schema(query, params))
+    }
+
+    /// Like `query`, but returns a `ResultSet` which is independent of the statement.
+    ///
+    /// See `tokio_postgres::Client::query_owned` for details.
+    pub fn query_owned<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<ResultSet, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection
+            .block_on(self.client.query_owned(query, params))
+    }
+
+    /// Like `query`, but also returns the notices emitted while the statement runs.
+    ///
+    /// Notices collected this way are not passed to the `Config::notice_callback`.
+    ///
+    /// See `tokio_postgres::Client::query_with_notices` for details.
+    pub fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection
+            .block_on(self.client.query_with_notices(query, params))
+    }
+
     /// Executes a statement which returns a single row, returning it.
     ///
     /// Returns an error if the query does not return exactly one row.
@@ -149,6 +198,51 @@ impl Client {
             .block_on(self.client.query_one(query, params))
     }
 
+    /// Executes a statement, returning the values of the first column of the resulting rows.
+    ///
+    /// See `tokio_postgres::Client::query_scalar` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let ids = client.query_scalar::<_, i32>("SELECT id FROM users WHERE active", &[])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_scalar<T, F>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<F>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.connection
+            .block_on(self.client.query_scalar(query, params))
+    }
+
+    /// Executes a statement which returns a single row, returning the value of its first column.
+    ///
+    /// See `tokio_postgres::Client::query_scalar_one` for details.
+    pub fn query_scalar_one<T, F>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<F, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.connection
+            .block_on(self.client.query_scalar_one(query, params))
+    }
+
     /// Executes a statement which returns zero or one rows, returning it.
     ///
     /// Returns an error if the query returns more than one row.
@@ -381,6 +475,29 @@ impl Client {
             .block_on(self.client.prepare_typed(query, types))
     }
 
+    /// Like `prepare`, but reuses a statement previously prepared for the same query text.
+    ///
+    /// See `tokio_postgres::Client::prepare_cached` for details.
+    pub fn prepare_cached(&mut self, query: &str) -> Result<Statement, Error> {
+        self.connection.block_on(self.client.prepare_cached(query))
+    }
+
+    /// Creates several prepared statements in a single round trip to the server.
+    ///
+    /// See `tokio_postgres::Client::prepare_all` for details.
+    pub fn prepare_all(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.connection.block_on(self.client.prepare_all(queries))
+    }
+
+    /// Looks up a type by name.
+    ///
+    /// If `schema` is `None`, the name is resolved against the connection's `search_path`. The type's definition is
+    /// loaded and cached in the same way as types referenced by prepared statements.
+    pub fn type_by_name(&mut self, schema: Option<&str>, name: &str) -> Result<Type, Error> {
+        self.connection
+            .block_on(self.client.type_by_name(schema, name))
+    }
+
     /// Executes a `COPY FROM STDIN` statement, returning the number of rows created.
     ///
     /// The `query` argument can either be a `Statement`, or a raw query string. The data in the provided reader is
@@ -512,6 +629,34 @@ impl Client {
         Ok(Transaction::new(self.connection.as_ref(), transaction))
     }
 
+    /// Executes several statements atomically inside a new database transaction, returning the number of rows
+    /// modified by each.
+    ///
+    /// See `tokio_postgres::Client::execute_all_in_transaction` for details.
+    pub fn execute_all_in_transaction(
+        &mut self,
+        statements: &[(&str, &[&(dyn ToSql + Sync)])],
+    ) -> Result<Vec<u64>, Error> {
+        self.connection
+            .block_on(self.client.execute_all_in_transaction(statements))
+    }
+
+    /// Executes a statement with `search_path` set to `schema`, returning the resulting rows.
+    ///
+    /// See `tokio_postgres::Client::query_in_schema` for details.
+    pub fn query_in_schema<T>(
+        &mut self,
+        schema: &str,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection
+            .block_on(self.client.query_in_schema(schema, query, params))
+    }
+
     /// Returns a builder for a transaction with custom settings.
     ///
     /// Unlike the `transaction` method, the builder can be used to control the transaction's isolation level and other
@@ -586,6 +731,23 @@ impl Client {
         CancelToken::new(self.client.cancel_token())
     }
 
+    /// Returns information about how the connection authenticated with the server.
+    pub fn auth_info(&self) -> AuthInfo {
+        self.client.auth_info()
+    }
+
+    /// Returns the value of a parameter the server reported for the connection, such as `server_version`.
+    ///
+    /// See `tokio_postgres::Client::server_parameter` for details.
+    pub fn server_parameter(&self, name: &str) -> Option<String> {
+        self.client.server_parameter(name)
+    }
+
+    /// Returns all of the parameters the server has reported for the connection, along with their latest values.
+    pub fn server_parameters(&self) -> HashMap<String, String> {
+        self.client.server_parameters()
+    }
+
     /// Clears the client's type information cache.
     ///
     /// When user-defined types are used in a query, the client loads their definitions from the database and caches
@@ -595,6 +757,35 @@ impl Client {
         self.client.clear_type_cache();
     }
 
+    /// Removes the type with the given OID from the client's type information cache.
+    ///
+    /// See `tokio_postgres::Client::clear_type` for details.
+    pub fn clear_type(&self, oid: Oid) {
+        self.client.clear_type(oid);
+    }
+
+    /// Adds a type to the client's type information cache, keyed by its OID.
+    ///
+    /// See `tokio_postgres::Client::register_type` for details.
+    pub fn register_type(&self, type_: Type) {
+        self.client.register_type(type_);
+    }
+
+    /// Returns the definition of the type with the given OID from the client's type information cache, if it is
+    /// there.
+    ///
+    /// See `tokio_postgres::Client::cached_type` for details.
+    pub fn cached_type(&self, oid: Oid) -> Option<Type> {
+        self.client.cached_type(oid)
+    }
+
+    /// Clears the client's cache of prepared statements.
+    ///
+    /// See `tokio_postgres::Client::prepare_cached` for details.
+    pub fn clear_statement_cache(&self) {
+        self.client.clear_statement_cache();
+    }
+
     /// Determines if the client's connection has already closed.
     ///
     /// If this returns `true`, the client is no longer usable.
@@ -5,6 +5,7 @@
 use crate::connection::Connection;
 use crate::Client;
 use log::info;
+use std::borrow::Cow;
 use std::fmt;
 use std::net::IpAddr;
 use std::path::Path;
@@ -45,6 +46,10 @@ use tokio_postgres::{Error, Socket};
 /// * `sslnegotiation` - TLS negotiation method. If set to `direct`, the client will perform direct TLS handshake, this only works for PostgreSQL 17 and newer.
 ///     Note that you will need to setup ALPN of TLS client configuration to `postgresql` when using direct TLS.
 ///     If set to `postgres`, the default value, it follows original postgres wire protocol to perform the negotiation.
+/// * `sslrootcert` - The path to a file containing the certificate authorities used to verify the server's certificate.
+/// * `sslcert` - The path to a file containing the client's certificate.
+/// * `sslkey` - The path to a file containing the client's private key.
+/// * `krbsrvname` - The Kerberos service name to use when authenticating with GSSAPI. Defaults to `postgres`.
 /// * `hostaddr` - Numeric IP address of host to connect to. This should be in the standard IPv4 address format,
 ///     e.g., 172.28.40.9. If your machine supports IPv6, you can also use those addresses.
 ///     If this parameter is not specified, the value of `host` will be looked up to find the corresponding IP address,
@@ -74,9 +79,12 @@ use tokio_postgres::{Error, Socket};
 ///     This option is ignored when connecting with Unix sockets.
 /// * `keepalives_retries` - The maximum number of TCP keepalive probes that will be sent before dropping a connection.
 ///     This option is ignored when connecting with Unix sockets.
+/// * `keepalives_count` - An alias for `keepalives_retries`, matching the name used by libpq.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
 ///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
-///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
+///     in a database cluster as opposed to the secondary read-only mirrors. If set to `read-only`, the client will
+///     instead require that `transaction_read_only` is `on`. If set to `prefer-standby`, read-only servers are tried
+///     first, and the remaining hosts are only tried if none of them is available. Defaults to `all`.
 /// * `channel_binding` - Controls usage of channel binding in the authentication process. If set to `disable`, channel
 ///     binding will not be used. If set to `prefer`, channel binding will be used if available, but not used otherwise.
 ///     If set to `require`, the authentication process will fail if channel binding is not used. Defaults to `prefer`.
@@ -246,6 +254,61 @@ impl Config {
         self.config.get_ssl_negotiation()
     }
 
+    /// Sets the path to a file containing the certificate authorities used to verify the server's certificate.
+    pub fn ssl_root_cert<T>(&mut self, ssl_root_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_root_cert(ssl_root_cert);
+        self
+    }
+
+    /// Gets the path to the root certificate file, if one has been configured with the `ssl_root_cert` method.
+    pub fn get_ssl_root_cert(&self) -> Option<&Path> {
+        self.config.get_ssl_root_cert()
+    }
+
+    /// Sets the path to a file containing the client's certificate.
+    pub fn ssl_cert<T>(&mut self, ssl_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_cert(ssl_cert);
+        self
+    }
+
+    /// Gets the path to the client certificate file, if one has been configured with the `ssl_cert` method.
+    pub fn get_ssl_cert(&self) -> Option<&Path> {
+        self.config.get_ssl_cert()
+    }
+
+    /// Sets the path to a file containing the client's private key.
+    pub fn ssl_key<T>(&mut self, ssl_key: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.config.ssl_key(ssl_key);
+        self
+    }
+
+    /// Gets the path to the client private key file, if one has been configured with the `ssl_key` method.
+    pub fn get_ssl_key(&self) -> Option<&Path> {
+        self.config.get_ssl_key()
+    }
+
+    /// Sets the Kerberos service name used to build the service principal name for GSSAPI authentication.
+    ///
+    /// Defaults to `postgres`.
+    pub fn krbsrvname(&mut self, krbsrvname: impl Into<String>) -> &mut Config {
+        self.config.krbsrvname(krbsrvname);
+        self
+    }
+
+    /// Gets the Kerberos service name, if one has been configured with the `krbsrvname` method.
+    pub fn get_krbsrvname(&self) -> Option<&str> {
+        self.config.get_krbsrvname()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -317,6 +380,35 @@ impl Config {
         self.config.get_connect_timeout()
     }
 
+    /// Sets the number of times a failed connection attempt will be retried.
+    ///
+    /// Only transient failures are retried: failures to establish the socket, such as a refused connection or a
+    /// timeout, and the server reporting that it is not yet accepting connections. Other errors, such as
+    /// authentication failures, are returned immediately. If every attempt fails, the error from the last attempt is
+    /// returned. Defaults to 0.
+    pub fn connect_retries(&mut self, connect_retries: u32) -> &mut Config {
+        self.config.connect_retries(connect_retries);
+        self
+    }
+
+    /// Gets the number of connection retries.
+    pub fn get_connect_retries(&self) -> u32 {
+        self.config.get_connect_retries()
+    }
+
+    /// Sets the delay before the first connection retry.
+    ///
+    /// The delay doubles after each subsequent failed attempt. Defaults to 100 milliseconds.
+    pub fn connect_retry_backoff(&mut self, connect_retry_backoff: Duration) -> &mut Config {
+        self.config.connect_retry_backoff(connect_retry_backoff);
+        self
+    }
+
+    /// Gets the delay before the first connection retry.
+    pub fn get_connect_retry_backoff(&self) -> Duration {
+        self.config.get_connect_retry_backoff()
+    }
+
     /// Sets the TCP user timeout.
     ///
     /// This is ignored for Unix domain socket connections. It is only supported on systems where
@@ -387,6 +479,12 @@ impl Config {
         self.config.get_keepalives_retries()
     }
 
+    /// Like `keepalives_retries`, under the name used by libpq.
+    pub fn keepalives_count(&mut self, keepalives_count: u32) -> &mut Config {
+        self.config.keepalives_count(keepalives_count);
+        self
+    }
+
     /// Sets the requirements of the session.
     ///
     /// This can be used to connect to the primary server in a clustered database rather than one of the read-only
@@ -404,6 +502,23 @@ impl Config {
         self.config.get_target_session_attrs()
     }
 
+    /// Sets a query used to decide whether a server is acceptable.
+    ///
+    /// The query is run on each new connection after the `target_session_attrs` check, before the connection is
+    /// returned. It must produce a single boolean value. If it returns `false`, no rows, or fails, the server is
+    /// rejected and the next host is tried. This can be used for checks the built-in session requirements cannot
+    /// express, such as limiting the replication lag of a standby.
+    pub fn validation_query(&mut self, validation_query: impl Into<String>) -> &mut Config {
+        self.config.validation_query(validation_query);
+        self
+    }
+
+    /// Gets the query used to decide whether a server is acceptable, if one has been configured with the
+    /// `validation_query` method.
+    pub fn get_validation_query(&self) -> Option<&str> {
+        self.config.get_validation_query()
+    }
+
     /// Sets the channel binding behavior.
     ///
     /// Defaults to `prefer`.
@@ -430,6 +545,91 @@ impl Config {
         self.config.get_load_balance_hosts()
     }
 
+    /// Sets a function which rewrites the text of queries before they are sent to the server.
+    ///
+    /// See `tokio_postgres::Config::query_rewriter` for details.
+    #[allow(clippy::type_complexity)]
+    pub fn query_rewriter(
+        &mut self,
+        rewriter: Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>,
+    ) -> &mut Config {
+        self.config.query_rewriter(rewriter);
+        self
+    }
+
+    /// Gets the query rewriter, if one has been configured with the `query_rewriter` method.
+    #[allow(clippy::type_complexity)]
+    pub fn get_query_rewriter(&self) -> Option<&Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>> {
+        self.config.get_query_rewriter()
+    }
+
+    /// Sets the maximum number of rows that methods collecting a query's results into memory will buffer.
+    ///
+    /// See `tokio_postgres::Config::max_result_rows` for details.
+    pub fn max_result_rows(&mut self, max_result_rows: usize) -> &mut Config {
+        self.config.max_result_rows(max_result_rows);
+        self
+    }
+
+    /// Gets the maximum number of rows buffered by methods collecting a query's results, if one has been set.
+    pub fn get_max_result_rows(&self) -> Option<usize> {
+        self.config.get_max_result_rows()
+    }
+
+    /// Sets the number of rows to fetch at a time from queries which return rows.
+    ///
+    /// See `tokio_postgres::Config::fetch_count` for details.
+    pub fn fetch_count(&mut self, fetch_count: usize) -> &mut Config {
+        self.config.fetch_count(fetch_count);
+        self
+    }
+
+    /// Gets the number of rows fetched at a time from queries which return rows, if one has been set.
+    pub fn get_fetch_count(&self) -> Option<usize> {
+        self.config.get_fetch_count()
+    }
+
+    /// Sets the number of prepared statements the client caches by their query text.
+    ///
+    /// See `tokio_postgres::Config::statement_cache_capacity` for details.
+    pub fn statement_cache_capacity(&mut self, statement_cache_capacity: usize) -> &mut Config {
+        self.config
+            .statement_cache_capacity(statement_cache_capacity);
+        self
+    }
+
+    /// Gets the number of prepared statements the client caches by their query text.
+    pub fn get_statement_cache_capacity(&self) -> usize {
+        self.config.get_statement_cache_capacity()
+    }
+
+    /// Sets the number of user-defined types the client caches the definitions of.
+    ///
+    /// See `tokio_postgres::Config::type_cache_capacity` for details.
+    pub fn type_cache_capacity(&mut self, type_cache_capacity: usize) -> &mut Config {
+        self.config.type_cache_capacity(type_cache_capacity);
+        self
+    }
+
+    /// Gets the number of user-defined types the client caches the definitions of, if a limit has been set.
+    pub fn get_type_cache_capacity(&self) -> Option<usize> {
+        self.config.get_type_cache_capacity()
+    }
+
+    /// Sets the number of batches of response messages the connection buffers for each request ahead of its consumer.
+    ///
+    /// See `tokio_postgres::Config::response_buffer_capacity` for details.
+    pub fn response_buffer_capacity(&mut self, response_buffer_capacity: usize) -> &mut Config {
+        self.config
+            .response_buffer_capacity(response_buffer_capacity);
+        self
+    }
+
+    /// Gets the number of batches of response messages the connection buffers for each request.
+    pub fn get_response_buffer_capacity(&self) -> usize {
+        self.config.get_response_buffer_capacity()
+    }
+
     /// Sets the notice callback.
     ///
     /// This callback will be invoked with the contents of every
@@ -449,6 +649,31 @@ impl Config {
         self
     }
 
+    /// Sets the maximum number of notifications buffered by the client.
+    ///
+    /// Notifications received by the connection are buffered until they are read through the [`Notifications`] API,
+    /// so a client which receives notifications faster than it reads them would otherwise buffer an unbounded number
+    /// of them. Once the limit is reached, the oldest buffered notification is discarded to make room for each new
+    /// one. The number of notifications discarded is available from [`Notifications::dropped`].
+    ///
+    /// The limit is stored in the underlying `tokio_postgres::Config`, which applies it to its notification streams
+    /// as well.
+    ///
+    /// Defaults to no limit.
+    ///
+    /// [`Notifications`]: crate::Notifications
+    /// [`Notifications::dropped`]: crate::Notifications::dropped
+    pub fn max_buffered_notifications(&mut self, max_buffered_notifications: usize) -> &mut Config {
+        self.config
+            .max_buffered_notifications(max_buffered_notifications);
+        self
+    }
+
+    /// Gets the maximum number of notifications buffered by the client, if one has been set.
+    pub fn get_max_buffered_notifications(&self) -> Option<usize> {
+        self.config.get_max_buffered_notifications()
+    }
+
     /// Opens a connection to a PostgreSQL database.
     pub fn connect<T>(&self, tls: T) -> Result<Client, Error>
     where
@@ -464,7 +689,12 @@ impl Config {
 
         let (client, connection) = runtime.block_on(self.config.connect(tls))?;
 
-        let connection = Connection::new(runtime, connection, self.notice_callback.clone());
+        let connection = Connection::new(
+            runtime,
+            connection,
+            self.notice_callback.clone(),
+            self.config.get_max_buffered_notifications(),
+        );
         Ok(Client::new(connection, client))
     }
 }
@@ -15,6 +15,8 @@ pub struct Connection {
     runtime: Runtime,
     connection: Pin<Box<dyn Stream<Item = Result<AsyncMessage, Error>> + Send>>,
     notifications: VecDeque<Notification>,
+    max_notifications: Option<usize>,
+    dropped_notifications: u64,
     notice_callback: Arc<dyn Fn(DbError) + Sync + Send>,
 }
 
@@ -23,6 +25,7 @@ impl Connection {
         runtime: Runtime,
         connection: tokio_postgres::Connection<S, T>,
         notice_callback: Arc<dyn Fn(DbError) + Sync + Send>,
+        max_notifications: Option<usize>,
     ) -> Connection
     where
         S: AsyncRead + AsyncWrite + Unpin + 'static + Send,
@@ -32,6 +35,8 @@ impl Connection {
             runtime,
             connection: Box::pin(ConnectionStream { connection }),
             notifications: VecDeque::new(),
+            max_notifications,
+            dropped_notifications: 0,
             notice_callback,
         }
     }
@@ -62,6 +67,8 @@ impl Connection {
     {
         let connection = &mut self.connection;
         let notifications = &mut self.notifications;
+        let max_notifications = self.max_notifications;
+        let dropped_notifications = &mut self.dropped_notifications;
         let notice_callback = &mut self.notice_callback;
         self.runtime.block_on({
             future::poll_fn(|cx| {
@@ -69,6 +76,10 @@ impl Connection {
                     match connection.as_mut().poll_next(cx) {
                         Poll::Ready(Some(Ok(AsyncMessage::Notification(notification)))) => {
                             notifications.push_back(notification);
+                            if max_notifications.is_some_and(|max| notifications.len() > max) {
+                                notifications.pop_front();
+                                *dropped_notifications += 1;
+                            }
                         }
                         Poll::Ready(Some(Ok(AsyncMessage::Notice(notice)))) => {
                             notice_callback(notice)
@@ -92,6 +103,10 @@ impl Connection {
     pub fn notifications_mut(&mut self) -> &mut VecDeque<Notification> {
         &mut self.notifications
     }
+
+    pub fn dropped_notifications(&self) -> u64 {
+        self.dropped_notifications
+    }
 }
 
 pub struct ConnectionRef<'a> {
@@ -68,8 +68,8 @@
 
 pub use fallible_iterator;
 pub use tokio_postgres::{
-    error, row, tls, types, Column, IsolationLevel, Notification, Portal, SimpleQueryMessage,
-    Socket, Statement, ToStatement,
+    error, row, tls, types, AuthInfo, AuthMethod, Column, DropBehavior, IsolationLevel,
+    Notification, Portal, SimpleQueryMessage, Socket, Statement, ToStatement,
 };
 
 pub use crate::cancel_token::CancelToken;
@@ -83,7 +83,7 @@ pub use crate::generic_client::GenericClient;
 #[doc(inline)]
 pub use crate::notifications::Notifications;
 #[doc(no_inline)]
-pub use crate::row::{Row, SimpleQueryRow};
+pub use crate::row::{CachedRow, ResultSet, ResultSetRow, Row, SimpleQueryRow};
 pub use crate::row_iter::RowIter;
 #[doc(no_inline)]
 pub use crate::tls::NoTls;
@@ -29,6 +29,13 @@ impl<'a> Notifications<'a> {
         self.connection.notifications().is_empty()
     }
 
+    /// Returns the number of notifications which have been discarded because the buffer was full.
+    ///
+    /// See `Config::max_buffered_notifications` for details.
+    pub fn dropped(&self) -> u64 {
+        self.connection.dropped_notifications()
+    }
+
     /// Returns a nonblocking iterator over notifications.
     ///
     /// If there are no already buffered pending notifications, this iterator will poll the connection but will not
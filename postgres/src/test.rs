@@ -102,6 +102,53 @@ fn transaction_drop() {
     assert_eq!(rows.len(), 0);
 }
 
+#[test]
+fn transaction_drop_behavior() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id SERIAL PRIMARY KEY)")
+        .unwrap();
+
+    let mut transaction = client
+        .build_transaction()
+        .drop_behavior(DropBehavior::Commit)
+        .start()
+        .unwrap();
+    transaction
+        .execute("INSERT INTO foo DEFAULT VALUES", &[])
+        .unwrap();
+    drop(transaction);
+
+    let mut transaction = client
+        .build_transaction()
+        .drop_behavior(DropBehavior::Panic)
+        .start()
+        .unwrap();
+    transaction
+        .execute("INSERT INTO foo DEFAULT VALUES", &[])
+        .unwrap();
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(transaction)));
+    assert!(panic.is_err());
+
+    // A transaction which is dropped while unwinding from a panic is rolled back rather than committed.
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut transaction = client
+            .build_transaction()
+            .drop_behavior(DropBehavior::Commit)
+            .start()
+            .unwrap();
+        transaction
+            .execute("INSERT INTO foo DEFAULT VALUES", &[])
+            .unwrap();
+        panic!("failed after inserting");
+    }));
+    assert!(panic.is_err());
+
+    let rows = client.query("SELECT * FROM foo", &[]).unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
 #[test]
 fn transaction_drop_immediate_rollback() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -478,6 +525,33 @@ fn notifications_timeout_iter() {
     assert_eq!(notifications[1].payload(), "world");
 }
 
+#[test]
+fn max_buffered_notifications() {
+    let mut client = Config::from_str("host=localhost port=5433 user=postgres")
+        .unwrap()
+        .max_buffered_notifications(2)
+        .connect(NoTls)
+        .unwrap();
+
+    client
+        .batch_execute(
+            "\
+        LISTEN max_buffered_notifications;
+        NOTIFY max_buffered_notifications, 'a';
+        NOTIFY max_buffered_notifications, 'b';
+        NOTIFY max_buffered_notifications, 'c';
+    ",
+        )
+        .unwrap();
+
+    let mut notifications = client.notifications();
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications.dropped(), 1);
+    let notifications = notifications.iter().collect::<Vec<_>>().unwrap();
+    assert_eq!(notifications[0].payload(), "b");
+    assert_eq!(notifications[1].payload(), "c");
+}
+
 #[test]
 fn notice_callback() {
     let (notice_tx, notice_rx) = mpsc::sync_channel(64);
@@ -1,12 +1,18 @@
 use crate::connection::ConnectionRef;
-use crate::{CancelToken, CopyInWriter, CopyOutReader, Portal, RowIter, Statement, ToStatement};
-use tokio_postgres::types::{BorrowToSql, ToSql, Type};
-use tokio_postgres::{Error, Row, SimpleQueryMessage};
+use crate::{
+    CancelToken, Column, CopyInWriter, CopyOutReader, DropBehavior, Portal, RowIter, Statement,
+    ToStatement,
+};
+use std::thread;
+use tokio_postgres::error::DbError;
+use tokio_postgres::types::{BorrowToSql, FromSqlOwned, ToSql, Type};
+use tokio_postgres::{Error, ResultSet, Row, SimpleQueryMessage};
 
 /// A representation of a PostgreSQL database transaction.
 ///
-/// Transactions will implicitly roll back by default when dropped. Use the `commit` method to commit the changes made
-/// in the transaction. Transactions can be nested, with inner transactions implemented via savepoints.
+/// Transactions will implicitly roll back by default when dropped, unless configured otherwise with
+/// `TransactionBuilder::drop_behavior`. Use the `commit` method to commit the changes made in the transaction.
+/// Transactions can be nested, with inner transactions implemented via savepoints.
 pub struct Transaction<'a> {
     connection: ConnectionRef<'a>,
     transaction: Option<tokio_postgres::Transaction<'a>>,
@@ -15,7 +21,16 @@ pub struct Transaction<'a> {
 impl Drop for Transaction<'_> {
     fn drop(&mut self) {
         if let Some(transaction) = self.transaction.take() {
+            let drop_behavior = transaction.drop_behavior();
+            if drop_behavior == DropBehavior::Commit && !thread::panicking() {
+                let _ = self.connection.block_on(transaction.commit());
+                return;
+            }
+
             let _ = self.connection.block_on(transaction.rollback());
+            if drop_behavior == DropBehavior::Panic && !thread::panicking() {
+                panic!("transaction dropped without being committed or rolled back");
+            }
         }
     }
 }
@@ -37,6 +52,11 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.take().unwrap().commit())
     }
 
+    /// Returns what the transaction does when it is dropped without being committed or rolled back.
+    pub fn drop_behavior(&self) -> DropBehavior {
+        self.transaction.as_ref().unwrap().drop_behavior()
+    }
+
     /// Rolls the transaction back, discarding all changes made within it.
     ///
     /// This is equivalent to `Transaction`'s `Drop` implementation, but provides any error encountered to the caller.
@@ -61,6 +81,18 @@ impl<'a> Transaction<'a> {
         )
     }
 
+    /// Like `Client::prepare_cached`.
+    pub fn prepare_cached(&mut self, query: &str) -> Result<Statement, Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().prepare_cached(query))
+    }
+
+    /// Like `Client::prepare_all`.
+    pub fn prepare_all(&mut self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().prepare_all(queries))
+    }
+
     /// Like `Client::execute`.
     pub fn execute<T>(&mut self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error>
     where
@@ -79,6 +111,57 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.as_ref().unwrap().query(query, params))
     }
 
+    /// Like `Client::query_with_schema`.
+    pub fn query_with_schema<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Column>, Vec<Row>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_with_schema(query, params),
+        )
+    }
+
+    /// Like `Client::query_owned`.
+    pub fn query_owned<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<ResultSet, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_owned(query, params),
+        )
+    }
+
+    /// Like `Client::query_with_notices`.
+    pub fn query_with_notices<T>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_with_notices(query, params),
+        )
+    }
+
     /// Like `Client::query_one`.
     pub fn query_one<T>(&mut self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<Row, Error>
     where
@@ -88,6 +171,42 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.as_ref().unwrap().query_one(query, params))
     }
 
+    /// Like `Client::query_scalar`.
+    pub fn query_scalar<T, F>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<F>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_scalar(query, params),
+        )
+    }
+
+    /// Like `Client::query_scalar_one`.
+    pub fn query_scalar_one<T, F>(
+        &mut self,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<F, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_scalar_one(query, params),
+        )
+    }
+
     /// Like `Client::query_opt`.
     pub fn query_opt<T>(
         &mut self,
@@ -162,6 +281,26 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.as_ref().unwrap().bind(query, params))
     }
 
+    /// Like `bind`, but creates the portal with the specified name rather than a generated one.
+    ///
+    /// See `tokio_postgres::Transaction::bind_named` for details.
+    pub fn bind_named<T>(
+        &mut self,
+        name: &str,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Portal, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .bind_named(name, query, params),
+        )
+    }
+
     /// Continues execution of a portal, returning the next set of rows.
     ///
     /// Unlike `query`, portals can be incrementally evaluated by limiting the number of rows returned in each call to
@@ -224,6 +363,34 @@ impl<'a> Transaction<'a> {
             .block_on(self.transaction.as_ref().unwrap().batch_execute(query))
     }
 
+    /// Exports the transaction's snapshot, returning an identifier which other sessions can import.
+    ///
+    /// See `tokio_postgres::Transaction::export_snapshot` for details.
+    pub fn export_snapshot(&mut self) -> Result<String, Error> {
+        self.connection
+            .block_on(self.transaction.as_ref().unwrap().export_snapshot())
+    }
+
+    /// Like `Client::query_in_schema`, but restores the previous search path within this transaction.
+    ///
+    /// See `tokio_postgres::Transaction::query_in_schema` for details.
+    pub fn query_in_schema<T>(
+        &mut self,
+        schema: &str,
+        query: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.connection.block_on(
+            self.transaction
+                .as_ref()
+                .unwrap()
+                .query_in_schema(schema, query, params),
+        )
+    }
+
     /// Like `Client::cancel_token`.
     pub fn cancel_token(&self) -> CancelToken {
         CancelToken::new(self.transaction.as_ref().unwrap().cancel_token())
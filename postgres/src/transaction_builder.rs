@@ -1,5 +1,5 @@
 use crate::connection::ConnectionRef;
-use crate::{Error, IsolationLevel, Transaction};
+use crate::{DropBehavior, Error, IsolationLevel, Transaction};
 
 /// A builder for database transactions.
 pub struct TransactionBuilder<'a> {
@@ -40,9 +40,28 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
+    /// Sets the snapshot the transaction runs with, as returned by `Transaction::export_snapshot` on another
+    /// connection.
+    ///
+    /// The transaction will see the same data as the exporting transaction, which must still be open. The isolation
+    /// level must be `RepeatableRead` or `Serializable`.
+    pub fn snapshot(mut self, snapshot: impl Into<String>) -> Self {
+        self.builder = self.builder.snapshot(snapshot);
+        self
+    }
+
+    /// Sets what the transaction does when it is dropped without being committed or rolled back.
+    ///
+    /// Defaults to `DropBehavior::Rollback`. Nested transactions created from the transaction inherit the behavior.
+    pub fn drop_behavior(mut self, drop_behavior: DropBehavior) -> Self {
+        self.builder = self.builder.drop_behavior(drop_behavior);
+        self
+    }
+
     /// Begins the transaction.
     ///
-    /// The transaction will roll back by default - use the `commit` method to commit it.
+    /// The transaction will roll back by default - use the `commit` method to commit it, or `drop_behavior` to change
+    /// what happens when it is dropped.
     pub fn start(mut self) -> Result<Transaction<'a>, Error> {
         let transaction = self.connection.block_on(self.builder.start())?;
         Ok(Transaction::new(self.connection, transaction))
@@ -1,10 +1,34 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use bytes::BytesMut;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use futures_channel::oneshot;
+use futures_util::{pin_mut, TryStreamExt};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::runtime::Runtime;
+use tokio_postgres::types::{ToSql, Type};
 use tokio_postgres::{Client, NoTls};
 
+/// Counts allocations so benchmarks can check that an encoding path does not allocate.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
 fn setup() -> (Client, Runtime) {
     let runtime = Runtime::new().unwrap();
     let (client, conn) = runtime
@@ -54,5 +78,75 @@ fn query_prepared(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, query_prepared);
+fn bytea_param(c: &mut Criterion) {
+    let blob = vec![0xa5u8; 4 * 1024 * 1024];
+    let param: &[u8] = &blob;
+
+    // The slice is written directly into the output buffer, so once it has enough capacity
+    // encoding must not allocate.
+    let mut buf = BytesMut::with_capacity(blob.len());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    param.to_sql_checked(&Type::BYTEA, &mut buf).unwrap();
+    assert_eq!(ALLOCATIONS.load(Ordering::Relaxed), before);
+    assert_eq!(buf.len(), blob.len());
+
+    let mut group = c.benchmark_group("bytea_param");
+    group.throughput(Throughput::Bytes(blob.len() as u64));
+    group.bench_function("encode", |b| {
+        b.iter(|| {
+            buf.clear();
+            param.to_sql_checked(&Type::BYTEA, &mut buf).unwrap();
+        })
+    });
+
+    let (client, runtime) = setup();
+    let statement = runtime
+        .block_on(client.prepare("SELECT octet_length($1::BYTEA)"))
+        .unwrap();
+    group.bench_function("query", |b| {
+        b.iter(|| {
+            runtime
+                .block_on(client.query(&statement, &[&param]))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn row_scan(c: &mut Criterion) {
+    const ROWS: u64 = 1_000_000;
+
+    let (client, runtime) = setup();
+    let statement = runtime
+        .block_on(client.prepare("SELECT i, i::TEXT FROM generate_series(1, $1::INT8) i"))
+        .unwrap();
+
+    let scan = || {
+        runtime.block_on(async {
+            let rows = client.query_raw(&statement, [ROWS as i64]).await.unwrap();
+            pin_mut!(rows);
+            let mut sum = 0;
+            while let Some(row) = rows.try_next().await.unwrap() {
+                sum += row.get::<_, i64>(0);
+            }
+            sum
+        })
+    };
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    scan();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "row_scan: {:.2} allocations per row",
+        allocations as f64 / ROWS as f64
+    );
+
+    let mut group = c.benchmark_group("row_scan");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(ROWS));
+    group.bench_function("dropped", |b| b.iter(scan));
+    group.finish();
+}
+
+criterion_group!(benches, query_prepared, bytea_param, row_scan);
 criterion_main!(benches);
@@ -3,8 +3,13 @@
 //! This module is a copy of query functions using bumpalo arena.
 
 pub mod client;
+pub mod copy_in;
+pub mod copy_out;
+pub mod portal;
 mod query;
 pub mod row;
 mod prepare;
-mod statement;
+pub(crate) mod statement;
+pub mod simple_query;
 mod to_statement;
+pub mod transaction;
@@ -1,17 +1,23 @@
 //! Client
 
+use crate::arena::copy_in::{self, CopyInSink};
+use crate::arena::copy_out::{self, CopyOutStream};
 use crate::arena::prepare::prepare_in;
 use crate::arena::query;
 use crate::arena::query::RowStream;
 use crate::arena::row::Row;
+use crate::arena::simple_query::{self, SimpleQueryStream};
 use crate::arena::statement::Statement;
 use crate::arena::to_statement::ToStatement;
+use crate::arena::transaction::Transaction;
+use crate::types::FromSqlOwned;
 #[cfg(feature = "runtime")]
 use crate::types::{ToSql, Type};
 use crate::{slice_iter, Client, Error};
 use bumpalo::Bump;
+use bytes::Buf;
 use futures_util::{pin_mut, TryStreamExt};
-use postgres_types::BorrowToSql;
+use postgres_types::{BorrowToSql, Format};
 
 impl Client {
     /// Executes a statement, returning a vector of the resulting rows.
@@ -33,13 +39,7 @@ impl Client {
     {
         self.query_raw_in(statement, slice_iter(params), arena)
             .await?
-            .try_fold(
-                bumpalo::collections::Vec::new_in(arena),
-                |mut vec, row| async {
-                    vec.push(row);
-                    Ok(vec)
-                },
-            )
+            .collect_in()
             .await
     }
 
@@ -109,6 +109,71 @@ impl Client {
         Ok(first)
     }
 
+    /// Executes a statement, returning the values of the first column of the resulting rows.
+    ///
+    /// Any columns after the first are ignored, and an error is returned before the statement is executed if it does
+    /// not return any columns.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. If the same statement will be
+    /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
+    /// with the `prepare` method.
+    pub async fn query_scalar_in<'a, T, F>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+        arena: &'a Bump,
+    ) -> Result<bumpalo::collections::Vec<'a, F>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned + 'a,
+    {
+        let statement = statement.__convert().into_statement_in(self, arena).await?;
+        if statement.columns().is_empty() {
+            return Err(Error::column_count(0, 1));
+        }
+
+        query::query_in(&self.inner, statement, slice_iter(params), arena)
+            .await?
+            .try_fold(
+                bumpalo::collections::Vec::new_in(arena),
+                |mut vec, row| async move {
+                    vec.push(row.try_get(0)?);
+                    Ok(vec)
+                },
+            )
+            .await
+    }
+
+    /// Executes a statement which returns a single row, returning the value of its first column.
+    ///
+    /// Returns an error if the query does not return exactly one row, or if it does not return any columns.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. If the same statement will be
+    /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
+    /// with the `prepare` method.
+    pub async fn query_scalar_one_in<'a, T, F>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+        arena: &'a Bump,
+    ) -> Result<F, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned + 'a,
+    {
+        let values = self.query_scalar_in(statement, params, arena).await?;
+        match values.len() {
+            1 => Ok(values.into_iter().next().unwrap()),
+            _ => Err(Error::row_count()),
+        }
+    }
+
     /// The maximally flexible version of [`query`].
     ///
     /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
@@ -159,6 +224,31 @@ impl Client {
         query::query_in(&self.inner, statement, params, arena).await
     }
 
+    /// Like `query_raw_in`, but requests the results in the given formats rather than the binary format.
+    ///
+    /// This is an escape hatch for custom types which have a text output function but no binary one. As in the
+    /// protocol's `Bind` message, a single format applies to every column of the results, and otherwise one format
+    /// must be given for each column.
+    ///
+    /// Values in the text format are decoded as `TEXT` whatever the type of their column, so they can be read as
+    /// `&str` or `String`, or as raw bytes with `Row::get_bytes`.
+    pub async fn query_raw_formats_in<'a, T, P, I>(
+        &self,
+        statement: &'a T,
+        params: I,
+        result_formats: &[Format],
+        arena: &'a Bump,
+    ) -> Result<RowStream<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let statement = statement.__convert().into_statement_in(self, arena).await?;
+        query::query_with_formats_in(&self.inner, statement, params, result_formats, arena).await
+    }
+
     /// Like `query`, but requires the types of query parameters to be explicitly specified.
     ///
     /// Compared to `query`, this method allows performing queries without three round trips (for
@@ -176,13 +266,7 @@ impl Client {
     ) -> Result<bumpalo::collections::Vec<'a, Row<'a>>, Error> {
         self.query_typed_raw_in(query, params.iter().map(|(v, t)| (*v, t.clone())), arena)
             .await?
-            .try_fold(
-                bumpalo::collections::Vec::new_in(arena),
-                |mut vec, row| async {
-                    vec.push(row);
-                    Ok(vec)
-                },
-            )
+            .collect_in()
             .await
     }
 
@@ -232,7 +316,8 @@ impl Client {
         P: BorrowToSql,
         I: IntoIterator<Item = (P, Type)>,
     {
-        query::query_typed_in(&self.inner, query, params, arena).await
+        let query = self.rewrite_query(query);
+        query::query_typed_in(&self.inner, &query, params, arena).await
     }
 
     /// Executes a statement, returning the number of rows modified.
@@ -284,11 +369,64 @@ impl Client {
         query::execute_in(self.inner(), statement, params, arena).await
     }
 
+    /// Executes a `COPY FROM STDIN` statement, returning a sink used to write the copy data.
+    ///
+    /// Small items written to the sink are buffered in `arena` rather than in a separately allocated `BytesMut`. The
+    /// buffer is reused after each flush, so `arena` does not grow with the total amount of data copied.
+    ///
+    /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any. The copy *must*
+    /// be explicitly completed via the `Sink::close` or `finish` methods. If it is not, the copy will be aborted.
+    ///
+    /// An error is returned if the statement does not start a `COPY FROM STDIN`.
+    pub async fn copy_in_in<'a, T, U>(
+        &self,
+        statement: &T,
+        arena: &'a Bump,
+    ) -> Result<CopyInSink<'a, U>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: Buf + 'static + Send,
+    {
+        let statement = statement.__convert().into_statement_in(self, arena).await?;
+        copy_in::copy_in_in(self.inner(), statement, arena).await
+    }
+
+    /// Executes a `COPY TO STDOUT` statement, returning a stream of the resulting data.
+    ///
+    /// Each chunk of data is copied into a slice allocated in `arena` rather than yielded as the `Bytes` it was received
+    /// in. The arena holds every chunk until it is reset, so it grows with the size of the whole export; use
+    /// `CopyOutStream::for_each_in` to process an export too large to hold in memory. The number of rows copied is
+    /// available from the stream's `rows_affected` once it has been exhausted.
+    ///
+    /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any.
+    ///
+    /// An error is returned if the statement does not start a `COPY TO STDOUT`.
+    pub async fn copy_out_in<'a, T>(
+        &self,
+        statement: &T,
+        arena: &'a Bump,
+    ) -> Result<CopyOutStream<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let statement = statement.__convert().into_statement_in(self, arena).await?;
+        copy_out::copy_out_in(self.inner(), statement, arena).await
+    }
+
     /// Creates a new prepared statement.
     ///
     /// Prepared statements can be executed repeatedly, and may contain query parameters (indicated by `$1`, `$2`, etc),
     /// which are set when executed. Prepared statements can only be used with the connection that created them.
-    pub async fn prepare_in<'a>(&self, query: &str, arena: &'a Bump) -> Result<Statement<'a>, Error> {
+    ///
+    /// While a statement for the same query and parameter types is still alive, in this or any other arena, the
+    /// statement already prepared on the server is reused rather than preparing a new one. The server-side statement
+    /// is closed once every statement sharing it has been dropped. `clear_statement_cache` can be used to stop
+    /// existing statements from being reused, for example after the schema they depend on has changed.
+    pub async fn prepare_in<'a>(
+        &self,
+        query: &str,
+        arena: &'a Bump,
+    ) -> Result<Statement<'a>, Error> {
         self.prepare_typed_in(query, &[], arena).await
     }
 
@@ -302,6 +440,38 @@ impl Client {
         parameter_types: &[Type],
         arena: &'a Bump,
     ) -> Result<Statement<'a>, Error> {
-        prepare_in(&self.inner, query, parameter_types, arena).await
+        let query = self.rewrite_query(query);
+        prepare_in(&self.inner, &query, parameter_types, arena).await
+    }
+
+    /// Executes a sequence of SQL statements using the simple query protocol, returning a stream of the results.
+    ///
+    /// Like `simple_query`, the stream yields an enum which indicates either the description of the columns of the
+    /// following rows, the completion of one of the commands, or a row of data. The column names and the values of
+    /// rows are allocated in `arena`, so no `String` is allocated per column.
+    ///
+    /// # Warning
+    ///
+    /// Prepared statements should be use for any query which contains user-specified data, as they provided the
+    /// functionality to safely embed that data in the request. Do not form statements via string concatenation and pass
+    /// them to this method!
+    pub async fn simple_query_in<'a>(
+        &self,
+        query: &str,
+        arena: &'a Bump,
+    ) -> Result<SimpleQueryStream<'a>, Error> {
+        simple_query::simple_query_in(self.inner(), query, arena).await
+    }
+
+    /// Begins a new database transaction whose rows are allocated in `arena`.
+    ///
+    /// The transaction will roll back by default - use the `commit` method to commit it. Rows returned by the
+    /// transaction share its lifetime, so they must be dropped before the client can be used again.
+    pub async fn transaction_in<'a>(
+        &'a mut self,
+        arena: &'a Bump,
+    ) -> Result<Transaction<'a>, Error> {
+        let transaction = self.transaction().await?;
+        Ok(Transaction::new(transaction, arena))
     }
 }
@@ -0,0 +1,245 @@
+//! COPY FROM STDIN
+
+use crate::arena::query;
+use crate::arena::statement::Statement;
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::copy_in::{self, CopyInMessage, CopyInReceiver, SinkState, DEFAULT_FLUSH_THRESHOLD};
+use crate::error::CopyProgress;
+use crate::query::extract_row_affected;
+use crate::{slice_iter, Error};
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use bytes::{Buf, BytesMut};
+use futures_channel::mpsc;
+use futures_util::{future, ready, Sink, SinkExt};
+use log::debug;
+use pin_project_lite::pin_project;
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend::CopyData;
+use std::marker::{PhantomData, PhantomPinned};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pin_project! {
+    /// A sink for `COPY ... FROM STDIN` query data.
+    ///
+    /// Small items are accumulated in a buffer allocated in the arena. The buffer is reused once its contents have
+    /// been sent, so the arena only grows to fit the largest batch buffered between two flushes. Each batch is framed
+    /// as a message in a second buffer owned by the sink, whose memory is reused once the connection has written the
+    /// message out.
+    ///
+    /// The copy *must* be explicitly completed via the `Sink::close` or `finish` methods. If it is
+    /// not, the copy will be aborted.
+    pub struct CopyInSink<'a, T> {
+        #[pin]
+        sender: mpsc::Sender<CopyInMessage>,
+        responses: Responses,
+        buf: Vec<'a, u8>,
+        framed: BytesMut,
+        flush_threshold: usize,
+        state: SinkState,
+        bytes_sent: u64,
+        rows_sent: u64,
+        buffered_rows: u64,
+        #[pin]
+        _p: PhantomPinned,
+        _p2: PhantomData<T>,
+    }
+}
+
+impl<T> CopyInSink<'_, T>
+where
+    T: Buf + 'static + Send,
+{
+    /// A poll-based version of `finish`.
+    pub fn poll_finish(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<u64, Error>> {
+        let r = ready!(self.as_mut().poll_finish_inner(cx));
+        Poll::Ready(r.map_err(|e| e.with_copy_progress(self.progress())))
+    }
+
+    fn poll_finish_inner(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<u64, Error>> {
+        loop {
+            match self.state {
+                SinkState::Active => {
+                    ready!(self.as_mut().poll_flush(cx))?;
+                    let mut this = self.as_mut().project();
+                    ready!(this.sender.as_mut().poll_ready(cx)).map_err(|_| Error::closed())?;
+                    this.sender
+                        .start_send(CopyInMessage::Done)
+                        .map_err(|_| Error::closed())?;
+                    *this.state = SinkState::Closing;
+                }
+                SinkState::Closing => {
+                    let this = self.as_mut().project();
+                    ready!(this.sender.poll_close(cx)).map_err(|_| Error::closed())?;
+                    *this.state = SinkState::Reading;
+                }
+                SinkState::Reading => {
+                    let this = self.as_mut().project();
+                    match ready!(this.responses.poll_next(cx))? {
+                        Message::CommandComplete(body) => {
+                            let rows = extract_row_affected(&body)?;
+                            return Poll::Ready(Ok(rows));
+                        }
+                        _ => return Poll::Ready(Err(Error::unexpected_message())),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Completes the copy, returning the number of rows inserted.
+    ///
+    /// If the copy fails, the progress it had made is available from the error via `Error::copy_progress`.
+    ///
+    /// The `Sink::close` method is equivalent to `finish`, except that it does not return the
+    /// number of rows.
+    pub async fn finish(mut self: Pin<&mut Self>) -> Result<u64, Error> {
+        future::poll_fn(|cx| self.as_mut().poll_finish(cx)).await
+    }
+
+    /// Sends any buffered data to the server.
+    ///
+    /// Like `tokio_postgres::CopyInSink::flush`.
+    pub async fn flush(mut self: Pin<&mut Self>) -> Result<(), Error> {
+        future::poll_fn(|cx| Sink::<T>::poll_flush(self.as_mut(), cx)).await
+    }
+
+    /// Sets the number of bytes which may be buffered before data is sent to the server.
+    ///
+    /// Like `tokio_postgres::CopyInSink::set_flush_threshold`.
+    pub fn set_flush_threshold(self: Pin<&mut Self>, threshold: usize) {
+        *self.project().flush_threshold = threshold;
+    }
+
+    fn progress(&self) -> CopyProgress {
+        CopyProgress::new(self.bytes_sent, self.rows_sent)
+    }
+}
+
+/// Frames `data` as a `CopyData` message in `framed`, splitting it off to be owned by the connection.
+fn frame<B>(data: B, framed: &mut BytesMut) -> Result<FrontendMessage, Error>
+where
+    B: Buf,
+{
+    framed.reserve(data.remaining() + 5);
+    let data = CopyData::new(data).map_err(Error::encode)?;
+    data.write(framed);
+    Ok(FrontendMessage::Raw(framed.split().freeze()))
+}
+
+impl<T> Sink<T> for CopyInSink<'_, T>
+where
+    T: Buf + 'static + Send,
+{
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.project()
+            .sender
+            .poll_ready(cx)
+            .map_err(|_| Error::closed())
+    }
+
+    fn start_send(self: Pin<&mut Self>, mut item: T) -> Result<(), Error> {
+        let this = self.project();
+
+        let len = (this.buf.len() + item.remaining()) as u64;
+        let message = if item.remaining() > *this.flush_threshold {
+            if this.buf.is_empty() {
+                let data: Box<dyn Buf + Send> = Box::new(item);
+                FrontendMessage::CopyData(CopyData::new(data).map_err(Error::encode)?)
+            } else {
+                frame((&this.buf[..]).chain(item), this.framed)?
+            }
+        } else {
+            while item.has_remaining() {
+                let chunk = item.chunk();
+                this.buf.extend_from_slice(chunk);
+                let n = chunk.len();
+                item.advance(n);
+            }
+            if this.buf.len() <= *this.flush_threshold {
+                *this.buffered_rows += 1;
+                return Ok(());
+            }
+            frame(&this.buf[..], this.framed)?
+        };
+        this.sender
+            .start_send(CopyInMessage::Message(message))
+            .map_err(|_| Error::closed())?;
+        this.buf.clear();
+        *this.bytes_sent += len;
+        *this.rows_sent += std::mem::take(this.buffered_rows) + 1;
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let mut this = self.project();
+
+        if !this.buf.is_empty() {
+            ready!(this.sender.as_mut().poll_ready(cx)).map_err(|_| Error::closed())?;
+            let len = this.buf.len() as u64;
+            let message = frame(&this.buf[..], this.framed)?;
+            this.sender
+                .as_mut()
+                .start_send(CopyInMessage::Message(message))
+                .map_err(|_| Error::closed())?;
+            this.buf.clear();
+            *this.bytes_sent += len;
+            *this.rows_sent += std::mem::take(this.buffered_rows);
+        }
+
+        this.sender.poll_flush(cx).map_err(|_| Error::closed())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.poll_finish(cx).map_ok(|_| ())
+    }
+}
+
+pub(crate) async fn copy_in_in<'a, T>(
+    client: &InnerClient,
+    statement: Statement<'_>,
+    arena: &'a Bump,
+) -> Result<CopyInSink<'a, T>, Error>
+where
+    T: Buf + 'static + Send,
+{
+    debug!("executing copy in statement {}", statement.name());
+
+    let buf = query::encode(client, &statement, slice_iter(&[]))?;
+
+    let (mut sender, receiver) = mpsc::channel(1);
+    let receiver = CopyInReceiver::new(receiver);
+    let mut responses = client.send(RequestMessages::CopyIn(receiver))?;
+
+    sender
+        .send(CopyInMessage::Message(FrontendMessage::Raw(buf)))
+        .await
+        .map_err(|_| Error::closed())?;
+
+    if let Err(e) = copy_in::start(&mut responses).await {
+        let _ = sender.send(CopyInMessage::Abandon).await;
+        return Err(e);
+    }
+
+    Ok(CopyInSink {
+        sender,
+        responses,
+        buf: Vec::new_in(arena),
+        framed: BytesMut::new(),
+        flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+        state: SinkState::Active,
+        bytes_sent: 0,
+        rows_sent: 0,
+        buffered_rows: 0,
+        _p: PhantomPinned,
+        _p2: PhantomData,
+    })
+}
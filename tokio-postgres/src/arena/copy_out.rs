@@ -0,0 +1,128 @@
+//! COPY TO STDOUT
+
+use crate::arena::query;
+use crate::arena::statement::Statement;
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::{slice_iter, Error};
+use bumpalo::Bump;
+use bytes::Bytes;
+use futures_util::{future, ready, Stream};
+use log::debug;
+use pin_project_lite::pin_project;
+use postgres_protocol::message::backend::{CopyDataBody, Message};
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub(crate) async fn copy_out_in<'a>(
+    client: &InnerClient,
+    statement: Statement<'_>,
+    arena: &'a Bump,
+) -> Result<CopyOutStream<'a>, Error> {
+    debug!("executing copy out statement {}", statement.name());
+
+    let buf = query::encode(client, &statement, slice_iter(&[]))?;
+    let responses = start(client, buf).await?;
+    Ok(CopyOutStream {
+        responses,
+        rows_affected: None,
+        arena,
+        _p: PhantomPinned,
+    })
+}
+
+async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
+    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    match responses.next().await? {
+        Message::BindComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    match responses.next().await? {
+        Message::CopyOutResponse(_) => {}
+        _ => return Err(Error::copy_expected()),
+    }
+
+    Ok(responses)
+}
+
+pin_project! {
+    /// A stream of `COPY ... TO STDOUT` query data.
+    ///
+    /// Each chunk of data is copied into the arena, so the chunks remain valid for as long as the arena is borrowed.
+    /// Nothing is freed until the arena is reset, so the arena grows with the size of the whole export. Use
+    /// `for_each_in` to process a large export in bounded memory.
+    pub struct CopyOutStream<'a> {
+        responses: Responses,
+        rows_affected: Option<u64>,
+        arena: &'a Bump,
+        #[pin]
+        _p: PhantomPinned,
+    }
+}
+
+impl<'a> Stream for CopyOutStream<'a> {
+    type Item = Result<&'a [u8], Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let arena = self.arena;
+        match ready!(self.poll_data(cx)?) {
+            Some(body) => Poll::Ready(Some(Ok(arena.alloc_slice_copy(body.data())))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl CopyOutStream<'_> {
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<CopyDataBody>, Error>> {
+        let this = self.project();
+        loop {
+            match ready!(this.responses.poll_next(cx)?) {
+                Message::CopyData(body) => return Poll::Ready(Ok(Some(body))),
+                Message::CopyDone => {}
+                Message::CommandComplete(body) => {
+                    *this.rows_affected = Some(query::extract_row_affected(&body)?);
+                }
+                Message::ReadyForQuery(_) => return Poll::Ready(Ok(None)),
+                _ => return Poll::Ready(Err(Error::unexpected_message())),
+            }
+        }
+    }
+
+    /// Drives the stream to completion, passing each chunk of data to `f` along with `scratch`.
+    ///
+    /// Unlike the chunks yielded by the stream, each chunk is passed to `f` as it was received rather than copied into
+    /// the stream's arena. `f` can allocate whatever it parses from the chunk in `scratch`, which is reset once `f`
+    /// returns, so memory use stays flat however large the export is. The chunk and `scratch` are only borrowed for
+    /// the duration of the call, so `f` cannot retain either of them or anything allocated in `scratch`.
+    ///
+    /// Stops at the first error returned by the statement or by `f`. Once the stream has been exhausted,
+    /// `rows_affected` returns the number of rows copied as usual.
+    pub async fn for_each_in<F>(
+        mut self: Pin<&mut Self>,
+        scratch: &mut Bump,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&[u8], &Bump) -> Result<(), Error>,
+    {
+        while let Some(body) = future::poll_fn(|cx| self.as_mut().poll_data(cx)).await? {
+            f(body.data(), scratch)?;
+            scratch.reset();
+        }
+        Ok(())
+    }
+
+    /// Returns the number of rows copied.
+    ///
+    /// This function will return `None` until the stream has been exhausted.
+    pub fn rows_affected(&self) -> Option<u64> {
+        self.rows_affected
+    }
+}
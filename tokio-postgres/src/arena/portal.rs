@@ -0,0 +1,232 @@
+//! Portals
+
+use crate::arena::query;
+use crate::arena::row::Row;
+use crate::arena::statement::Statement;
+use crate::bind;
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::types::BorrowToSql;
+use crate::Error;
+use bumpalo::Bump;
+use futures_util::{ready, Stream};
+use log::debug;
+use pin_project_lite::pin_project;
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll};
+
+pub(crate) async fn bind_in<'a, P, I>(
+    client: &Arc<InnerClient>,
+    statement: Statement<'a>,
+    params: I,
+    arena: &'a Bump,
+) -> Result<Portal<'a>, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let name = bind::next_name();
+    debug!("binding statement {} to portal {}", statement.name(), name);
+
+    let buf = client.with_buf(|buf| {
+        query::encode_bind(&statement, params, &name, buf)?;
+        frontend::sync(buf);
+        Ok(buf.split().freeze())
+    })?;
+
+    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    match responses.next().await? {
+        Message::BindComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    Ok(Portal {
+        client: Arc::downgrade(client),
+        name,
+        statement,
+        arena,
+        complete: false,
+    })
+}
+
+/// A portal whose rows are allocated in an arena.
+///
+/// Rows are fetched from the portal in batches with `fetch`, or through a stream which fetches batches as it is
+/// polled, created with `into_stream`. Like other portals, it can only be used with the connection that created it,
+/// and only exists for the duration of the transaction in which it was created. It is closed when dropped.
+pub struct Portal<'a> {
+    client: Weak<InnerClient>,
+    name: String,
+    statement: Statement<'a>,
+    arena: &'a Bump,
+    complete: bool,
+}
+
+impl Drop for Portal<'_> {
+    fn drop(&mut self) {
+        if let Some(client) = self.client.upgrade() {
+            let buf = client.with_buf(|buf| {
+                frontend::close(b'P', &self.name, buf).unwrap();
+                frontend::sync(buf);
+                buf.split().freeze()
+            });
+            let _ = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+        }
+    }
+}
+
+impl<'a> Portal<'a> {
+    /// Returns the name of the portal.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the statement bound to the portal.
+    pub fn statement(&self) -> &Statement<'a> {
+        &self.statement
+    }
+
+    /// Determines if all of the portal's rows have been fetched.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Fetches up to `max_rows` rows from the portal, allocating them in the portal's arena.
+    ///
+    /// If the requested number is negative or 0, all remaining rows are returned. Fewer rows than requested are
+    /// returned once the portal is exhausted, after which `is_complete` returns `true` and further calls return no
+    /// rows.
+    pub async fn fetch(
+        &mut self,
+        max_rows: i32,
+    ) -> Result<bumpalo::collections::Vec<'a, Row<'a>>, Error> {
+        let mut responses = self.execute(max_rows)?;
+        let mut rows = bumpalo::collections::Vec::new_in(self.arena);
+        loop {
+            match responses.next().await? {
+                Message::DataRow(body) => {
+                    rows.push(Row::new(self.statement.clone(), body, &[], self.arena)?);
+                }
+                Message::CommandComplete(_) | Message::EmptyQueryResponse => self.complete = true,
+                Message::PortalSuspended => {}
+                Message::ReadyForQuery(_) => return Ok(rows),
+                _ => return Err(Error::unexpected_message()),
+            }
+        }
+    }
+
+    /// Converts the portal into a stream of its rows, which fetches them in batches of `max_rows`.
+    ///
+    /// Each time a batch has been consumed, the stream fetches the next one, until the portal is exhausted. If
+    /// `max_rows` is negative or 0, all rows are fetched in a single batch.
+    pub fn into_stream(self, max_rows: i32) -> PortalStream<'a> {
+        PortalStream {
+            portal: self,
+            max_rows,
+            responses: None,
+            rows: 0,
+            rows_affected: None,
+            done: false,
+            _p: PhantomPinned,
+        }
+    }
+
+    fn execute(&self, max_rows: i32) -> Result<Responses, Error> {
+        let client = self.client.upgrade().ok_or_else(Error::closed)?;
+        let buf = client.with_buf(|buf| {
+            frontend::execute(&self.name, max_rows, buf).map_err(Error::encode)?;
+            frontend::sync(buf);
+            Ok(buf.split().freeze())
+        })?;
+        client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+    }
+}
+
+pin_project! {
+    /// A stream of the rows of a portal, fetched in batches.
+    pub struct PortalStream<'a> {
+        portal: Portal<'a>,
+        max_rows: i32,
+        responses: Option<Responses>,
+        rows: u64,
+        rows_affected: Option<u64>,
+        done: bool,
+        #[pin]
+        _p: PhantomPinned,
+    }
+}
+
+impl<'a> Stream for PortalStream<'a> {
+    type Item = Result<Row<'a>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        loop {
+            if *this.done {
+                return Poll::Ready(None);
+            }
+
+            let responses = match this.responses {
+                Some(responses) => responses,
+                None => match this.portal.execute(*this.max_rows) {
+                    Ok(responses) => this.responses.insert(responses),
+                    Err(e) => {
+                        *this.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                },
+            };
+
+            let message = match ready!(responses.poll_next(cx)) {
+                Ok(message) => message,
+                Err(e) => {
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            };
+
+            match message {
+                Message::DataRow(body) => {
+                    *this.rows += 1;
+                    let row =
+                        Row::new(this.portal.statement.clone(), body, &[], this.portal.arena)?;
+                    return Poll::Ready(Some(Ok(row)));
+                }
+                Message::CommandComplete(_) | Message::EmptyQueryResponse => {
+                    this.portal.complete = true;
+                    *this.rows_affected = Some(*this.rows);
+                }
+                Message::PortalSuspended => {}
+                Message::ReadyForQuery(_) => {
+                    *this.responses = None;
+                    *this.done = this.portal.complete;
+                }
+                _ => {
+                    *this.done = true;
+                    return Poll::Ready(Some(Err(Error::unexpected_message())));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> PortalStream<'a> {
+    /// Returns the number of rows returned by the portal.
+    ///
+    /// This function will return `None` until the stream has been exhausted.
+    pub fn rows_affected(&self) -> Option<u64> {
+        self.rows_affected
+    }
+
+    /// Returns the portal the stream fetches rows from.
+    pub fn portal(&self) -> &Portal<'a> {
+        &self.portal
+    }
+}
@@ -1,8 +1,8 @@
-use crate::arena::statement::{Column, Statement};
+use crate::arena::statement::{ServerColumn, ServerStatement, Statement};
 use crate::client::InnerClient;
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
-use crate::prepare::{get_type, NEXT_ID};
+use crate::prepare::{get_type, next_statement_name, PrepareGuard};
 use crate::types::Type;
 use crate::Error;
 use bumpalo::Bump;
@@ -11,8 +11,6 @@ use fallible_iterator::FallibleIterator;
 use log::debug;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
-use std::fmt::Write;
-use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 pub async fn prepare_in<'a>(
@@ -21,14 +19,14 @@ pub async fn prepare_in<'a>(
     types: &[Type],
     arena: &'a Bump,
 ) -> Result<Statement<'a>, Error> {
-    let mut name = bumpalo::collections::string::String::new_in(arena);
-    match std::write!(name, "s{}", NEXT_ID.fetch_add(1, Ordering::SeqCst)) {
-        Ok(_) => {}
-        Err(err) => return Err(Error::config(Box::new(err))),
+    if let Some(server) = client.arena_statement(query, types) {
+        return Ok(Statement::new(server, query, arena));
     }
 
+    let name = next_statement_name(client);
     let buf = encode(client, &name, query, types)?;
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let guard = PrepareGuard::new(client, &name);
 
     match responses.next().await? {
         Message::ParseComplete => {}
@@ -46,29 +44,34 @@ pub async fn prepare_in<'a>(
         _ => return Err(Error::unexpected_message()),
     };
 
-    let mut parameters = bumpalo::collections::Vec::new_in(arena);
+    let mut parameters = vec![];
     let mut it = parameter_description.parameters();
     while let Some(oid) = it.next().map_err(Error::parse)? {
         let type_ = get_type(client, oid).await?;
         parameters.push(type_);
     }
 
-    let mut columns = bumpalo::collections::Vec::new_in(arena);
+    let mut columns = vec![];
     if let Some(row_description) = row_description {
         let mut it = row_description.fields();
         while let Some(field) = it.next().map_err(Error::parse)? {
             let type_ = get_type(client, field.type_oid()).await?;
-            let column = Column {
-                name: bumpalo::collections::String::from_str_in(field.name(), arena),
+            let column = ServerColumn {
+                name: field.name().to_string(),
                 table_oid: Some(field.table_oid()).filter(|n| *n != 0),
                 column_id: Some(field.column_id()).filter(|n| *n != 0),
                 r#type: type_,
+                type_modifier: field.type_modifier(),
+                format: field.format(),
             };
             columns.push(column);
         }
     }
 
-    Ok(Statement::new(client, name, parameters, columns))
+    guard.disarm();
+    let server = Arc::new(ServerStatement::new(client, name, parameters, columns));
+    client.cache_arena_statement(query, types, &server);
+    Ok(Statement::new(server, query, arena))
 }
 
 fn encode(client: &InnerClient, name: &str, query: &str, types: &[Type]) -> Result<Bytes, Error> {
@@ -4,16 +4,16 @@ use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::prepare::get_type;
-use crate::types::{BorrowToSql, IsNull};
+use crate::types::{BorrowToSql, Format, IsNull};
 use crate::Error;
 use bumpalo::collections::CollectIn;
 use bumpalo::Bump;
 use bytes::{Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
-use futures_util::{ready, Stream};
+use futures_util::{future, pin_mut, ready, Stream, TryStreamExt};
 use log::{debug, log_enabled, Level};
 use pin_project_lite::pin_project;
-use postgres_protocol::message::backend::{CommandCompleteBody, Message};
+use postgres_protocol::message::backend::{CommandCompleteBody, DataRowBody, Message};
 use postgres_protocol::message::frontend;
 use postgres_types::Type;
 use std::fmt;
@@ -46,6 +46,22 @@ where
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
+    query_with_formats_in(client, statement, params, &[Format::Binary], arena).await
+}
+
+pub async fn query_with_formats_in<'a, P, I>(
+    client: &InnerClient,
+    statement: Statement<'a>,
+    params: I,
+    result_formats: &[Format],
+    arena: &'a Bump,
+) -> Result<RowStream<'a>, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let formats = column_formats(&statement, result_formats, arena)?;
     let buf = if log_enabled!(Level::Debug) {
         let params = params.into_iter().collect::<Vec<_>>();
         debug!(
@@ -53,20 +69,40 @@ where
             statement.name(),
             BorrowToSqlParamsDebug(params.as_slice()),
         );
-        encode(client, &statement, params)?
+        encode_with_formats(client, &statement, params, result_formats)?
     } else {
-        encode(client, &statement, params)?
+        encode_with_formats(client, &statement, params, result_formats)?
     };
     let responses = start(client, buf).await?;
     Ok(RowStream {
         statement,
         responses,
+        formats,
         rows_affected: None,
+        command_tag: None,
         arena,
         _p: PhantomPinned,
     })
 }
 
+/// Resolves the formats requested for the results of `statement` to the format of each of its columns.
+///
+/// As in the `Bind` message, a single format applies to every column. An empty slice is returned if every column is
+/// in the binary format.
+fn column_formats<'a>(
+    statement: &Statement<'_>,
+    result_formats: &[Format],
+    arena: &'a Bump,
+) -> Result<&'a [Format], Error> {
+    let columns = statement.columns().len();
+    match *result_formats {
+        [Format::Binary] => Ok(&[]),
+        [Format::Text] => Ok(arena.alloc_slice_fill_copy(columns, Format::Text)),
+        _ if result_formats.len() == columns => Ok(arena.alloc_slice_copy(result_formats)),
+        _ => Err(Error::column_count(result_formats.len(), columns)),
+    }
+}
+
 pub async fn query_typed_in<'a, P, I>(
     client: &Arc<InnerClient>,
     query: &str,
@@ -83,7 +119,7 @@ where
 
         client.with_buf(|buf| {
             frontend::parse("", query, param_oids.into_iter(), buf).map_err(Error::parse)?;
-            encode_bind_raw("", params, "", buf)?;
+            encode_bind_raw("", params, "", &[Format::Binary], buf)?;
             frontend::describe(b'S', "", buf).map_err(Error::encode)?;
             frontend::execute("", 0, buf).map_err(Error::encode)?;
             frontend::sync(buf);
@@ -100,36 +136,43 @@ where
             Message::NoData => {
                 return Ok(RowStream {
                     statement: Statement::unnamed_in(
+                        bumpalo::collections::String::from_str_in(query, arena),
                         bumpalo::collections::Vec::new_in(arena),
-                        bumpalo::collections::Vec::new_in(arena),
+                        vec![],
                     ),
                     responses,
+                    formats: &[],
                     rows_affected: None,
+                    command_tag: None,
                     arena,
                     _p: PhantomPinned,
                 });
             }
             Message::RowDescription(row_description) => {
-                let mut columns: bumpalo::collections::Vec<'a, Column<'_>> =
-                    bumpalo::collections::Vec::new_in(arena);
+                let mut columns = vec![];
                 let mut it = row_description.fields();
                 while let Some(field) = it.next().map_err(Error::parse)? {
                     let type_ = get_type(client, field.type_oid()).await?;
                     let column = Column {
-                        name: bumpalo::collections::String::from_str_in(field.name(), arena),
+                        name: arena.alloc_str(field.name()),
                         table_oid: Some(field.table_oid()).filter(|n| *n != 0),
                         column_id: Some(field.column_id()).filter(|n| *n != 0),
                         r#type: type_,
+                        type_modifier: field.type_modifier(),
+                        format: field.format(),
                     };
                     columns.push(column);
                 }
                 return Ok(RowStream {
                     statement: Statement::unnamed_in(
+                        bumpalo::collections::String::from_str_in(query, arena),
                         bumpalo::collections::Vec::new_in(arena),
                         columns,
                     ),
                     responses,
+                    formats: &[],
                     rows_affected: None,
+                    command_tag: None,
                     arena,
                     _p: PhantomPinned,
                 });
@@ -208,13 +251,27 @@ pub fn encode<P, I>(
     statement: &Statement<'_>,
     params: I,
 ) -> Result<Bytes, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    encode_with_formats(client, statement, params, &[Format::Binary])
+}
+
+fn encode_with_formats<P, I>(
+    client: &InnerClient,
+    statement: &Statement<'_>,
+    params: I,
+    result_formats: &[Format],
+) -> Result<Bytes, Error>
 where
     P: BorrowToSql,
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
     client.with_buf(|buf| {
-        encode_bind(statement, params, "", buf)?;
+        encode_bind_with_formats(statement, params, "", result_formats, buf)?;
         frontend::execute("", 0, buf).map_err(Error::encode)?;
         frontend::sync(buf);
         Ok(buf.split().freeze())
@@ -227,6 +284,21 @@ pub fn encode_bind<P, I>(
     portal: &str,
     buf: &mut BytesMut,
 ) -> Result<(), Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    encode_bind_with_formats(statement, params, portal, &[Format::Binary], buf)
+}
+
+fn encode_bind_with_formats<P, I>(
+    statement: &Statement<'_>,
+    params: I,
+    portal: &str,
+    result_formats: &[Format],
+    buf: &mut BytesMut,
+) -> Result<(), Error>
 where
     P: BorrowToSql,
     I: IntoIterator<Item = P>,
@@ -241,6 +313,7 @@ where
         statement.name(),
         params.zip(statement.params().iter().cloned()),
         portal,
+        result_formats,
         buf,
     )
 }
@@ -249,6 +322,7 @@ fn encode_bind_raw<P, I>(
     statement_name: &str,
     params: I,
     portal: &str,
+    result_formats: &[Format],
     buf: &mut BytesMut,
 ) -> Result<(), Error>
 where
@@ -275,7 +349,7 @@ where
                 Err(e)
             }
         },
-        Some(1),
+        result_formats.iter().map(|format| *format as i16),
         buf,
     );
     match r {
@@ -290,7 +364,9 @@ pin_project! {
     pub struct RowStream<'a> {
         statement: Statement<'a>,
         responses: Responses,
+        formats: &'a [Format],
         rows_affected: Option<u64>,
+        command_tag: Option<&'a str>,
         arena: &'a Bump,
         #[pin]
         _p: PhantomPinned,
@@ -301,32 +377,87 @@ impl<'a> Stream for RowStream<'a> {
     type Item = Result<Row<'a>, Error>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let arena = self.arena;
+        let statement = self.statement.clone();
+        let formats = self.formats;
+        match ready!(self.poll_body(cx)?) {
+            Some(body) => Poll::Ready(Some(Ok(Row::new(statement, body, formats, arena)?))),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl<'a> RowStream<'a> {
+    fn poll_body(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<DataRowBody>, Error>> {
         let this = self.project();
         loop {
             match ready!(this.responses.poll_next(cx)?) {
-                Message::DataRow(body) => {
-                    return Poll::Ready(Some(Ok(Row::new(
-                        this.statement.clone(),
-                        body,
-                        this.arena,
-                    )?)))
-                }
+                Message::DataRow(body) => return Poll::Ready(Ok(Some(body))),
                 Message::CommandComplete(body) => {
                     *this.rows_affected = Some(extract_row_affected(&body)?);
+                    *this.command_tag =
+                        Some(this.arena.alloc_str(body.tag().map_err(Error::parse)?));
                 }
                 Message::EmptyQueryResponse | Message::PortalSuspended => {}
-                Message::ReadyForQuery(_) => return Poll::Ready(None),
-                _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                Message::ReadyForQuery(_) => return Poll::Ready(Ok(None)),
+                _ => return Poll::Ready(Err(Error::unexpected_message())),
             }
         }
     }
-}
 
-impl RowStream<'_> {
     /// Returns the number of rows affected by the query.
     ///
     /// This function will return `None` until the stream has been exhausted.
     pub fn rows_affected(&self) -> Option<u64> {
         self.rows_affected
     }
+
+    /// Returns the command tag of the query, such as `INSERT 0 5` or `MERGE 3`.
+    ///
+    /// The tag is allocated in the stream's arena. This function will return `None` until the stream has been
+    /// exhausted.
+    pub fn command_tag(&self) -> Option<&'a str> {
+        self.command_tag
+    }
+
+    /// Consumes the stream, collecting its rows into a vector allocated in the stream's arena.
+    ///
+    /// Returns the first error encountered, if any.
+    pub async fn collect_in(self) -> Result<bumpalo::collections::Vec<'a, Row<'a>>, Error> {
+        let mut rows = bumpalo::collections::Vec::new_in(self.arena);
+        let stream = self;
+        pin_mut!(stream);
+        while let Some(row) = stream.try_next().await? {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Drives the stream to completion, passing each row to `f`.
+    ///
+    /// Unlike rows yielded by the stream, the positions of each row's values are allocated in `scratch` rather than in
+    /// the stream's arena, and `scratch` is reset once `f` returns. Memory use therefore stays flat however many rows
+    /// the query returns. Each row is only borrowed for the duration of its call, so `f` cannot retain it or any value
+    /// borrowed from it; use `Row::to_owned` to keep a row beyond the call.
+    ///
+    /// Stops at the first error returned by the query or by `f`. Once the stream has been exhausted, `rows_affected`
+    /// and `command_tag` return its outcome as usual.
+    pub async fn for_each_in<F>(
+        mut self: Pin<&mut Self>,
+        scratch: &mut Bump,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(Row<'_>) -> Result<(), Error>,
+    {
+        while let Some(body) = future::poll_fn(|cx| self.as_mut().poll_body(cx)).await? {
+            let row = Row::new(self.statement.clone(), body, self.formats, scratch)?;
+            f(row)?;
+            scratch.reset();
+        }
+        Ok(())
+    }
 }
@@ -1,9 +1,9 @@
 //! Rows.
 
 use crate::arena::row::sealed::{AsName, Sealed};
+use crate::arena::simple_query::SimpleColumn;
 use crate::arena::statement::{Column, Statement};
-use crate::simple_query::SimpleColumn;
-use crate::types::{FromSql, WrongType};
+use crate::types::{Format, FromSql, Type, WrongType};
 use crate::Error;
 use bumpalo::Bump;
 use fallible_iterator::FallibleIterator;
@@ -99,6 +99,7 @@ where
 pub struct Row<'a> {
     statement: Statement<'a>,
     body: DataRowBody,
+    formats: &'a [Format],
     ranges: bumpalo::collections::Vec<'a, Option<Range<usize>>>,
 }
 
@@ -114,12 +115,13 @@ impl<'a> Row<'a> {
     pub(crate) fn new(
         statement: Statement<'a>,
         body: DataRowBody,
+        formats: &'a [Format],
         arena: &'a Bump,
     ) -> Result<Row<'a>, Error> {
         let ranges = body
             .ranges()
             .try_fold(
-                bumpalo::collections::Vec::new_in(arena),
+                bumpalo::collections::Vec::with_capacity_in(statement.columns().len(), arena),
                 |mut vec, range| {
                     vec.push(range);
                     Ok(vec)
@@ -130,12 +132,16 @@ impl<'a> Row<'a> {
         Ok(Row {
             statement,
             body,
+            formats,
             ranges,
         })
     }
 
     /// Returns information about the columns of data in the row.
-    pub fn columns(&self) -> &[Column<'_>] {
+    ///
+    /// Like `Statement::columns`, the columns are borrowed from the row's statement, and only their names can be
+    /// borrowed for as long as the arena is.
+    pub fn columns(&self) -> &[Column<'a>] {
         self.statement.columns()
     }
 
@@ -149,6 +155,52 @@ impl<'a> Row<'a> {
         self.columns().len()
     }
 
+    /// Copies the row out of its arena into a standard `Row`.
+    ///
+    /// The column information and the row's data are copied onto the heap, so the result can be kept after the arena
+    /// has been reset or dropped. This is useful when most rows of a query are processed and discarded within the
+    /// arena, but a few need to be retained. The copy does not share the buffer of the batch the row arrived in.
+    ///
+    /// The standard `Row` decodes every value as binary, so values requested in the text format should be read
+    /// before the row is converted.
+    pub fn to_owned(&self) -> crate::Row {
+        let statement = self.owned_statement();
+        crate::Row::from_parts(statement, self.body.detach(), &self.ranges)
+    }
+
+    /// Converts the row into a standard `Row` which does not borrow from its arena.
+    ///
+    /// Like `to_owned`, this allocates to copy the column information onto the heap, so the result can be kept after
+    /// the arena has been reset or dropped, for example to send it to another task. The row's data is moved rather
+    /// than copied, so like other rows the result shares the buffer of the batch the row arrived in.
+    pub fn into_owned(self) -> crate::Row {
+        let statement = self.owned_statement();
+        crate::Row::from_parts(statement, self.body, &self.ranges)
+    }
+
+    fn owned_statement(&self) -> crate::Statement {
+        let columns = self
+            .columns()
+            .iter()
+            .map(|column| crate::Column {
+                name: column.name().to_string(),
+                table_oid: column.table_oid(),
+                column_id: column.column_id(),
+                r#type: column.type_().clone(),
+            })
+            .collect();
+        crate::Statement::unnamed(self.statement.params().to_vec(), columns)
+    }
+
+    /// Returns the index of the column with the given name, if there is one.
+    ///
+    /// Names are matched in the same way as by `get`, preferring an exact match and falling back to an ASCII
+    /// case-insensitive one. When reading the same column from many rows, the index can be resolved once and passed
+    /// to `get` in place of the name to avoid repeating the lookup for every row.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        name.__idx(self.columns())
+    }
+
     /// Deserializes a value from the row.
     ///
     /// The value can be specified either by its numeric index in the row, or by its column name.
@@ -177,6 +229,85 @@ impl<'a> Row<'a> {
         self.get_inner(&idx)
     }
 
+    /// Like `Row::try_get`, but returns `None` rather than an error if the row has no such column.
+    ///
+    /// This is useful for queries whose columns vary, where a missing column is expected rather than an error. Errors
+    /// converting the value of a column which does exist are still returned.
+    pub fn get_opt<'b, I, T>(&'b self, idx: I) -> Option<Result<T, Error>>
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSql<'b>,
+    {
+        let idx = idx.__idx(self.columns())?;
+        Some(self.get_inner(&idx))
+    }
+
+    /// Returns the raw bytes of a value in the row, without decoding them.
+    ///
+    /// The value can be specified either by its numeric index in the row, or by its column name. The bytes are
+    /// borrowed from the buffer the row was received in and are in the format the server sent them, which is binary
+    /// unless the text format was requested with `Client::query_raw_formats_in`. Returns `None` if the value is
+    /// `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[track_caller]
+    pub fn get_bytes<I>(&self, idx: I) -> Option<&[u8]>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        match self.try_get_bytes(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `Row::get_bytes`, but returns a `Result` rather than panicking.
+    pub fn try_get_bytes<I>(&self, idx: I) -> Result<Option<&[u8]>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        match idx.__idx(self.columns()) {
+            Some(idx) => Ok(self.col_buffer(idx)),
+            None => Err(Error::column(idx.to_string())),
+        }
+    }
+
+    /// Returns the type and raw bytes of a value in the row, without decoding it.
+    ///
+    /// The value can be specified either by its numeric index in the row, or by its column name. Unlike `try_get`, the
+    /// type of the column is not checked, so this can be used to choose how to decode a value based on its type.
+    /// Returns `None` if the value is `NULL`.
+    pub fn try_get_raw<I>(&self, idx: I) -> Result<Option<RawField<'_>>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        let idx = match idx.__idx(self.columns()) {
+            Some(idx) => idx,
+            None => return Err(Error::column(idx.to_string())),
+        };
+
+        let type_ = self.columns()[idx].type_();
+        let format = self.format(idx);
+        Ok(self.col_buffer(idx).map(|bytes| RawField {
+            type_,
+            format,
+            bytes,
+        }))
+    }
+
+    /// Decodes a `JSON` or `JSONB` column into a `serde_json::Value`.
+    ///
+    /// Returns `Ok(None)` if the value is `NULL`.
+    #[cfg(feature = "with-serde_json-1")]
+    pub fn json_value<I>(&self, idx: I) -> Result<Option<serde_json_1::Value>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        self.get_inner(&idx)
+    }
+
     fn get_inner<'b, I, T>(&'b self, idx: &I) -> Result<T, Error>
     where
         I: RowIndex + fmt::Display,
@@ -187,15 +318,28 @@ impl<'a> Row<'a> {
             None => return Err(Error::column(idx.to_string())),
         };
 
-        let ty = self.columns()[idx].type_();
-        if !T::accepts(ty) {
+        let column = &self.columns()[idx];
+        let ty = column.type_();
+        // Values in the text format are decoded as text, whatever the type of their column, so that is the type a
+        // mismatch is reported against.
+        let decode_ty = match self.format(idx) {
+            Format::Binary => ty,
+            Format::Text => &Type::TEXT,
+        };
+        if !T::accepts(decode_ty) {
             return Err(Error::from_sql(
-                Box::new(WrongType::new::<T>(ty.clone())),
+                Box::new(WrongType::new::<T>(decode_ty.clone()).with_column(column.name())),
                 idx,
             ));
         }
 
-        FromSql::from_sql_nullable(ty, self.col_buffer(idx)).map_err(|e| Error::from_sql(e, idx))
+        FromSql::from_sql_nullable(decode_ty, self.col_buffer(idx))
+            .map_err(|e| Error::from_sql(e, idx))
+    }
+
+    /// Returns the format the value at the given index was sent in.
+    fn format(&self, idx: usize) -> Format {
+        self.formats.get(idx).copied().unwrap_or(Format::Binary)
     }
 
     /// Get the raw bytes for the column at the given index.
@@ -205,14 +349,144 @@ impl<'a> Row<'a> {
     }
 
     /// Clean the body bytes
+    ///
+    /// The row's data is a view into the buffer of the batch of messages it arrived in, which is freed once no row
+    /// refers to it, so dropping the row releases the same memory without any risk.
+    ///
+    /// # Safety
+    ///
+    /// The row must not be read after this is called.
+    #[deprecated(since = "0.7.14", note = "drop the row instead")]
     pub unsafe fn clean_body(&mut self) {
         self.body.clean();
         self.ranges.clear();
     }
 }
 
-impl AsName for SimpleColumn {
+/// The undecoded value of a column of a row, along with its type.
+#[derive(Debug, Copy, Clone)]
+pub struct RawField<'a> {
+    type_: &'a Type,
+    format: Format,
+    bytes: &'a [u8],
+}
+
+impl<'a> RawField<'a> {
+    /// Returns the type of the value.
+    pub fn type_(&self) -> &'a Type {
+        self.type_
+    }
+
+    /// Returns the format the value was sent in.
+    ///
+    /// This is the binary format unless the text format was requested with `Client::query_raw_formats_in`.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the raw bytes of the value, as they were sent by the server.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl AsName for crate::simple_query::SimpleColumn {
+    fn as_name(&self) -> &str {
+        self.name()
+    }
+}
+
+impl AsName for SimpleColumn<'_> {
     fn as_name(&self) -> &str {
         self.name()
     }
 }
+
+/// A row of data returned from the database by a simple query.
+///
+/// The values of the row are copied into the arena as it is received, so they can be borrowed for as long as the
+/// arena is rather than only for as long as the row.
+#[derive(Debug)]
+pub struct SimpleQueryRow<'a> {
+    columns: &'a [SimpleColumn<'a>],
+    values: &'a [Option<&'a str>],
+}
+
+impl<'a> SimpleQueryRow<'a> {
+    pub(crate) fn new(
+        columns: &'a [SimpleColumn<'a>],
+        body: DataRowBody,
+        arena: &'a Bump,
+    ) -> Result<SimpleQueryRow<'a>, Error> {
+        let mut values = bumpalo::collections::Vec::new_in(arena);
+        let mut ranges = body.ranges();
+        while let Some(range) = ranges.next().map_err(Error::parse)? {
+            let value = match range {
+                Some(range) => {
+                    let value = str::from_utf8(&body.buffer()[range])
+                        .map_err(|e| Error::from_sql(Box::new(e), values.len()))?;
+                    Some(&*arena.alloc_str(value))
+                }
+                None => None,
+            };
+            values.push(value);
+        }
+
+        Ok(SimpleQueryRow {
+            columns,
+            values: values.into_bump_slice(),
+        })
+    }
+
+    /// Returns information about the columns of data in the row.
+    pub fn columns(&self) -> &'a [SimpleColumn<'a>] {
+        self.columns
+    }
+
+    /// Determines if the row contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of values in the row.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns the index of the column with the given name, if there is one.
+    ///
+    /// Names are matched in the same way as by `get`, preferring an exact match and falling back to an ASCII
+    /// case-insensitive one.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        name.__idx(self.columns)
+    }
+
+    /// Returns a value from the row.
+    ///
+    /// The value can be specified either by its numeric index in the row, or by its column name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    #[track_caller]
+    pub fn get<I>(&self, idx: I) -> Option<&'a str>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        match self.try_get(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `SimpleQueryRow::get`, but returns a `Result` rather than panicking.
+    pub fn try_get<I>(&self, idx: I) -> Result<Option<&'a str>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        match idx.__idx(self.columns) {
+            Some(idx) => Ok(self.values[idx]),
+            None => Err(Error::column(idx.to_string())),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Simple query
+
+use crate::arena::row::SimpleQueryRow;
+use crate::client::{InnerClient, Responses};
+use crate::codec::FrontendMessage;
+use crate::connection::RequestMessages;
+use crate::query::extract_row_affected;
+use crate::simple_query::encode;
+use crate::Error;
+use bumpalo::Bump;
+use fallible_iterator::FallibleIterator;
+use futures_util::{ready, Stream};
+use log::debug;
+use pin_project_lite::pin_project;
+use postgres_protocol::message::backend::Message;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Information about a column of a single simple query row.
+#[derive(Debug)]
+pub struct SimpleColumn<'a> {
+    name: &'a str,
+}
+
+impl<'a> SimpleColumn<'a> {
+    /// Returns the name of the column.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+}
+
+/// Message returned by the arena `SimpleQueryStream`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SimpleQueryMessage<'a> {
+    /// A row of data.
+    Row(SimpleQueryRow<'a>),
+    /// A statement in the query has completed.
+    ///
+    /// The number of rows modified or selected is returned.
+    CommandComplete(u64),
+    /// Column values of the proceeding row values
+    RowDescription(&'a [SimpleColumn<'a>]),
+}
+
+pub(crate) async fn simple_query_in<'a>(
+    client: &InnerClient,
+    query: &str,
+    arena: &'a Bump,
+) -> Result<SimpleQueryStream<'a>, Error> {
+    debug!("executing simple query: {}", query);
+
+    let buf = encode(client, query)?;
+    let responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+
+    Ok(SimpleQueryStream {
+        responses,
+        columns: None,
+        arena,
+        _p: PhantomPinned,
+    })
+}
+
+pin_project! {
+    /// A stream of simple query results whose rows are allocated in an arena.
+    pub struct SimpleQueryStream<'a> {
+        responses: Responses,
+        columns: Option<&'a [SimpleColumn<'a>]>,
+        arena: &'a Bump,
+        #[pin]
+        _p: PhantomPinned,
+    }
+}
+
+impl<'a> Stream for SimpleQueryStream<'a> {
+    type Item = Result<SimpleQueryMessage<'a>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        match ready!(this.responses.poll_next(cx)?) {
+            Message::CommandComplete(body) => {
+                let rows = extract_row_affected(&body)?;
+                Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(rows))))
+            }
+            Message::EmptyQueryResponse => {
+                Poll::Ready(Some(Ok(SimpleQueryMessage::CommandComplete(0))))
+            }
+            Message::RowDescription(body) => {
+                let arena = *this.arena;
+                let mut columns = bumpalo::collections::Vec::new_in(arena);
+                let mut fields = body.fields();
+                while let Some(field) = fields.next().map_err(Error::parse)? {
+                    columns.push(SimpleColumn {
+                        name: arena.alloc_str(field.name()),
+                    });
+                }
+                let columns = columns.into_bump_slice();
+
+                *this.columns = Some(columns);
+                Poll::Ready(Some(Ok(SimpleQueryMessage::RowDescription(columns))))
+            }
+            Message::DataRow(body) => {
+                let row = match this.columns {
+                    Some(columns) => SimpleQueryRow::new(columns, body, this.arena)?,
+                    None => return Poll::Ready(Some(Err(Error::unexpected_message()))),
+                };
+                Poll::Ready(Some(Ok(SimpleQueryMessage::Row(row))))
+            }
+            Message::ReadyForQuery(_) => Poll::Ready(None),
+            _ => Poll::Ready(Some(Err(Error::unexpected_message()))),
+        }
+    }
+}
@@ -1,23 +1,53 @@
+use crate::arena::prepare::prepare_in;
+use crate::arena::row::RowIndex;
 use crate::client::InnerClient;
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
-use crate::types::Type;
+use crate::types::{Oid, Type};
+use crate::{Client, Error};
+use bumpalo::Bump;
 use postgres_protocol::message::frontend;
+use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 
-struct StatementInner<'a> {
+/// A statement prepared on the server, shared by all arena statements for the same query and parameter types.
+///
+/// The statement is closed once the last arena statement using it has been dropped.
+pub(crate) struct ServerStatement {
     client: Weak<InnerClient>,
-    name: bumpalo::collections::String<'a>,
-    params: bumpalo::collections::Vec<'a, Type>,
-    columns: bumpalo::collections::Vec<'a, Column<'a>>,
+    name: String,
+    params: Vec<Type>,
+    columns: Vec<ServerColumn>,
 }
 
-impl Drop for StatementInner<'_> {
-    fn drop(&mut self) {
-        if self.name.is_empty() {
-            // Unnamed statements don't need to be closed
-            return;
+/// A column of a `ServerStatement`, which is copied into the arena of each statement sharing it.
+pub(crate) struct ServerColumn {
+    pub(crate) name: String,
+    pub(crate) table_oid: Option<u32>,
+    pub(crate) column_id: Option<i16>,
+    pub(crate) r#type: Type,
+    pub(crate) type_modifier: i32,
+    pub(crate) format: i16,
+}
+
+impl ServerStatement {
+    pub(crate) fn new(
+        inner: &Arc<InnerClient>,
+        name: String,
+        params: Vec<Type>,
+        columns: Vec<ServerColumn>,
+    ) -> ServerStatement {
+        ServerStatement {
+            client: Arc::downgrade(inner),
+            name,
+            params,
+            columns,
         }
+    }
+}
+
+impl Drop for ServerStatement {
+    fn drop(&mut self) {
         if let Some(client) = self.client.upgrade() {
             let buf = client.with_buf(|buf| {
                 frontend::close(b'S', &self.name, buf).unwrap();
@@ -29,6 +59,50 @@ impl Drop for StatementInner<'_> {
     }
 }
 
+/// A cache of the statements prepared by `Client::prepare_in`, keyed by their query text and parameter types.
+///
+/// Statements are held weakly, so they are still closed as soon as no arena statement uses them.
+#[derive(Default)]
+pub(crate) struct StatementCache {
+    statements: HashMap<String, Vec<CachedStatement>>,
+}
+
+/// The parameter types a statement was prepared with, and the statement.
+type CachedStatement = (Vec<Type>, Weak<ServerStatement>);
+
+impl StatementCache {
+    pub(crate) fn get(&self, query: &str, types: &[Type]) -> Option<Arc<ServerStatement>> {
+        self.statements
+            .get(query)?
+            .iter()
+            .find(|(t, _)| t == types)
+            .and_then(|(_, statement)| statement.upgrade())
+    }
+
+    pub(crate) fn insert(&mut self, query: &str, types: &[Type], statement: &Arc<ServerStatement>) {
+        // forget statements which have been closed since they were cached
+        self.statements.retain(|_, entries| {
+            entries.retain(|(_, statement)| statement.strong_count() > 0);
+            !entries.is_empty()
+        });
+
+        let entries = self.statements.entry(query.to_string()).or_default();
+        entries.retain(|(t, _)| t != types);
+        entries.push((types.to_vec(), Arc::downgrade(statement)));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.statements.clear();
+    }
+}
+
+struct StatementInner<'a> {
+    server: Option<Arc<ServerStatement>>,
+    query: bumpalo::collections::String<'a>,
+    params: bumpalo::collections::Vec<'a, Type>,
+    columns: Vec<Column<'a>>,
+}
+
 /// A prepared statement.
 ///
 /// Prepared statements can only be used with the connection that created them.
@@ -36,34 +110,57 @@ impl Drop for StatementInner<'_> {
 pub struct Statement<'a>(Arc<StatementInner<'a>>);
 
 impl<'a> Statement<'a> {
-    pub(crate) fn new(
-        inner: &Arc<InnerClient>,
-        name: bumpalo::collections::String<'a>,
-        params: bumpalo::collections::Vec<'a, Type>,
-        columns: bumpalo::collections::Vec<'a, Column<'_>>,
-    ) -> Statement<'a> {
+    pub(crate) fn new(server: Arc<ServerStatement>, query: &str, arena: &'a Bump) -> Statement<'a> {
+        let mut params = bumpalo::collections::Vec::with_capacity_in(server.params.len(), arena);
+        params.extend(server.params.iter().cloned());
+        let columns = server
+            .columns
+            .iter()
+            .map(|column| Column {
+                name: arena.alloc_str(&column.name),
+                table_oid: column.table_oid,
+                column_id: column.column_id,
+                r#type: column.r#type.clone(),
+                type_modifier: column.type_modifier,
+                format: column.format,
+            })
+            .collect();
+
         Statement(Arc::new(StatementInner {
-            client: Arc::downgrade(inner),
-            name,
+            server: Some(server),
+            query: bumpalo::collections::String::from_str_in(query, arena),
             params,
             columns,
         }))
     }
 
     pub(crate) fn unnamed_in(
+        query: bumpalo::collections::String<'a>,
         params: bumpalo::collections::Vec<'a, Type>,
-        columns: bumpalo::collections::Vec<'a, Column<'_>>,
+        columns: Vec<Column<'a>>,
     ) -> Statement<'a> {
         Statement(Arc::new(StatementInner {
-            client: Weak::new(),
-            name: bumpalo::collections::String::new_in(columns.bump()),
+            server: None,
+            query,
             params,
             columns,
         }))
     }
 
-    pub(crate) fn name(&self) -> &str {
-        &self.0.name
+    /// Returns the name of the statement on the server.
+    ///
+    /// Statements for the same query and parameter types which are shared by `Client::prepare_in` have the same name.
+    /// The name is empty for the unnamed statements used by `query_typed_in`.
+    pub fn name(&self) -> &str {
+        // Unnamed statements don't need to be closed, so they have no server statement
+        self.0.server.as_ref().map_or("", |server| &server.name)
+    }
+
+    /// Returns the query text the statement was prepared from.
+    ///
+    /// This is the text sent to the server, so it reflects any rewriting by `Config::query_rewriter`.
+    pub fn query(&self) -> &str {
+        &self.0.query
     }
 
     /// Returns the expected types of the statement's parameters.
@@ -71,16 +168,53 @@ impl<'a> Statement<'a> {
         &self.0.params
     }
 
+    /// Returns the OIDs of the statement's parameter types.
+    ///
+    /// This is equivalent to mapping `params` through `Type::oid`.
+    pub fn parameter_oids(&self) -> Vec<Oid> {
+        self.0.params.iter().map(Type::oid).collect()
+    }
+
     /// Returns information about the columns returned when the statement is queried.
-    pub fn columns(&self) -> &[Column<'_>] {
+    ///
+    /// The columns are borrowed from the statement rather than from the arena. The type of a column can hold a
+    /// reference-counted definition of a custom type, and the arena never runs destructors, so columns allocated in it
+    /// would leak those definitions. Only the names of the columns are allocated in the arena, so `Column::name` can
+    /// be borrowed for as long as the arena is. To keep the columns themselves, keep the statement, which is cheap to
+    /// clone.
+    pub fn columns(&self) -> &[Column<'a>] {
         &self.0.columns
     }
+
+    /// Returns the index of the column with the given name, if there is one.
+    ///
+    /// Names are matched in the same way as by `Row::get`, so the index can be resolved once for the statement and
+    /// then used to read the column from each of its rows.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        name.__idx(self.columns())
+    }
+
+    /// Prepares this statement again, allocating the new statement in a different arena.
+    ///
+    /// A statement cannot outlive the arena it was prepared in. This creates an independent copy which lives as long
+    /// as `arena`, so that a statement prepared in a short-lived arena can be kept for use with a longer-lived one. Like
+    /// `Client::prepare_in`, this reuses the statement already prepared on the server for the same query and parameter
+    /// types if the client has cached it, and otherwise prepares the statement again.
+    pub async fn reprepare_in<'b>(
+        &self,
+        client: &Client,
+        arena: &'b Bump,
+    ) -> Result<Statement<'b>, Error> {
+        // The stored query has already been through the client's query rewriter.
+        prepare_in(client.inner(), self.query(), self.params(), arena).await
+    }
 }
 
 impl std::fmt::Debug for Statement<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         f.debug_struct("Statement")
-            .field("name", &self.0.name)
+            .field("name", &self.name())
+            .field("query", &self.query())
             .field("params", &self.0.params)
             .field("columns", &self.0.columns)
             .finish_non_exhaustive()
@@ -90,16 +224,20 @@ impl std::fmt::Debug for Statement<'_> {
 /// Information about a column of a query.
 #[derive(Debug)]
 pub struct Column<'a> {
-    pub(crate) name: bumpalo::collections::String<'a>,
+    pub(crate) name: &'a str,
     pub(crate) table_oid: Option<u32>,
     pub(crate) column_id: Option<i16>,
     pub(crate) r#type: Type,
+    pub(crate) type_modifier: i32,
+    pub(crate) format: i16,
 }
 
-impl Column<'_> {
+impl<'a> Column<'a> {
     /// Returns the name of the column.
-    pub fn name(&self) -> &str {
-        &self.name
+    ///
+    /// The name is allocated in the arena, so it can be kept after the statement and its rows have been dropped.
+    pub fn name(&self) -> &'a str {
+        self.name
     }
 
     /// Returns the OID of the underlying database table.
@@ -116,4 +254,20 @@ impl Column<'_> {
     pub fn type_(&self) -> &Type {
         &self.r#type
     }
+
+    /// Returns the type modifier of the column, such as the declared length of a `varchar(n)`.
+    ///
+    /// The meaning of the modifier depends on the type, and it is -1 if the type has no modifier.
+    pub fn type_modifier(&self) -> i32 {
+        self.type_modifier
+    }
+
+    /// Returns the format code of the column as reported by the server: 0 for text, or 1 for binary.
+    ///
+    /// The server describes a statement before the format of its results is chosen, and reports 0 for the columns of
+    /// such descriptions. Queries run by this module request every column in binary unless other formats are passed
+    /// to `Client::query_raw_formats_in`, so use `RawField::format` to find out which format a row's value is in.
+    pub fn format(&self) -> i16 {
+        self.format
+    }
 }
@@ -0,0 +1,201 @@
+//! Transaction
+
+use crate::arena::portal::{self, Portal, PortalStream};
+use crate::arena::query::RowStream;
+use crate::arena::row::Row;
+use crate::arena::statement::Statement;
+use crate::arena::to_statement::ToStatement;
+use crate::types::{BorrowToSql, ToSql, Type};
+use crate::{slice_iter, Client, Error};
+use bumpalo::Bump;
+
+/// A representation of a PostgreSQL database transaction whose rows are allocated in an arena.
+///
+/// This wraps a regular `Transaction`, so it begins, commits, and rolls back in the same way, and implicitly rolls
+/// back when dropped unless it has been committed.
+pub struct Transaction<'a> {
+    transaction: crate::Transaction<'a>,
+    arena: &'a Bump,
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn new(transaction: crate::Transaction<'a>, arena: &'a Bump) -> Transaction<'a> {
+        Transaction { transaction, arena }
+    }
+
+    /// Consumes the transaction, committing all changes made within it.
+    pub async fn commit(self) -> Result<(), Error> {
+        self.transaction.commit().await
+    }
+
+    /// Rolls the transaction back, discarding all changes made within it.
+    ///
+    /// This is equivalent to `Transaction`'s `Drop` implementation, but provides any error encountered to the caller.
+    pub async fn rollback(self) -> Result<(), Error> {
+        self.transaction.rollback().await
+    }
+
+    /// Like `Client::prepare_in`.
+    pub async fn prepare_in(&self, query: &str) -> Result<Statement<'a>, Error> {
+        self.client().prepare_in(query, self.arena).await
+    }
+
+    /// Like `Client::prepare_typed_in`.
+    pub async fn prepare_typed_in(
+        &self,
+        query: &str,
+        parameter_types: &[Type],
+    ) -> Result<Statement<'a>, Error> {
+        self.client()
+            .prepare_typed_in(query, parameter_types, self.arena)
+            .await
+    }
+
+    /// Like `Client::query_in`.
+    pub async fn query_in<T>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<bumpalo::collections::Vec<'a, Row<'a>>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client().query_in(statement, params, self.arena).await
+    }
+
+    /// Like `Client::query_one_in`.
+    pub async fn query_one_in<T>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client()
+            .query_one_in(statement, params, self.arena)
+            .await
+    }
+
+    /// Like `Client::query_opt_in`.
+    pub async fn query_opt_in<T>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row<'a>>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client()
+            .query_opt_in(statement, params, self.arena)
+            .await
+    }
+
+    /// Like `Client::query_raw_in`.
+    pub async fn query_raw_in<T, P, I>(
+        &self,
+        statement: &'a T,
+        params: I,
+    ) -> Result<RowStream<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        self.client()
+            .query_raw_in(statement, params, self.arena)
+            .await
+    }
+
+    /// Like `Client::execute_in`.
+    pub async fn execute_in<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client()
+            .execute_in(statement, params, self.arena)
+            .await
+    }
+
+    /// Like `Client::execute_raw_in`.
+    pub async fn execute_raw_in<T, P, I>(&self, statement: &T, params: I) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        self.client()
+            .execute_raw_in(statement, params, self.arena)
+            .await
+    }
+
+    /// Binds a statement to a set of parameters, creating a `Portal` whose rows can be fetched in batches.
+    ///
+    /// Like `tokio_postgres::Transaction::bind`, the portal is given a generated name, and only lasts for the
+    /// duration of the transaction.
+    pub async fn bind_in<T>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Portal<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.bind_raw_in(statement, slice_iter(params)).await
+    }
+
+    /// A maximally flexible version of `bind_in`.
+    pub async fn bind_raw_in<T, P, I>(
+        &self,
+        statement: &'a T,
+        params: I,
+    ) -> Result<Portal<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let statement = statement
+            .__convert()
+            .into_statement_in(self.client(), self.arena)
+            .await?;
+        portal::bind_in(self.client().inner(), statement, params, self.arena).await
+    }
+
+    /// Executes a statement through a portal, returning a stream of the resulting rows.
+    ///
+    /// Rather than returning all rows at once, the server is asked for batches of `max_rows` rows, and the stream
+    /// requests the next batch once the previous one has been consumed. This bounds the number of rows buffered on the
+    /// client for queries returning large result sets. If `max_rows` is negative or 0, all rows are requested at once.
+    ///
+    /// To control when each batch is fetched, use `bind_in` and `Portal::fetch` instead.
+    pub async fn query_portal_in<T>(
+        &self,
+        statement: &'a T,
+        params: &[&(dyn ToSql + Sync)],
+        max_rows: i32,
+    ) -> Result<PortalStream<'a>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let portal = self.bind_in(statement, params).await?;
+        Ok(portal.into_stream(max_rows))
+    }
+
+    /// Returns the arena rows are allocated in.
+    pub fn arena(&self) -> &'a Bump {
+        self.arena
+    }
+
+    /// Returns a reference to the underlying `Client`.
+    pub fn client(&self) -> &Client {
+        self.transaction.client()
+    }
+}
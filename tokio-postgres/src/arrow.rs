@@ -0,0 +1,312 @@
+//! Decoding of query results into Arrow record batches.
+//!
+//! See [`Client::copy_out_arrow`] for details.
+//!
+//! Requires the `with-arrow-57` feature.
+//!
+//! [`Client::copy_out_arrow`]: crate::Client::copy_out_arrow
+
+use crate::binary_copy::{BinaryCopyOutRow, BinaryCopyOutStream};
+use crate::types::{FromSql, Type, WrongType};
+use crate::{Column, Error};
+use arrow_array_57::builder::{
+    BinaryBuilder, BooleanBuilder, Date32Builder, Float32Builder, Float64Builder, Int16Builder,
+    Int32Builder, Int64Builder, StringBuilder, TimestampMicrosecondBuilder,
+};
+use arrow_array_57::{ArrayRef, RecordBatch};
+use arrow_schema_57::{DataType, Field, SchemaRef, TimeUnit};
+use futures_util::{ready, Stream};
+use pin_project_lite::pin_project;
+use postgres_protocol::types;
+use std::error;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+// Postgres counts timestamps and dates from 2000-01-01, and Arrow from 1970-01-01.
+const TIMESTAMP_OFFSET: i64 = 946_684_800_000_000;
+const DATE_OFFSET: i32 = 10_957;
+
+/// Returns the Postgres types whose binary representations are decoded into the fields of `schema`.
+pub(crate) fn postgres_types(schema: &SchemaRef) -> Result<Vec<Type>, Error> {
+    schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let ty = match field.data_type() {
+                DataType::Boolean => Type::BOOL,
+                DataType::Int16 => Type::INT2,
+                DataType::Int32 => Type::INT4,
+                DataType::Int64 => Type::INT8,
+                DataType::Float32 => Type::FLOAT4,
+                DataType::Float64 => Type::FLOAT8,
+                DataType::Utf8 => Type::TEXT,
+                DataType::Binary => Type::BYTEA,
+                DataType::Timestamp(TimeUnit::Microsecond, None) => Type::TIMESTAMP,
+                DataType::Timestamp(TimeUnit::Microsecond, Some(_)) => Type::TIMESTAMPTZ,
+                DataType::Date32 => Type::DATE,
+                data_type => {
+                    return Err(Error::from_sql(
+                        format!("Arrow type {} is not supported", data_type).into(),
+                        idx,
+                    ))
+                }
+            };
+            Ok(ty)
+        })
+        .collect()
+}
+
+/// Checks that the values of each of `columns` can be decoded into the corresponding field of `schema`.
+pub(crate) fn check_columns(schema: &SchemaRef, columns: &[Column]) -> Result<(), Error> {
+    for (idx, (field, column)) in schema.fields().iter().zip(columns).enumerate() {
+        match field.data_type() {
+            DataType::Boolean => check_column::<bool>(column, idx)?,
+            DataType::Int16 => check_column::<i16>(column, idx)?,
+            DataType::Int32 => check_column::<i32>(column, idx)?,
+            DataType::Int64 => check_column::<i64>(column, idx)?,
+            DataType::Float32 => check_column::<f32>(column, idx)?,
+            DataType::Float64 => check_column::<f64>(column, idx)?,
+            DataType::Utf8 => check_column::<&str>(column, idx)?,
+            DataType::Binary => check_column::<&[u8]>(column, idx)?,
+            DataType::Timestamp(_, _) => check_column::<Timestamp>(column, idx)?,
+            DataType::Date32 => check_column::<Date>(column, idx)?,
+            // `postgres_types` has already rejected any other types.
+            _ => unreachable!(),
+        }
+    }
+    Ok(())
+}
+
+fn check_column<'a, T>(column: &Column, idx: usize) -> Result<(), Error>
+where
+    T: FromSql<'a>,
+{
+    if T::accepts(column.type_()) {
+        Ok(())
+    } else {
+        Err(Error::from_sql(
+            Box::new(WrongType::new::<T>(column.type_().clone()).with_column(column.name())),
+            idx,
+        ))
+    }
+}
+
+enum ColumnBuilder {
+    Bool(BooleanBuilder),
+    Int16(Int16Builder),
+    Int32(Int32Builder),
+    Int64(Int64Builder),
+    Float32(Float32Builder),
+    Float64(Float64Builder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+    Timestamp(TimestampMicrosecondBuilder),
+    Date32(Date32Builder),
+}
+
+impl ColumnBuilder {
+    fn new(data_type: &DataType, capacity: usize) -> ColumnBuilder {
+        match data_type {
+            DataType::Boolean => ColumnBuilder::Bool(BooleanBuilder::with_capacity(capacity)),
+            DataType::Int16 => ColumnBuilder::Int16(Int16Builder::with_capacity(capacity)),
+            DataType::Int32 => ColumnBuilder::Int32(Int32Builder::with_capacity(capacity)),
+            DataType::Int64 => ColumnBuilder::Int64(Int64Builder::with_capacity(capacity)),
+            DataType::Float32 => ColumnBuilder::Float32(Float32Builder::with_capacity(capacity)),
+            DataType::Float64 => ColumnBuilder::Float64(Float64Builder::with_capacity(capacity)),
+            DataType::Utf8 => ColumnBuilder::Utf8(StringBuilder::new()),
+            DataType::Binary => ColumnBuilder::Binary(BinaryBuilder::new()),
+            DataType::Timestamp(_, timezone) => ColumnBuilder::Timestamp(
+                TimestampMicrosecondBuilder::with_capacity(capacity)
+                    .with_timezone_opt(timezone.clone()),
+            ),
+            DataType::Date32 => ColumnBuilder::Date32(Date32Builder::with_capacity(capacity)),
+            // `postgres_types` has already rejected any other types.
+            _ => unreachable!(),
+        }
+    }
+
+    fn push(&mut self, row: &BinaryCopyOutRow, idx: usize, field: &Field) -> Result<(), Error> {
+        match self {
+            ColumnBuilder::Bool(b) => b.append_option(get::<bool>(row, idx, field)?),
+            ColumnBuilder::Int16(b) => b.append_option(get::<i16>(row, idx, field)?),
+            ColumnBuilder::Int32(b) => b.append_option(get::<i32>(row, idx, field)?),
+            ColumnBuilder::Int64(b) => b.append_option(get::<i64>(row, idx, field)?),
+            ColumnBuilder::Float32(b) => b.append_option(get::<f32>(row, idx, field)?),
+            ColumnBuilder::Float64(b) => b.append_option(get::<f64>(row, idx, field)?),
+            ColumnBuilder::Utf8(b) => b.append_option(get::<&str>(row, idx, field)?),
+            ColumnBuilder::Binary(b) => b.append_option(get::<&[u8]>(row, idx, field)?),
+            ColumnBuilder::Timestamp(b) => {
+                b.append_option(get::<Timestamp>(row, idx, field)?.map(|v| v.0))
+            }
+            ColumnBuilder::Date32(b) => b.append_option(get::<Date>(row, idx, field)?.map(|v| v.0)),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ColumnBuilder::Bool(b) => Arc::new(b.finish()),
+            ColumnBuilder::Int16(b) => Arc::new(b.finish()),
+            ColumnBuilder::Int32(b) => Arc::new(b.finish()),
+            ColumnBuilder::Int64(b) => Arc::new(b.finish()),
+            ColumnBuilder::Float32(b) => Arc::new(b.finish()),
+            ColumnBuilder::Float64(b) => Arc::new(b.finish()),
+            ColumnBuilder::Utf8(b) => Arc::new(b.finish()),
+            ColumnBuilder::Binary(b) => Arc::new(b.finish()),
+            ColumnBuilder::Timestamp(b) => Arc::new(b.finish()),
+            ColumnBuilder::Date32(b) => Arc::new(b.finish()),
+        }
+    }
+}
+
+fn get<'a, T>(row: &'a BinaryCopyOutRow, idx: usize, field: &Field) -> Result<Option<T>, Error>
+where
+    T: FromSql<'a>,
+{
+    let value = row.try_get::<Option<T>>(idx)?;
+    if value.is_none() && !field.is_nullable() {
+        return Err(Error::from_sql(
+            format!("unexpected NULL in non-nullable field `{}`", field.name()).into(),
+            idx,
+        ));
+    }
+    Ok(value)
+}
+
+/// A timestamp in microseconds since the Unix epoch.
+struct Timestamp(i64);
+
+impl<'a> FromSql<'a> for Timestamp {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Timestamp, Box<dyn error::Error + Sync + Send>> {
+        match types::timestamp_from_sql(raw)? {
+            i64::MAX | i64::MIN => Err("infinite timestamps are not supported".into()),
+            value => value
+                .checked_add(TIMESTAMP_OFFSET)
+                .map(Timestamp)
+                .ok_or_else(|| "value too large to decode".into()),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::TIMESTAMP | Type::TIMESTAMPTZ)
+    }
+}
+
+/// A date in days since the Unix epoch.
+struct Date(i32);
+
+impl<'a> FromSql<'a> for Date {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Date, Box<dyn error::Error + Sync + Send>> {
+        match types::date_from_sql(raw)? {
+            i32::MAX | i32::MIN => Err("infinite dates are not supported".into()),
+            value => value
+                .checked_add(DATE_OFFSET)
+                .map(Date)
+                .ok_or_else(|| "value too large to decode".into()),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::DATE
+    }
+}
+
+pin_project! {
+    /// A stream of Arrow record batches decoded from a binary `COPY ... TO STDOUT`.
+    ///
+    /// Created by `Client::copy_out_arrow`.
+    pub struct RecordBatchStream {
+        #[pin]
+        rows: BinaryCopyOutStream,
+        schema: SchemaRef,
+        builders: Vec<ColumnBuilder>,
+        batch_size: usize,
+        len: usize,
+        done: bool,
+    }
+}
+
+impl RecordBatchStream {
+    pub(crate) fn new(
+        rows: BinaryCopyOutStream,
+        schema: SchemaRef,
+        batch_size: usize,
+    ) -> RecordBatchStream {
+        let builders = schema
+            .fields()
+            .iter()
+            .map(|field| ColumnBuilder::new(field.data_type(), batch_size))
+            .collect();
+        RecordBatchStream {
+            rows,
+            schema,
+            builders,
+            batch_size,
+            len: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the schema of the record batches.
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+}
+
+impl Stream for RecordBatchStream {
+    type Item = Result<RecordBatch, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done {
+            let row = match ready!(this.rows.as_mut().poll_next(cx)) {
+                Some(Ok(row)) => row,
+                Some(Err(e)) => {
+                    *this.done = true;
+                    *this.len = 0;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                None => {
+                    *this.done = true;
+                    break;
+                }
+            };
+
+            for (idx, (builder, field)) in this
+                .builders
+                .iter_mut()
+                .zip(this.schema.fields())
+                .enumerate()
+            {
+                if let Err(e) = builder.push(&row, idx, field) {
+                    *this.done = true;
+                    *this.len = 0;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+            *this.len += 1;
+
+            if *this.len == *this.batch_size {
+                break;
+            }
+        }
+
+        if *this.len == 0 {
+            return Poll::Ready(None);
+        }
+        *this.len = 0;
+
+        let columns = this
+            .builders
+            .iter_mut()
+            .map(ColumnBuilder::finish)
+            .collect();
+        let batch = RecordBatch::try_new(this.schema.clone(), columns)
+            .map_err(|e| Error::parse(io::Error::new(io::ErrorKind::InvalidData, e)));
+        Poll::Ready(Some(batch))
+    }
+}
@@ -0,0 +1,53 @@
+/// The authentication method negotiated with the server when a connection was established.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AuthMethod {
+    /// The server did not request authentication, for example because of a `trust` rule.
+    None,
+    /// A cleartext password.
+    Password,
+    /// An MD5-hashed password.
+    Md5,
+    /// SCRAM-SHA-256 without channel binding.
+    ScramSha256,
+    /// SCRAM-SHA-256 with channel binding (`SCRAM-SHA-256-PLUS`).
+    ScramSha256Plus,
+    /// GSSAPI, typically Kerberos.
+    Gss,
+}
+
+/// Information about how a connection authenticated with the server.
+///
+/// This only records what was negotiated during the handshake, and is intended for auditing that connections use the
+/// expected authentication method.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AuthInfo {
+    pub(crate) method: AuthMethod,
+    pub(crate) scram_iterations: Option<u32>,
+}
+
+impl AuthInfo {
+    pub(crate) fn new(method: AuthMethod) -> AuthInfo {
+        AuthInfo {
+            method,
+            scram_iterations: None,
+        }
+    }
+
+    /// Returns the authentication method that was used.
+    pub fn method(&self) -> AuthMethod {
+        self.method
+    }
+
+    /// Determines if SCRAM channel binding was used.
+    pub fn channel_binding(&self) -> bool {
+        self.method == AuthMethod::ScramSha256Plus
+    }
+
+    /// Returns the PBKDF2 iteration count requested by the server for SCRAM authentication.
+    ///
+    /// This is `None` for other authentication methods.
+    pub fn scram_iterations(&self) -> Option<u32> {
+        self.scram_iterations
+    }
+}
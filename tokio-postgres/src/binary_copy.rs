@@ -26,6 +26,7 @@ pin_project! {
         #[pin]
         sink: CopyInSink<Bytes>,
         types: Vec<Type>,
+        names: Option<Vec<String>>,
         buf: BytesMut,
     }
 }
@@ -33,6 +34,24 @@ pin_project! {
 impl BinaryCopyInWriter {
     /// Creates a new writer which will write rows of the provided types to the provided sink.
     pub fn new(sink: CopyInSink<Bytes>, types: &[Type]) -> BinaryCopyInWriter {
+        BinaryCopyInWriter::new_inner(sink, types.to_vec(), None)
+    }
+
+    /// Creates a new writer which will write rows of the provided columns to the provided sink.
+    ///
+    /// The columns should match the column list of the `COPY` statement, in order. The names are only used to
+    /// identify the offending column when a value cannot be converted to the column's type.
+    pub fn with_columns(sink: CopyInSink<Bytes>, columns: &[(&str, Type)]) -> BinaryCopyInWriter {
+        let types = columns.iter().map(|(_, type_)| type_.clone()).collect();
+        let names = columns.iter().map(|(name, _)| name.to_string()).collect();
+        BinaryCopyInWriter::new_inner(sink, types, Some(names))
+    }
+
+    fn new_inner(
+        sink: CopyInSink<Bytes>,
+        types: Vec<Type>,
+        names: Option<Vec<String>>,
+    ) -> BinaryCopyInWriter {
         let mut buf = BytesMut::new();
         buf.put_slice(MAGIC);
         buf.put_i32(0); // flags
@@ -40,25 +59,21 @@ impl BinaryCopyInWriter {
 
         BinaryCopyInWriter {
             sink,
-            types: types.to_vec(),
+            types,
+            names,
             buf,
         }
     }
 
     /// Writes a single row.
     ///
-    /// # Panics
-    ///
-    /// Panics if the number of values provided does not match the number expected.
+    /// Returns an error if the number of values provided does not match the number of columns, or if a value cannot
+    /// be converted to its column's type. The row is not written in that case, and the writer can continue to be used.
     pub async fn write(self: Pin<&mut Self>, values: &[&(dyn ToSql + Sync)]) -> Result<(), Error> {
         self.write_raw(slice_iter(values)).await
     }
 
     /// A maximally-flexible version of `write`.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of values provided does not match the number expected.
     pub async fn write_raw<P, I>(self: Pin<&mut Self>, values: I) -> Result<(), Error>
     where
         P: BorrowToSql,
@@ -68,28 +83,15 @@ impl BinaryCopyInWriter {
         let mut this = self.project();
 
         let values = values.into_iter();
-        assert!(
-            values.len() == this.types.len(),
-            "expected {} values but got {}",
-            this.types.len(),
-            values.len(),
-        );
-
-        this.buf.put_i16(this.types.len() as i16);
-
-        for (i, (value, type_)) in values.zip(this.types).enumerate() {
-            let idx = this.buf.len();
-            this.buf.put_i32(0);
-            let len = match value
-                .borrow_to_sql()
-                .to_sql_checked(type_, this.buf)
-                .map_err(|e| Error::to_sql(e, i))?
-            {
-                IsNull::Yes => -1,
-                IsNull::No => i32::try_from(this.buf.len() - idx - 4)
-                    .map_err(|e| Error::encode(io::Error::new(io::ErrorKind::InvalidInput, e)))?,
-            };
-            BigEndian::write_i32(&mut this.buf[idx..], len);
+        if values.len() != this.types.len() {
+            return Err(Error::column_count(values.len(), this.types.len()));
+        }
+
+        // Drop any partially encoded row so that it is never sent.
+        let start = this.buf.len();
+        if let Err(e) = encode_row(this.buf, values, this.types, this.names.as_deref()) {
+            this.buf.truncate(start);
+            return Err(e);
         }
 
         if this.buf.len() > 4096 {
@@ -111,6 +113,48 @@ impl BinaryCopyInWriter {
     }
 }
 
+fn encode_row<P, I>(
+    buf: &mut BytesMut,
+    values: I,
+    types: &[Type],
+    names: Option<&[String]>,
+) -> Result<(), Error>
+where
+    P: BorrowToSql,
+    I: Iterator<Item = P>,
+{
+    buf.put_i16(types.len() as i16);
+
+    for (i, (value, type_)) in values.zip(types).enumerate() {
+        let idx = buf.len();
+        buf.put_i32(0);
+        let len = match value
+            .borrow_to_sql()
+            .to_sql_checked(type_, buf)
+            .map_err(|e| Error::to_sql(with_column_name(e, names, i), i))?
+        {
+            IsNull::Yes => -1,
+            IsNull::No => i32::try_from(buf.len() - idx - 4)
+                .map_err(|e| Error::encode(io::Error::new(io::ErrorKind::InvalidInput, e)))?,
+        };
+        BigEndian::write_i32(&mut buf[idx..], len);
+    }
+
+    Ok(())
+}
+
+fn with_column_name(
+    e: Box<dyn std::error::Error + Sync + Send>,
+    names: Option<&[String]>,
+    idx: usize,
+) -> Box<dyn std::error::Error + Sync + Send> {
+    match (e.downcast::<WrongType>(), names) {
+        (Ok(e), Some(names)) => Box::new(e.with_column(&names[idx])),
+        (Ok(e), None) => e,
+        (Err(e), _) => e,
+    }
+}
+
 struct Header {
     has_oids: bool,
 }
@@ -20,7 +20,25 @@ where
     I: IntoIterator<Item = P>,
     I::IntoIter: ExactSizeIterator,
 {
-    let name = format!("p{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    bind_named(client, next_name(), statement, params).await
+}
+
+/// Generates a unique name for a portal created by `bind`.
+pub(crate) fn next_name() -> String {
+    format!("p{}", NEXT_ID.fetch_add(1, Ordering::SeqCst))
+}
+
+pub async fn bind_named<P, I>(
+    client: &Arc<InnerClient>,
+    name: String,
+    statement: Statement,
+    params: I,
+) -> Result<Portal, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
     let buf = client.with_buf(|buf| {
         query::encode_bind(&statement, params, &name, buf)?;
         frontend::sync(buf);
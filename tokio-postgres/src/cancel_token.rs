@@ -1,8 +1,10 @@
 use crate::config::{SslMode, SslNegotiation};
+use crate::connection::RequestStatus;
 use crate::tls::TlsConnect;
 #[cfg(feature = "runtime")]
 use crate::{cancel_query, client::SocketConfig, tls::MakeTlsConnect, Socket};
 use crate::{cancel_query_raw, Error};
+use std::sync::Arc;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 /// The capability to request cancellation of in-progress queries on a
@@ -46,6 +48,27 @@ impl CancelToken {
         .await
     }
 
+    /// Attempts to cancel a specific query on the connection associated with this `CancelToken`.
+    ///
+    /// Postgres can only cancel whatever is currently running on a connection, so when several queries are pipelined
+    /// `cancel_query` may end up cancelling a later query if the one it was meant for has already finished. This
+    /// method does nothing if all responses to the query identified by `handle` have already been received, which
+    /// avoids that in most cases. It is still best-effort: the query may finish on the server before the request
+    /// arrives, and if queries sent before it are still running, one of those will be cancelled instead.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub async fn cancel_query_handle<T>(&self, handle: &QueryHandle, tls: T) -> Result<(), Error>
+    where
+        T: MakeTlsConnect<Socket>,
+    {
+        if handle.is_complete() {
+            return Ok(());
+        }
+
+        self.cancel_query(tls).await
+    }
+
     /// Like `cancel_query`, but uses a stream which is already connected to the server rather than opening a new
     /// connection itself.
     pub async fn cancel_query_raw<S, T>(&self, stream: S, tls: T) -> Result<(), Error>
@@ -65,3 +88,23 @@ impl CancelToken {
         .await
     }
 }
+
+/// Identifies a query sent on a connection.
+///
+/// This can be passed to `CancelToken::cancel_query_handle` to avoid cancelling a different query if this one has
+/// already finished.
+#[derive(Debug, Clone)]
+pub struct QueryHandle {
+    pub(crate) seq: u64,
+    pub(crate) status: Arc<RequestStatus>,
+}
+
+impl QueryHandle {
+    /// Determines if all responses to the query have been received from the server.
+    ///
+    /// Responses are received by the connection in the background, so this may return `true` before they have been
+    /// consumed by the caller.
+    pub fn is_complete(&self) -> bool {
+        self.status.completed_requests() >= self.seq
+    }
+}
@@ -1,46 +1,72 @@
+#[cfg(feature = "with-arrow-57")]
+use crate::arrow::{self, RecordBatchStream};
+use crate::auth_info::AuthInfo;
+use crate::binary_copy::{BinaryCopyOutRow, BinaryCopyOutStream};
 use crate::codec::BackendMessages;
-use crate::config::{SslMode, SslNegotiation};
-use crate::connection::{Request, RequestMessages};
+use crate::config::{Config, QueryRewriter, SslMode, SslNegotiation};
+use crate::connection::{Request, RequestMessages, RequestStatus};
 use crate::copy_out::CopyOutStream;
+use crate::error::DbError;
 #[cfg(feature = "runtime")]
 use crate::keepalive::KeepaliveConfig;
+use crate::lru::LruCache;
+use crate::notifications::{NotificationStream, Subscribers};
 use crate::query::RowStream;
+use crate::row::RowIndex;
 use crate::simple_query::SimpleQueryStream;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
-use crate::types::{Oid, ToSql, Type};
+#[cfg(feature = "tracing")]
+use crate::trace;
+use crate::types::{FromSqlOwned, Oid, ToSql, Type};
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{
-    copy_in, copy_out, prepare, query, simple_query, slice_iter, CancelToken, CopyInSink, Error,
-    Row, SimpleQueryMessage, Statement, ToStatement, Transaction, TransactionBuilder,
+    copy_in, copy_out, explain, prepare, query, simple_query, slice_iter, CancelToken, Column,
+    CopyInSink, DuplicateKeys, Error, ExplainOptions, QueryHandle, ResultSet, Row,
+    SimpleQueryMessage, Statement, ToStatement, Transaction, TransactionBuilder,
 };
+#[cfg(feature = "with-arrow-57")]
+use arrow_schema_57::SchemaRef;
 use bytes::{Buf, BytesMut};
 use fallible_iterator::FallibleIterator;
 use futures_channel::mpsc;
+use futures_util::future::BoxFuture;
 use futures_util::{future, pin_mut, ready, StreamExt, TryStreamExt};
 use parking_lot::Mutex;
 use postgres_protocol::message::backend::Message;
 use postgres_types::BorrowToSql;
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
+use std::hash::Hash;
 #[cfg(feature = "runtime")]
 use std::net::IpAddr;
 #[cfg(feature = "runtime")]
 use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 #[cfg(feature = "runtime")]
 use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "runtime")]
+use tokio::time::{self, Instant};
 
 pub struct Responses {
     receiver: mpsc::Receiver<BackendMessages>,
     cur: BackendMessages,
+    handle: QueryHandle,
 }
 
 impl Responses {
+    pub fn handle(&self) -> &QueryHandle {
+        &self.handle
+    }
+
     pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Result<Message, Error>> {
         loop {
             match self.cur.next().map_err(Error::parse)? {
@@ -63,7 +89,6 @@ impl Responses {
 
 /// A cache of type info and prepared statements for fetching type info
 /// (corresponding to the queries in the [prepare](prepare) module).
-#[derive(Default)]
 struct CachedTypeInfo {
     /// A statement for basic information for a type from its
     /// OID. Corresponds to [TYPEINFO_QUERY](prepare::TYPEINFO_QUERY) (or its
@@ -76,33 +101,107 @@ struct CachedTypeInfo {
     /// Corresponds to [TYPEINFO_QUERY](prepare::TYPEINFO_COMPOSITE_QUERY) (or
     /// its fallback).
     typeinfo_enum: Option<Statement>,
+    /// A statement for getting the element type of a multirange type from its OID.
+    /// Corresponds to [TYPEINFO_MULTIRANGE_QUERY](prepare::TYPEINFO_MULTIRANGE_QUERY).
+    typeinfo_multirange: Option<Statement>,
+    /// A statement for getting the OID of a type from its name.
+    /// Corresponds to [TYPEINFO_NAME_QUERY](prepare::TYPEINFO_NAME_QUERY).
+    typeinfo_name: Option<Statement>,
 
     /// Cache of types already looked up.
-    types: HashMap<Oid, Type>,
+    types: LruCache<Oid, Type>,
 }
 
 pub struct InnerClient {
     sender: mpsc::UnboundedSender<Request>,
+
+    /// The sequence number of the most recently sent request.
+    ///
+    /// This is locked while the request is queued so that sequence numbers match the order the connection sees.
+    last_request: Mutex<u64>,
+    /// Set while a `RowStream` fetches its rows through a portal in a transaction it began itself.
+    ///
+    /// Other requests are refused in the meantime, rather than being run inside of that transaction. This is only
+    /// changed while `last_request` is locked, so that no request can be sent between the check and the change.
+    cursor_transaction: AtomicBool,
+    status: Arc<RequestStatus>,
     cached_typeinfo: Mutex<CachedTypeInfo>,
+    /// The statements prepared by `Client::prepare_cached`, keyed by their query text.
+    statement_cache: Mutex<LruCache<String, Statement>>,
+    #[cfg(feature = "bumpalo")]
+    arena_statement_cache: Mutex<crate::arena::statement::StatementCache>,
+
+    /// A random tag included in the names of this client's prepared statements.
+    ///
+    /// Statement numbers are only unique within a process, so this keeps names from colliding when a pooler
+    /// multiplexes clients from several processes onto one backend.
+    statement_prefix: String,
 
     /// A buffer to use when writing out postgres commands.
     buffer: Mutex<BytesMut>,
+
+    /// The capacity of the channel each request's responses are sent through.
+    response_buffer_capacity: usize,
 }
 
 impl InnerClient {
     pub fn send(&self, messages: RequestMessages) -> Result<Responses, Error> {
-        let (sender, receiver) = mpsc::channel(1);
-        let request = Request { messages, sender };
+        self.send_inner(messages, None, false)
+    }
+
+    /// Like `send`, but for the requests of the `RowStream` holding the cursor transaction.
+    pub fn send_in_cursor_transaction(
+        &self,
+        messages: RequestMessages,
+    ) -> Result<Responses, Error> {
+        self.send_inner(messages, None, true)
+    }
+
+    /// Like `send`, but routes the notices emitted while the request runs to `notices`.
+    pub fn send_with_notices(
+        &self,
+        messages: RequestMessages,
+        notices: mpsc::UnboundedSender<DbError>,
+    ) -> Result<Responses, Error> {
+        self.send_inner(messages, Some(notices), false)
+    }
+
+    fn send_inner(
+        &self,
+        messages: RequestMessages,
+        notices: Option<mpsc::UnboundedSender<DbError>>,
+        in_cursor_transaction: bool,
+    ) -> Result<Responses, Error> {
+        let (sender, receiver) = mpsc::channel(self.response_buffer_capacity);
+        let request = Request {
+            messages,
+            sender,
+            notices,
+        };
+
+        let mut last_request = self.last_request.lock();
+        if !in_cursor_transaction && self.cursor_transaction.load(Ordering::Relaxed) {
+            return Err(Error::cursor_transaction());
+        }
         self.sender
             .unbounded_send(request)
             .map_err(|_| Error::closed())?;
+        *last_request += 1;
 
         Ok(Responses {
             receiver,
             cur: BackendMessages::empty(),
+            handle: QueryHandle {
+                seq: *last_request,
+                status: self.status.clone(),
+            },
         })
     }
 
+    pub fn statement_prefix(&self) -> &str {
+        &self.statement_prefix
+    }
+
     pub fn typeinfo(&self) -> Option<Statement> {
         self.cached_typeinfo.lock().typeinfo.clone()
     }
@@ -127,6 +226,22 @@ impl InnerClient {
         self.cached_typeinfo.lock().typeinfo_enum = Some(statement.clone());
     }
 
+    pub fn typeinfo_multirange(&self) -> Option<Statement> {
+        self.cached_typeinfo.lock().typeinfo_multirange.clone()
+    }
+
+    pub fn set_typeinfo_multirange(&self, statement: &Statement) {
+        self.cached_typeinfo.lock().typeinfo_multirange = Some(statement.clone());
+    }
+
+    pub fn typeinfo_name(&self) -> Option<Statement> {
+        self.cached_typeinfo.lock().typeinfo_name.clone()
+    }
+
+    pub fn set_typeinfo_name(&self, statement: &Statement) {
+        self.cached_typeinfo.lock().typeinfo_name = Some(statement.clone());
+    }
+
     pub fn type_(&self, oid: Oid) -> Option<Type> {
         self.cached_typeinfo.lock().types.get(&oid).cloned()
     }
@@ -135,10 +250,71 @@ impl InnerClient {
         self.cached_typeinfo.lock().types.insert(oid, type_.clone());
     }
 
+    pub fn clear_type(&self, oid: Oid) {
+        self.cached_typeinfo.lock().types.remove(&oid);
+    }
+
     pub fn clear_type_cache(&self) {
         self.cached_typeinfo.lock().types.clear();
     }
 
+    /// Determines if the session was inside of a transaction block when the server last became ready for a query.
+    pub fn in_transaction(&self) -> bool {
+        self.status.in_transaction()
+    }
+
+    /// Starts refusing requests other than those of a `RowStream` which is about to begin a cursor transaction.
+    pub fn begin_cursor_transaction(&self) -> Result<(), Error> {
+        let _last_request = self.last_request.lock();
+        if self.cursor_transaction.swap(true, Ordering::Relaxed) {
+            return Err(Error::cursor_transaction());
+        }
+        Ok(())
+    }
+
+    /// Accepts requests again once the cursor transaction has ended.
+    pub fn end_cursor_transaction(&self) {
+        let _last_request = self.last_request.lock();
+        self.cursor_transaction.store(false, Ordering::Relaxed);
+    }
+
+    pub fn cached_statement(&self, query: &str) -> Option<Statement> {
+        self.statement_cache.lock().get(query).cloned()
+    }
+
+    pub fn cache_statement(&self, query: &str, statement: &Statement) {
+        self.statement_cache
+            .lock()
+            .insert(query.to_string(), statement.clone());
+    }
+
+    #[cfg(feature = "bumpalo")]
+    pub fn arena_statement(
+        &self,
+        query: &str,
+        types: &[Type],
+    ) -> Option<Arc<crate::arena::statement::ServerStatement>> {
+        self.arena_statement_cache.lock().get(query, types)
+    }
+
+    #[cfg(feature = "bumpalo")]
+    pub fn cache_arena_statement(
+        &self,
+        query: &str,
+        types: &[Type],
+        statement: &Arc<crate::arena::statement::ServerStatement>,
+    ) {
+        self.arena_statement_cache
+            .lock()
+            .insert(query, types, statement);
+    }
+
+    pub fn clear_statement_cache(&self) {
+        self.statement_cache.lock().clear();
+        #[cfg(feature = "bumpalo")]
+        self.arena_statement_cache.lock().clear();
+    }
+
     /// Call the given function with a buffer to be used when writing out
     /// postgres commands.
     pub fn with_buf<F, R>(&self, f: F) -> R
@@ -152,6 +328,16 @@ impl InnerClient {
     }
 }
 
+/// The key identifying the backend process, used to cancel its queries.
+///
+/// This is shared with the `Connection` so that it can be updated if the server sends new
+/// `BackendKeyData`.
+#[derive(Copy, Clone)]
+pub(crate) struct BackendKey {
+    pub process_id: i32,
+    pub secret_key: i32,
+}
+
 #[cfg(feature = "runtime")]
 #[derive(Clone)]
 pub(crate) struct SocketConfig {
@@ -181,30 +367,60 @@ pub struct Client {
     socket_config: Option<SocketConfig>,
     ssl_mode: SslMode,
     ssl_negotiation: SslNegotiation,
-    process_id: i32,
-    secret_key: i32,
+    backend_key: Arc<Mutex<BackendKey>>,
+    server_parameters: Arc<Mutex<HashMap<String, String>>>,
+    subscribers: Arc<Mutex<Subscribers>>,
+    auth_info: AuthInfo,
+    query_rewriter: Option<QueryRewriter>,
+    max_result_rows: Option<usize>,
+    fetch_count: Option<i32>,
 }
 
 impl Client {
     pub(crate) fn new(
         sender: mpsc::UnboundedSender<Request>,
-        ssl_mode: SslMode,
-        ssl_negotiation: SslNegotiation,
-        process_id: i32,
-        secret_key: i32,
+        config: &Config,
+        backend_key: Arc<Mutex<BackendKey>>,
+        server_parameters: Arc<Mutex<HashMap<String, String>>>,
+        subscribers: Arc<Mutex<Subscribers>>,
+        status: Arc<RequestStatus>,
+        auth_info: AuthInfo,
     ) -> Client {
         Client {
             inner: Arc::new(InnerClient {
                 sender,
-                cached_typeinfo: Default::default(),
+                last_request: Mutex::new(0),
+                cursor_transaction: AtomicBool::new(false),
+                status,
+                cached_typeinfo: Mutex::new(CachedTypeInfo {
+                    typeinfo: None,
+                    typeinfo_composite: None,
+                    typeinfo_enum: None,
+                    typeinfo_multirange: None,
+                    typeinfo_name: None,
+                    types: LruCache::new(config.type_cache_capacity),
+                }),
+                statement_cache: Mutex::new(LruCache::new(Some(config.statement_cache_capacity))),
+                #[cfg(feature = "bumpalo")]
+                arena_statement_cache: Default::default(),
+                statement_prefix: format!("{:08x}", rand::random::<u32>()),
                 buffer: Default::default(),
+                response_buffer_capacity: config.response_buffer_capacity,
             }),
             #[cfg(feature = "runtime")]
             socket_config: None,
-            ssl_mode,
-            ssl_negotiation,
-            process_id,
-            secret_key,
+            ssl_mode: config.ssl_mode,
+            ssl_negotiation: config.ssl_negotiation,
+            backend_key,
+            server_parameters,
+            subscribers,
+            auth_info,
+            query_rewriter: config.query_rewriter.clone(),
+            max_result_rows: config.max_result_rows,
+            fetch_count: config
+                .fetch_count
+                .filter(|n| *n > 0)
+                .map(|n| n.min(i32::MAX as usize) as i32),
         }
     }
 
@@ -212,6 +428,71 @@ impl Client {
         &self.inner
     }
 
+    /// Applies the configured query rewriter, if any, to the text of a user query.
+    pub(crate) fn rewrite_query<'a>(&self, query: &'a str) -> Cow<'a, str> {
+        match &self.query_rewriter {
+            Some(rewriter) => (rewriter.0)(query),
+            None => Cow::Borrowed(query),
+        }
+    }
+
+    /// Collects the rows of a stream, enforcing the configured `max_result_rows` limit.
+    async fn collect_rows(&self, stream: RowStream) -> Result<Vec<Row>, Error> {
+        let max = match self.max_result_rows {
+            Some(max) => max,
+            None => return stream.try_collect().await,
+        };
+
+        pin_mut!(stream);
+        let mut rows = vec![];
+        while let Some(row) = stream.try_next().await? {
+            if rows.len() == max {
+                drop(rows);
+                self.abandon_rows(stream).await;
+                return Err(Error::result_too_large(max));
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Stops the server from producing the rest of a result, and waits for it to become ready for the next query.
+    async fn abandon_rows(&self, mut stream: Pin<&mut RowStream>) {
+        // The cancellation request is sent without TLS, since the client does not keep the connector it was created
+        // with. If the server refuses it, the rest of the result is read and discarded instead. Cancelling a query
+        // inside of a transaction block would abort the whole transaction, so the result is always drained there.
+        #[cfg(feature = "runtime")]
+        if !self.inner.in_transaction() {
+            let handle = stream.query_handle();
+            let _ = self
+                .cancel_token()
+                .cancel_query_handle(&handle, crate::NoTls)
+                .await;
+        }
+
+        while let Some(result) = stream.next().await {
+            if result.is_err_and(|e| e.is_closed()) {
+                break;
+            }
+        }
+    }
+
+    /// Starts executing a statement, fetching its rows in batches if `Config::fetch_count` is set.
+    async fn query_stream<P, I>(&self, statement: Statement, params: I) -> Result<RowStream, Error>
+    where
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        match self.fetch_count {
+            Some(fetch_count) if !statement.columns().is_empty() => {
+                let begin = !self.inner.in_transaction();
+                query::query_batched(&self.inner, statement, params, fetch_count, begin).await
+            }
+            _ => query::query(&self.inner, statement, params).await,
+        }
+    }
+
     #[cfg(feature = "runtime")]
     pub(crate) fn set_socket_config(&mut self, socket_config: SocketConfig) {
         self.socket_config = Some(socket_config);
@@ -234,7 +515,84 @@ impl Client {
         query: &str,
         parameter_types: &[Type],
     ) -> Result<Statement, Error> {
-        prepare::prepare(&self.inner, query, parameter_types).await
+        #[cfg(feature = "tracing")]
+        let span = trace::span!("prepare", self);
+        let future = async {
+            let query = self.rewrite_query(query);
+            let statement = prepare::prepare(&self.inner, &query, parameter_types).await?;
+            #[cfg(feature = "tracing")]
+            trace::record_statement(&span, &statement);
+            Ok(statement)
+        };
+        #[cfg(feature = "tracing")]
+        let future = trace::instrument(span.clone(), future, |_| None);
+        future.await
+    }
+
+    /// Like `prepare`, but reuses a statement previously prepared for the same query text.
+    ///
+    /// Statements are cached by the client if `Config::statement_cache_capacity` is set, in which case the least
+    /// recently used statement is evicted once the cache is full. Queries passed as strings to methods like `query`
+    /// and `execute` go through the same cache, so that repeatedly running a query only has the server parse and
+    /// describe it once. If the cache is disabled, this is equivalent to `prepare`.
+    ///
+    /// Cached statements are tied to the definitions of the objects they reference at the time they were prepared. If
+    /// those are changed, for example by altering the type of a column, `clear_statement_cache` can be used to have
+    /// the statements prepared again.
+    pub async fn prepare_cached(&self, query: &str) -> Result<Statement, Error> {
+        if let Some(statement) = self.inner.cached_statement(query) {
+            return Ok(statement);
+        }
+
+        let statement = self.prepare(query).await?;
+        self.inner.cache_statement(query, &statement);
+        Ok(statement)
+    }
+
+    /// Creates several prepared statements in a single round trip to the server.
+    ///
+    /// This is intended for warming up a connection with a known set of statements, such as at service startup. The
+    /// statements are returned in the same order as `queries`. Preparation is all or nothing: if any query fails to
+    /// prepare, no statements are returned, and the index of the failing query is available from the error via
+    /// `Error::statement_index`.
+    pub async fn prepare_all(&self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        let queries = queries
+            .iter()
+            .map(|query| self.rewrite_query(query))
+            .collect::<Vec<_>>();
+        prepare::prepare_all(&self.inner, &queries).await
+    }
+
+    /// Looks up a type by name.
+    ///
+    /// If `schema` is `None`, the name is resolved against the connection's `search_path`, as it would be in a query.
+    /// The type's definition is loaded and cached in the same way as types referenced by prepared statements, which
+    /// makes this useful for seeding the cache ahead of `query_typed` calls. An error is returned if no such type
+    /// exists.
+    pub async fn type_by_name(&self, schema: Option<&str>, name: &str) -> Result<Type, Error> {
+        prepare::get_type_by_name(&self.inner, schema, name).await
+    }
+
+    /// Adds a type to the client's type information cache, keyed by its OID.
+    ///
+    /// When a statement refers to a type which is not built in, the client looks its definition up in the cache
+    /// before querying the database for it. Registering the definitions of known custom types, such as enums and
+    /// composites, up front avoids those queries entirely, which is useful where they are expensive or where
+    /// prepared statements are not supported. Any definition already cached for the OID is replaced.
+    ///
+    /// The built-in types are never looked up in the cache, so registering a type with the OID of one has no effect.
+    /// If `Config::type_cache_capacity` is set, registered types are evicted in the same way as loaded ones.
+    pub fn register_type(&self, type_: Type) {
+        self.inner.set_type(type_.oid(), &type_);
+    }
+
+    /// Returns the definition of the type with the given OID from the client's type information cache, if it is
+    /// there.
+    ///
+    /// The cache holds the types which have been registered with `register_type` or loaded from the database, but
+    /// not the built-in types, which are available from `Type::from_oid`.
+    pub fn cached_type(&self, oid: Oid) -> Option<Type> {
+        self.inner.type_(oid)
     }
 
     /// Executes a statement, returning a vector of the resulting rows.
@@ -253,10 +611,129 @@ impl Client {
     where
         T: ?Sized + ToStatement,
     {
-        self.query_raw(statement, slice_iter(params))
-            .await?
-            .try_collect()
-            .await
+        #[cfg(feature = "tracing")]
+        let span = trace::span!("query", self);
+        let future = async {
+            let statement = statement.__convert().into_statement(self).await?;
+            #[cfg(feature = "tracing")]
+            trace::record_statement(&span, &statement);
+            let stream = self.query_stream(statement, slice_iter(params)).await?;
+            self.collect_rows(stream).await
+        };
+        #[cfg(feature = "tracing")]
+        let future = trace::instrument(span.clone(), future, |rows| Some(rows.len() as u64));
+        future.await
+    }
+
+    /// Like `query`, but also returns information about the columns of the rows.
+    ///
+    /// The column information is cloned rather than borrowed from the statement, so that a complete result set can be
+    /// stored, such as in a cache, and its schema reconstructed later even though the statement has been dropped. The
+    /// columns are returned even if the query produces no rows.
+    pub async fn query_with_schema<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Column>, Vec<Row>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let statement = statement.__convert().into_statement(self).await?;
+        let rows = self.query(&statement, params).await?;
+        Ok((statement.columns().to_vec(), rows))
+    }
+
+    /// Like `query`, but returns a `ResultSet` which is independent of the statement.
+    ///
+    /// The result set owns its column information and row data, so it can be cached, sent to another thread, or
+    /// returned from a function without keeping the statement alive.
+    pub async fn query_owned<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<ResultSet, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let (columns, rows) = self.query_with_schema(statement, params).await?;
+        Ok(ResultSet::new(columns, rows))
+    }
+
+    /// Like `query`, but also returns the notices emitted while the statement runs.
+    ///
+    /// Notices, such as those raised with `RAISE NOTICE` by a function or trigger, are normally returned by the
+    /// `Connection` as `AsyncMessage::Notice`s. Those emitted during this query are instead collected and returned
+    /// alongside its rows, in the order they were received, which makes it easy to show a function's diagnostics next to
+    /// its results. The server's `client_min_messages` setting determines which notices are sent.
+    pub async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let statement = statement.__convert().into_statement(self).await?;
+        let (sender, mut receiver) = mpsc::unbounded();
+        let stream =
+            query::query_with_notices(&self.inner, statement, slice_iter(params), sender).await?;
+        let rows = self.collect_rows(stream).await?;
+
+        // Notices precede the end of the query's response, so they have all been received by now.
+        receiver.close();
+        let notices = receiver.collect().await;
+        Ok((rows, notices))
+    }
+
+    /// Returns the plan the server chooses for a statement, using `EXPLAIN`.
+    ///
+    /// The statement is prefixed with an `EXPLAIN` built from `options`, and may contain parameters which are bound
+    /// from `params` just as they would be for `query`. The plan is returned as text in the requested format: the
+    /// lines of the plan for `ExplainFormat::Text`, or a JSON document for `ExplainFormat::Json`.
+    ///
+    /// If `ExplainOptions::analyze` is set, the statement is executed.
+    pub async fn explain(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+        options: ExplainOptions,
+    ) -> Result<String, Error> {
+        explain::explain(self, statement, params, &options).await
+    }
+
+    /// Like `query`, but cancels the query if it has not completed by `deadline`.
+    ///
+    /// When the deadline passes, a cancellation request is sent to the server using `tls`, and this method waits for
+    /// the server to abandon the query so that the cancellation cannot affect a later one. An error for which
+    /// `Error::is_timeout` returns `true` is then returned. This is distinct from a query cancelled by other means,
+    /// which fails with the `QUERY_CANCELED` SQLSTATE.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub async fn query_deadline<T, U>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        let query = self.query(statement, params);
+        pin_mut!(query);
+
+        if let Ok(result) = time::timeout_at(deadline, &mut query).await {
+            return result;
+        }
+
+        // If the cancellation request could not be delivered, there is no risk of it hitting a later query.
+        if self.cancel_token().cancel_query(tls).await.is_ok() {
+            let _ = query.await;
+        }
+
+        Err(Error::__private_api_timeout())
     }
 
     /// Executes a statement which returns a single row, returning it.
@@ -299,26 +776,217 @@ impl Client {
     ) -> Result<Option<Row>, Error>
     where
         T: ?Sized + ToStatement,
+    {
+        #[cfg(feature = "tracing")]
+        let span = trace::span!("query", self);
+        let future = async {
+            let statement = statement.__convert().into_statement(self).await?;
+            #[cfg(feature = "tracing")]
+            trace::record_statement(&span, &statement);
+            let stream = self.query_stream(statement, slice_iter(params)).await?;
+            pin_mut!(stream);
+
+            let mut first = None;
+
+            // Originally this was two calls to `try_next().await?`,
+            // once for the first element, and second to error if more than one.
+            //
+            // However, this new form with only one .await in a loop generates
+            // slightly smaller codegen/stack usage for the resulting future.
+            while let Some(row) = stream.try_next().await? {
+                if first.is_some() {
+                    return Err(Error::row_count());
+                }
+
+                first = Some(row);
+            }
+
+            Ok(first)
+        };
+        #[cfg(feature = "tracing")]
+        let future = trace::instrument(span.clone(), future, |row| Some(row.is_some() as u64));
+        future.await
+    }
+
+    /// Executes a statement, returning the values of the first column of the resulting rows.
+    ///
+    /// This is convenient for queries which select a single column, such as a list of IDs. Any columns after the first
+    /// are ignored, and an error is returned before the statement is executed if it does not return any columns.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. If the same statement will be
+    /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
+    /// with the `prepare` method.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn async_main(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// let ids = client
+    ///     .query_scalar::<_, i32>("SELECT id FROM users WHERE active", &[])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_scalar<T, F>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<F>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        let statement = self.scalar_statement(statement).await?;
+        self.query(&statement, params)
+            .await?
+            .iter()
+            .map(|row| row.try_get(0))
+            .collect()
+    }
+
+    /// Executes a statement which returns a single row, returning the value of its first column.
+    ///
+    /// This is convenient for queries like `SELECT count(*) FROM ...`. Returns an error if the query does not return
+    /// exactly one row, or if it does not return any columns.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. If the same statement will be
+    /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
+    /// with the `prepare` method.
+    pub async fn query_scalar_one<T, F>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<F, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        let statement = self.scalar_statement(statement).await?;
+        self.query_one(&statement, params).await?.try_get(0)
+    }
+
+    async fn scalar_statement<T>(&self, statement: &T) -> Result<Statement, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let statement = statement.__convert().into_statement(self).await?;
+        if statement.columns().is_empty() {
+            return Err(Error::column_count(0, 1));
+        }
+        Ok(statement)
+    }
+
+    /// Executes a statement, returning the resulting rows in a map keyed by the value of one of their columns.
+    ///
+    /// This is convenient for loading lookup tables. `key` identifies the column to use as the key, and `duplicates`
+    /// controls what happens when more than one row has the same key.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. If the same statement will be
+    /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
+    /// with the `prepare` method.
+    pub async fn query_map_by<T, I, K>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        key: I,
+        duplicates: DuplicateKeys,
+    ) -> Result<HashMap<K, Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        I: RowIndex + fmt::Display,
+        K: FromSqlOwned + Eq + Hash,
     {
         let stream = self.query_raw(statement, slice_iter(params)).await?;
         pin_mut!(stream);
 
-        let mut first = None;
-
-        // Originally this was two calls to `try_next().await?`,
-        // once for the first element, and second to error if more than one.
-        //
-        // However, this new form with only one .await in a loop generates
-        // slightly smaller codegen/stack usage for the resulting future.
+        let mut map = HashMap::new();
         while let Some(row) = stream.try_next().await? {
-            if first.is_some() {
-                return Err(Error::row_count());
+            let k = row.try_get(&key)?;
+            match map.entry(k) {
+                Entry::Occupied(_) if duplicates == DuplicateKeys::Error => {
+                    return Err(Error::duplicate_key(key.to_string()));
+                }
+                Entry::Occupied(mut e) => {
+                    e.insert(row);
+                }
+                Entry::Vacant(e) => {
+                    e.insert(row);
+                }
             }
+        }
+
+        Ok(map)
+    }
+
+    /// Executes a statement, returning each resulting row as an array of values of the same type.
+    ///
+    /// This is convenient for homogeneous result sets, such as a matrix of numbers. The statement must return exactly
+    /// `N` columns, all of which can be converted to `F`; an error is returned before the statement is executed if the
+    /// number of columns does not match.
+    ///
+    /// A statement may contain parameters, specified by `$n`, where `n` is the index of the parameter of the list
+    /// provided, 1-indexed.
+    ///
+    /// The `statement` argument can either be a `Statement`, or a raw query string. If the same statement will be
+    /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
+    /// with the `prepare` method.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn async_main(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// let points = client
+    ///     .query_array::<_, f64, 2>("SELECT x, y FROM points", &[])
+    ///     .await?;
+    /// for [x, y] in points {
+    ///     println!("({}, {})", x, y);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_array<T, F, const N: usize>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<[F; N]>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        let statement = statement.__convert().into_statement(self).await?;
+        if statement.columns().len() != N {
+            return Err(Error::column_count(statement.columns().len(), N));
+        }
+
+        let stream = self.query_stream(statement, slice_iter(params)).await?;
+        pin_mut!(stream);
 
-            first = Some(row);
+        let mut rows = vec![];
+        while let Some(row) = stream.try_next().await? {
+            let mut error = None;
+            let values: [Option<F>; N] = std::array::from_fn(|i| match row.try_get(i) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    error.get_or_insert(e);
+                    None
+                }
+            });
+            if let Some(e) = error {
+                return Err(e);
+            }
+            rows.push(values.map(Option::unwrap));
         }
 
-        Ok(first)
+        Ok(rows)
     }
 
     /// The maximally flexible version of [`query`].
@@ -363,7 +1031,31 @@ impl Client {
         I::IntoIter: ExactSizeIterator,
     {
         let statement = statement.__convert().into_statement(self).await?;
-        query::query(&self.inner, statement, params).await
+        self.query_stream(statement, params).await
+    }
+
+    /// Like `query_raw`, but sends all parameters in the text format rather than the binary format.
+    ///
+    /// This is an escape hatch for custom types which have a text representation but no binary
+    /// encoding. Every parameter's `ToSql` implementation must write a valid text representation of
+    /// the value for its Postgres type, as the server will parse it with the type's input function.
+    /// The built-in `ToSql` implementations for most types write the binary format and will not
+    /// work with this method; string types are a notable exception.
+    ///
+    /// Result values are still requested in the binary format.
+    pub async fn query_raw_text_params<T, P, I>(
+        &self,
+        statement: &T,
+        params: I,
+    ) -> Result<RowStream, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let statement = statement.__convert().into_statement(self).await?;
+        query::query_text_params(&self.inner, statement, params).await
     }
 
     /// Like `query`, but requires the types of query parameters to be explicitly specified.
@@ -380,10 +1072,10 @@ impl Client {
         query: &str,
         params: &[(&(dyn ToSql + Sync), Type)],
     ) -> Result<Vec<Row>, Error> {
-        self.query_typed_raw(query, params.iter().map(|(v, t)| (*v, t.clone())))
-            .await?
-            .try_collect()
-            .await
+        let stream = self
+            .query_typed_raw(query, params.iter().map(|(v, t)| (*v, t.clone())))
+            .await?;
+        self.collect_rows(stream).await
     }
 
     /// The maximally flexible version of [`query_typed`].
@@ -427,7 +1119,8 @@ impl Client {
         P: BorrowToSql,
         I: IntoIterator<Item = (P, Type)>,
     {
-        query::query_typed(&self.inner, query, params).await
+        let query = self.rewrite_query(query);
+        query::query_typed(&self.inner, &query, params).await
     }
 
     /// Executes a statement, returning the number of rows modified.
@@ -448,7 +1141,17 @@ impl Client {
     where
         T: ?Sized + ToStatement,
     {
-        self.execute_raw(statement, slice_iter(params)).await
+        #[cfg(feature = "tracing")]
+        let span = trace::span!("execute", self);
+        let future = async {
+            let statement = statement.__convert().into_statement(self).await?;
+            #[cfg(feature = "tracing")]
+            trace::record_statement(&span, &statement);
+            query::execute(self.inner(), statement, slice_iter(params)).await
+        };
+        #[cfg(feature = "tracing")]
+        let future = trace::instrument(span.clone(), future, |rows| Some(*rows));
+        future.await
     }
 
     /// The maximally flexible version of [`execute`].
@@ -476,6 +1179,8 @@ impl Client {
     ///
     /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any. The copy *must*
     /// be explicitly completed via the `Sink::close` or `finish` methods. If it is not, the copy will be aborted.
+    ///
+    /// An error is returned if the statement does not start a `COPY FROM STDIN`.
     pub async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
     where
         T: ?Sized + ToStatement,
@@ -488,6 +1193,8 @@ impl Client {
     /// Executes a `COPY TO STDOUT` statement, returning a stream of the resulting data.
     ///
     /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any.
+    ///
+    /// An error is returned if the statement does not start a `COPY TO STDOUT`.
     pub async fn copy_out<T>(&self, statement: &T) -> Result<CopyOutStream, Error>
     where
         T: ?Sized + ToStatement,
@@ -496,6 +1203,102 @@ impl Client {
         copy_out::copy_out(self.inner(), statement).await
     }
 
+    /// Runs a query through a binary `COPY ... TO STDOUT`, collecting the decoded rows.
+    ///
+    /// The query is wrapped in `COPY (query) TO STDOUT WITH (FORMAT binary)`, and `types` must match the types of
+    /// the columns it returns. For large result sets this is typically faster than running the query normally,
+    /// since the binary copy format is more compact than the row messages of the extended query protocol.
+    ///
+    /// The binary copy format does not identify the types of its values, so the query is first prepared to look up
+    /// the types of its columns. An error is returned before the copy is started if the query does not return
+    /// `types.len()` columns, or if the type of any of them differs from the corresponding entry in `types`.
+    ///
+    /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any.
+    pub async fn copy_out_rows(
+        &self,
+        query: &str,
+        types: &[Type],
+    ) -> Result<Vec<BinaryCopyOutRow>, Error> {
+        let columns = self.copy_out_columns(query, types.len()).await?;
+        for (idx, (column, type_)) in columns.iter().zip(types).enumerate() {
+            if column.type_() != type_ {
+                return Err(Error::from_sql(
+                    format!(
+                        "column \"{}\" is of type {} rather than {}",
+                        column.name(),
+                        column.type_(),
+                        type_,
+                    )
+                    .into(),
+                    idx,
+                ));
+            }
+        }
+
+        let query = format!("COPY ({}) TO STDOUT WITH (FORMAT binary)", query);
+        let stream = self.copy_out(&*query).await?;
+        BinaryCopyOutStream::new(stream, types).try_collect().await
+    }
+
+    /// Runs a query through a binary `COPY ... TO STDOUT`, decoding the rows into Arrow record batches.
+    ///
+    /// The query is wrapped in `COPY (query) TO STDOUT WITH (FORMAT binary)`, and the fields of `schema` must match
+    /// the columns it returns. Each field's Arrow type determines the Postgres type it is decoded from:
+    ///
+    /// | Arrow type | Postgres type |
+    /// |------------|---------------|
+    /// | `Boolean` | `BOOL` |
+    /// | `Int16` | `INT2` |
+    /// | `Int32` | `INT4` |
+    /// | `Int64` | `INT8` |
+    /// | `Float32` | `FLOAT4` |
+    /// | `Float64` | `FLOAT8` |
+    /// | `Utf8` | `TEXT` |
+    /// | `Binary` | `BYTEA` |
+    /// | `Timestamp(Microsecond, None)` | `TIMESTAMP` |
+    /// | `Timestamp(Microsecond, Some(_))` | `TIMESTAMPTZ` |
+    /// | `Date32` | `DATE` |
+    ///
+    /// An error is returned before the query is sent if the schema contains any other type. The query is then prepared
+    /// to look up the types of its columns, and an error is returned before the copy is started if it does not return
+    /// one column per field, or if the type of a column cannot be decoded into its field's Arrow type. Each batch holds
+    /// at most `batch_size` rows.
+    ///
+    /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is 0.
+    #[cfg(feature = "with-arrow-57")]
+    pub async fn copy_out_arrow(
+        &self,
+        query: &str,
+        schema: SchemaRef,
+        batch_size: usize,
+    ) -> Result<RecordBatchStream, Error> {
+        assert!(batch_size > 0, "batch_size must be positive");
+        let types = arrow::postgres_types(&schema)?;
+        let columns = self.copy_out_columns(query, types.len()).await?;
+        arrow::check_columns(&schema, &columns)?;
+
+        let query = format!("COPY ({}) TO STDOUT WITH (FORMAT binary)", query);
+        let stream = self.copy_out(&*query).await?;
+        Ok(RecordBatchStream::new(
+            BinaryCopyOutStream::new(stream, &types),
+            schema,
+            batch_size,
+        ))
+    }
+
+    /// Returns the columns of the source query of a binary copy, checking that there are `len` of them.
+    async fn copy_out_columns(&self, query: &str, len: usize) -> Result<Vec<Column>, Error> {
+        let statement = self.prepare(query).await?;
+        if statement.columns().len() != len {
+            return Err(Error::column_count(statement.columns().len(), len));
+        }
+        Ok(statement.columns().to_vec())
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol, returning the resulting rows.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
@@ -520,7 +1323,12 @@ impl Client {
     /// Executes a sequence of SQL statements using the simple query protocol.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
-    /// point. This is intended for use when, for example, initializing a database schema.
+    /// point. This is intended for use when, for example, initializing a database schema. The index of the statement
+    /// which failed is available from the error via `Error::statement_index`.
+    ///
+    /// The script is encoded into the buffer the client reuses for all outgoing messages, and any rows returned by
+    /// its statements are discarded without being decoded, so running a large script allocates little beyond the
+    /// message itself. There is consequently no arena version of this method.
     ///
     /// # Warning
     ///
@@ -546,19 +1354,119 @@ impl Client {
         TransactionBuilder::new(self)
     }
 
+    /// Runs a closure inside a new database transaction.
+    ///
+    /// The transaction is committed if the closure's future resolves to `Ok`, and rolled back otherwise. If the body
+    /// succeeds but the commit fails, the commit error is returned. If the body fails, its error is returned and any
+    /// error encountered while rolling back is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn async_main(client: &mut tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// let id: i32 = client
+    ///     .with_transaction(|transaction| {
+    ///         Box::pin(async move {
+    ///             transaction.execute("UPDATE foo SET bar = 10", &[]).await?;
+    ///             let row = transaction.query_one("SELECT id FROM foo", &[]).await?;
+    ///             Ok(row.get(0))
+    ///         })
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_transaction<F, T>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: for<'t> FnOnce(&'t Transaction<'_>) -> BoxFuture<'t, Result<T, Error>>,
+    {
+        let transaction = self.transaction().await?;
+        match f(&transaction).await {
+            Ok(value) => {
+                transaction.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = transaction.rollback().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Executes several statements atomically inside a new database transaction, returning the number of rows
+    /// modified by each.
+    ///
+    /// Each entry of `statements` is a query along with its parameters. The statements are executed in order, and the
+    /// transaction is committed once all of them have succeeded. If any statement fails, the transaction is rolled
+    /// back and the error is returned, with the index of the failing statement available from
+    /// `Error::statement_index`.
+    pub async fn execute_all_in_transaction(
+        &mut self,
+        statements: &[(&str, &[&(dyn ToSql + Sync)])],
+    ) -> Result<Vec<u64>, Error> {
+        let transaction = self.transaction().await?;
+        let mut counts = Vec::with_capacity(statements.len());
+        for (idx, (statement, params)) in statements.iter().enumerate() {
+            match transaction.execute(*statement, params).await {
+                Ok(count) => counts.push(count),
+                Err(e) => {
+                    let _ = transaction.rollback().await;
+                    return Err(e.with_statement_index(idx));
+                }
+            }
+        }
+        transaction.commit().await?;
+        Ok(counts)
+    }
+
+    /// Executes a statement with `search_path` set to `schema`, returning the resulting rows.
+    ///
+    /// The statement runs in a new transaction in which the search path is set with `SET LOCAL`, so the setting ends
+    /// with the transaction and does not affect later queries on the connection. The transaction is committed if the
+    /// statement succeeds and rolled back otherwise, so the statement may modify data. To run a statement under a
+    /// different search path within an existing transaction, use `Transaction::query_in_schema`.
+    ///
+    /// If `statement` is a query string, it is prepared after the search path has been set, so unqualified names in
+    /// it resolve against `schema`. Postgres's own catalog, `pg_catalog`, is always searched as well.
+    pub async fn query_in_schema<T>(
+        &mut self,
+        schema: &str,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let transaction = self.transaction().await?;
+        let rows = transaction
+            .query_in_schema(schema, statement, params)
+            .await?;
+        transaction.commit().await?;
+        Ok(rows)
+    }
+
     /// Constructs a cancellation token that can later be used to request cancellation of a query running on the
     /// connection associated with this client.
+    ///
+    /// The token uses the backend key most recently sent by the server.
     pub fn cancel_token(&self) -> CancelToken {
+        let backend_key = *self.backend_key.lock();
         CancelToken {
             #[cfg(feature = "runtime")]
             socket_config: self.socket_config.clone(),
             ssl_mode: self.ssl_mode,
             ssl_negotiation: self.ssl_negotiation,
-            process_id: self.process_id,
-            secret_key: self.secret_key,
+            process_id: backend_key.process_id,
+            secret_key: backend_key.secret_key,
         }
     }
 
+    /// Returns the process ID of the connection's backend, as most recently reported by the server.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn backend_pid(&self) -> i32 {
+        self.backend_key.lock().process_id
+    }
+
     /// Attempts to cancel an in-progress query.
     ///
     /// The server provides no information about whether a cancellation attempt was successful or not. An error will
@@ -585,6 +1493,45 @@ impl Client {
         self.cancel_token().cancel_query_raw(stream, tls).await
     }
 
+    /// Returns information about how the connection authenticated with the server.
+    pub fn auth_info(&self) -> AuthInfo {
+        self.auth_info
+    }
+
+    /// Returns the value of a parameter the server reported for the connection, such as `server_version`,
+    /// `server_encoding`, or `TimeZone`.
+    ///
+    /// The server reports these parameters when the connection is established, and reports their new values as they
+    /// change, for example after a `SET TimeZone`, so the latest value is returned. Returns `None` if the server has
+    /// not reported the parameter.
+    ///
+    /// The value is copied, since it may be updated by the connection at any time.
+    pub fn server_parameter(&self, name: &str) -> Option<String> {
+        self.server_parameters.lock().get(name).cloned()
+    }
+
+    /// Returns all of the parameters the server has reported for the connection, along with their latest values.
+    ///
+    /// See `server_parameter` for details.
+    pub fn server_parameters(&self) -> HashMap<String, String> {
+        self.server_parameters.lock().clone()
+    }
+
+    /// Returns a stream of the asynchronous notifications received by the connection.
+    ///
+    /// After a `LISTEN` command has subscribed the connection to a channel, the notifications raised on it with
+    /// `NOTIFY` are delivered to every stream returned by this method, without the need to issue queries. Only the
+    /// notifications received after the stream has been created are delivered. The stream ends when the connection
+    /// closes, and is empty if it has already closed.
+    ///
+    /// Each stream buffers the notifications which have not been read from it yet, up to
+    /// `Config::max_buffered_notifications`.
+    ///
+    /// The connection still returns the notifications from `Connection::poll_message` as well.
+    pub fn notifications(&self) -> NotificationStream {
+        Subscribers::subscribe(&self.subscribers)
+    }
+
     /// Clears the client's type information cache.
     ///
     /// When user-defined types are used in a query, the client loads their definitions from the database and caches
@@ -594,6 +1541,23 @@ impl Client {
         self.inner().clear_type_cache();
     }
 
+    /// Removes the type with the given OID from the client's type information cache.
+    ///
+    /// Unlike `clear_type_cache`, this only flushes the definition of a single type, such as one which has just been
+    /// altered or dropped, so that it is loaded again the next time it is used. Definitions of other types which
+    /// refer to it, such as arrays of it, are not removed.
+    pub fn clear_type(&self, oid: Oid) {
+        self.inner().clear_type(oid);
+    }
+
+    /// Clears the client's cache of prepared statements.
+    ///
+    /// See `prepare_cached` for details. This also clears the cache `prepare_in` uses to share statements between
+    /// arenas, although statements which are still in use remain prepared until they are dropped.
+    pub fn clear_statement_cache(&self) {
+        self.inner().clear_statement_cache();
+    }
+
     /// Determines if the connection to the server has already closed.
     ///
     /// In that case, all future queries will fail.
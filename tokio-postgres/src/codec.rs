@@ -1,3 +1,5 @@
+#[cfg(feature = "message-tracing")]
+use crate::message_trace::{Direction, MessageTag, MessageTracer};
 use bytes::{Buf, Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::backend;
@@ -24,6 +26,11 @@ impl BackendMessages {
     pub fn empty() -> BackendMessages {
         BackendMessages(BytesMut::new())
     }
+
+    /// Returns the transaction status of the `ReadyForQuery` message which ends the messages of a completed request.
+    pub fn transaction_status(&self) -> Option<u8> {
+        self.0.last().copied()
+    }
 }
 
 impl FallibleIterator for BackendMessages {
@@ -35,17 +42,51 @@ impl FallibleIterator for BackendMessages {
     }
 }
 
-pub struct PostgresCodec;
+pub struct PostgresCodec {
+    #[cfg(feature = "message-tracing")]
+    tracer: Option<MessageTracer>,
+    #[cfg(feature = "message-tracing")]
+    startup: bool,
+}
+
+impl PostgresCodec {
+    pub fn new() -> PostgresCodec {
+        PostgresCodec {
+            #[cfg(feature = "message-tracing")]
+            tracer: None,
+            #[cfg(feature = "message-tracing")]
+            startup: true,
+        }
+    }
+
+    /// Sets the tracer reporting each message passing through the codec.
+    ///
+    /// The codec is created once TLS has been negotiated, so the first message it encodes is the startup message.
+    #[cfg(feature = "message-tracing")]
+    pub fn with_tracer(mut self, tracer: Option<MessageTracer>) -> PostgresCodec {
+        self.tracer = tracer;
+        self
+    }
+}
 
 impl Encoder<FrontendMessage> for PostgresCodec {
     type Error = io::Error;
 
     fn encode(&mut self, item: FrontendMessage, dst: &mut BytesMut) -> io::Result<()> {
+        #[cfg(feature = "message-tracing")]
+        let start = dst.len();
+
         match item {
             FrontendMessage::Raw(buf) => dst.extend_from_slice(&buf),
             FrontendMessage::CopyData(data) => data.write(dst),
         }
 
+        #[cfg(feature = "message-tracing")]
+        if let Some(tracer) = &self.tracer {
+            tracer.trace(Direction::Frontend, &dst[start..], self.startup);
+            self.startup = false;
+        }
+
         Ok(())
     }
 }
@@ -67,8 +108,11 @@ impl Decoder for PostgresCodec {
             match header.tag() {
                 backend::NOTICE_RESPONSE_TAG
                 | backend::NOTIFICATION_RESPONSE_TAG
-                | backend::PARAMETER_STATUS_TAG => {
+                | backend::PARAMETER_STATUS_TAG
+                | backend::BACKEND_KEY_DATA_TAG => {
                     if idx == 0 {
+                        #[cfg(feature = "message-tracing")]
+                        self.trace(header.tag(), len);
                         let message = backend::Message::parse(src)?.unwrap();
                         return Ok(Some(BackendMessage::Async(message)));
                     } else {
@@ -78,6 +122,8 @@ impl Decoder for PostgresCodec {
                 _ => {}
             }
 
+            #[cfg(feature = "message-tracing")]
+            self.trace(header.tag(), len);
             idx += len;
 
             if header.tag() == backend::READY_FOR_QUERY_TAG {
@@ -96,3 +142,13 @@ impl Decoder for PostgresCodec {
         }
     }
 }
+
+#[cfg(feature = "message-tracing")]
+impl PostgresCodec {
+    #[inline]
+    fn trace(&self, tag: u8, len: usize) {
+        if let Some(tracer) = &self.tracer {
+            (tracer.0)(Direction::Backend, MessageTag(tag), len);
+        }
+    }
+}
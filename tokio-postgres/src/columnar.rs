@@ -0,0 +1,217 @@
+//! Utilities for collecting query results into a column-major layout.
+//!
+//! See [`RowStream::collect_columnar`] for details.
+//!
+//! [`RowStream::collect_columnar`]: crate::RowStream::collect_columnar
+
+use crate::types::{FromSql, Type};
+use crate::{Column, Error, Row};
+
+/// The decoded values of a single column.
+///
+/// Slots corresponding to `NULL` values hold the type's default value; consult the column's validity bitmap to tell
+/// them apart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    /// `BOOL` values.
+    Bool(Vec<bool>),
+    /// `INT2` values.
+    Int2(Vec<i16>),
+    /// `INT4` values.
+    Int4(Vec<i32>),
+    /// `INT8` values.
+    Int8(Vec<i64>),
+    /// `FLOAT4` values.
+    Float4(Vec<f32>),
+    /// `FLOAT8` values.
+    Float8(Vec<f64>),
+    /// `TEXT`, `VARCHAR`, `BPCHAR` and `NAME` values.
+    Text(Vec<String>),
+    /// `BYTEA` values.
+    Bytea(Vec<Vec<u8>>),
+}
+
+impl ColumnData {
+    fn new(ty: &Type) -> Option<ColumnData> {
+        let data = match *ty {
+            Type::BOOL => ColumnData::Bool(vec![]),
+            Type::INT2 => ColumnData::Int2(vec![]),
+            Type::INT4 => ColumnData::Int4(vec![]),
+            Type::INT8 => ColumnData::Int8(vec![]),
+            Type::FLOAT4 => ColumnData::Float4(vec![]),
+            Type::FLOAT8 => ColumnData::Float8(vec![]),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME => ColumnData::Text(vec![]),
+            Type::BYTEA => ColumnData::Bytea(vec![]),
+            _ => return None,
+        };
+        Some(data)
+    }
+
+    /// Returns the number of values in the column.
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::Bool(v) => v.len(),
+            ColumnData::Int2(v) => v.len(),
+            ColumnData::Int4(v) => v.len(),
+            ColumnData::Int8(v) => v.len(),
+            ColumnData::Float4(v) => v.len(),
+            ColumnData::Float8(v) => v.len(),
+            ColumnData::Text(v) => v.len(),
+            ColumnData::Bytea(v) => v.len(),
+        }
+    }
+
+    /// Determines if the column contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A single column of a [`ColumnStore`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnArray {
+    name: String,
+    data: ColumnData,
+    validity: Vec<u8>,
+}
+
+impl ColumnArray {
+    /// Returns the name of the column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the decoded values of the column.
+    pub fn data(&self) -> &ColumnData {
+        &self.data
+    }
+
+    /// Consumes the column, returning its decoded values.
+    pub fn into_data(self) -> ColumnData {
+        self.data
+    }
+
+    /// Returns the validity bitmap of the column.
+    ///
+    /// Bit `i % 8` of byte `i / 8` is set if the value in row `i` is not `NULL`.
+    pub fn validity(&self) -> &[u8] {
+        &self.validity
+    }
+
+    /// Determines if the value in the specified row is not `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row is out of bounds.
+    pub fn is_valid(&self, row: usize) -> bool {
+        assert!(row < self.data.len(), "row index out of bounds");
+        self.validity[row / 8] & (1 << (row % 8)) != 0
+    }
+
+    /// Returns the number of `NULL` values in the column.
+    pub fn null_count(&self) -> usize {
+        let valid = self
+            .validity
+            .iter()
+            .map(|b| b.count_ones() as usize)
+            .sum::<usize>();
+        self.data.len() - valid
+    }
+
+    fn push(&mut self, row: &Row, idx: usize) -> Result<(), Error> {
+        let valid = match &mut self.data {
+            ColumnData::Bool(v) => push_value(v, row, idx)?,
+            ColumnData::Int2(v) => push_value(v, row, idx)?,
+            ColumnData::Int4(v) => push_value(v, row, idx)?,
+            ColumnData::Int8(v) => push_value(v, row, idx)?,
+            ColumnData::Float4(v) => push_value(v, row, idx)?,
+            ColumnData::Float8(v) => push_value(v, row, idx)?,
+            ColumnData::Text(v) => push_value(v, row, idx)?,
+            ColumnData::Bytea(v) => push_value(v, row, idx)?,
+        };
+
+        let len = self.data.len();
+        if len > self.validity.len() * 8 {
+            self.validity.push(0);
+        }
+        if valid {
+            self.validity[(len - 1) / 8] |= 1 << ((len - 1) % 8);
+        }
+
+        Ok(())
+    }
+}
+
+fn push_value<T>(values: &mut Vec<T>, row: &Row, idx: usize) -> Result<bool, Error>
+where
+    T: for<'a> FromSql<'a> + Default,
+{
+    let value = row.try_get::<_, Option<T>>(idx)?;
+    let valid = value.is_some();
+    values.push(value.unwrap_or_default());
+    Ok(valid)
+}
+
+/// Query results stored column by column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStore {
+    columns: Vec<ColumnArray>,
+    rows: usize,
+}
+
+impl ColumnStore {
+    pub(crate) fn new(names: &[Column], types: &[Type]) -> Result<ColumnStore, Error> {
+        if names.len() != types.len() {
+            return Err(Error::column_count(types.len(), names.len()));
+        }
+
+        let columns = names
+            .iter()
+            .zip(types)
+            .enumerate()
+            .map(|(idx, (column, ty))| {
+                let data = ColumnData::new(ty).ok_or_else(|| {
+                    Error::from_sql(
+                        format!("type {} cannot be collected into columns", ty).into(),
+                        idx,
+                    )
+                })?;
+                Ok(ColumnArray {
+                    name: column.name().to_string(),
+                    data,
+                    validity: vec![],
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(ColumnStore { columns, rows: 0 })
+    }
+
+    pub(crate) fn push(&mut self, row: &Row) -> Result<(), Error> {
+        for (idx, column) in self.columns.iter_mut().enumerate() {
+            column.push(row, idx)?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Determines if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    /// Returns the columns.
+    pub fn columns(&self) -> &[ColumnArray] {
+        &self.columns
+    }
+
+    /// Consumes the store, returning its columns.
+    pub fn into_columns(self) -> Vec<ColumnArray> {
+        self.columns
+    }
+}
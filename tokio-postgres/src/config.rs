@@ -5,8 +5,12 @@
 #[cfg(feature = "runtime")]
 use crate::connect::connect;
 use crate::connect_raw::connect_raw;
+#[cfg(feature = "gssapi")]
+use crate::gss::{GssContext, GssProvider};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::keepalive::KeepaliveConfig;
+#[cfg(feature = "message-tracing")]
+use crate::message_trace::{Direction, MessageTag, MessageTracer};
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
@@ -20,10 +24,10 @@ use std::net::IpAddr;
 use std::ops::Deref;
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
-#[cfg(unix)]
 use std::path::{Path, PathBuf};
 use std::str;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{error, fmt, iter, mem};
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -38,6 +42,8 @@ pub enum TargetSessionAttrs {
     ReadWrite,
     /// The session allow only reads.
     ReadOnly,
+    /// Sessions which allow only reads are preferred, but any session is accepted if none of the hosts provide one.
+    PreferStandby,
 }
 
 /// TLS configuration.
@@ -131,6 +137,10 @@ pub enum Host {
 ///     provided for that.
 ///     If set to `postgres`, the default value, it follows original postgres
 ///     wire protocol to perform the negotiation.
+/// * `sslrootcert` - The path to a file containing the certificate authorities used to verify the server's certificate.
+/// * `sslcert` - The path to a file containing the client's certificate.
+/// * `sslkey` - The path to a file containing the client's private key.
+/// * `krbsrvname` - The Kerberos service name to use when authenticating with GSSAPI. Defaults to `postgres`.
 /// * `hostaddr` - Numeric IP address of host to connect to. This should be in the standard IPv4 address format,
 ///     e.g., 172.28.40.9. If your machine supports IPv6, you can also use those addresses.
 ///     If this parameter is not specified, the value of `host` will be looked up to find the corresponding IP address,
@@ -160,9 +170,12 @@ pub enum Host {
 ///     This option is ignored when connecting with Unix sockets.
 /// * `keepalives_retries` - The maximum number of TCP keepalive probes that will be sent before dropping a connection.
 ///     This option is ignored when connecting with Unix sockets.
+/// * `keepalives_count` - An alias for `keepalives_retries`, matching the name used by libpq.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
 ///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
-///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
+///     in a database cluster as opposed to the secondary read-only mirrors. If set to `read-only`, the client will
+///     instead require that `transaction_read_only` is `on`. If set to `prefer-standby`, read-only servers are tried
+///     first, and the remaining hosts are only tried if none of them is available. Defaults to `all`.
 /// * `channel_binding` - Controls usage of channel binding in the authentication process. If set to `disable`, channel
 ///     binding will not be used. If set to `prefer`, channel binding will be used if available, but not used otherwise.
 ///     If set to `require`, the authentication process will fail if channel binding is not used. Defaults to `prefer`.
@@ -224,17 +237,35 @@ pub struct Config {
     pub(crate) application_name: Option<String>,
     pub(crate) ssl_mode: SslMode,
     pub(crate) ssl_negotiation: SslNegotiation,
+    pub(crate) ssl_root_cert: Option<PathBuf>,
+    pub(crate) ssl_cert: Option<PathBuf>,
+    pub(crate) ssl_key: Option<PathBuf>,
+    pub(crate) krbsrvname: Option<String>,
     pub(crate) host: Vec<Host>,
     pub(crate) hostaddr: Vec<IpAddr>,
     pub(crate) port: Vec<u16>,
     pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) connect_retries: u32,
+    pub(crate) connect_retry_backoff: Duration,
     pub(crate) tcp_user_timeout: Option<Duration>,
     pub(crate) keepalives: bool,
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) keepalive_config: KeepaliveConfig,
     pub(crate) target_session_attrs: TargetSessionAttrs,
+    pub(crate) validation_query: Option<String>,
     pub(crate) channel_binding: ChannelBinding,
     pub(crate) load_balance_hosts: LoadBalanceHosts,
+    pub(crate) query_rewriter: Option<QueryRewriter>,
+    pub(crate) max_result_rows: Option<usize>,
+    pub(crate) fetch_count: Option<usize>,
+    pub(crate) statement_cache_capacity: usize,
+    pub(crate) response_buffer_capacity: usize,
+    pub(crate) type_cache_capacity: Option<usize>,
+    pub(crate) max_buffered_notifications: Option<usize>,
+    #[cfg(feature = "message-tracing")]
+    pub(crate) message_tracer: Option<MessageTracer>,
+    #[cfg(feature = "gssapi")]
+    pub(crate) gss_provider: Option<GssProvider>,
 }
 
 impl Default for Config {
@@ -254,10 +285,16 @@ impl Config {
             application_name: None,
             ssl_mode: SslMode::Prefer,
             ssl_negotiation: SslNegotiation::Postgres,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
+            krbsrvname: None,
             host: vec![],
             hostaddr: vec![],
             port: vec![],
             connect_timeout: None,
+            connect_retries: 0,
+            connect_retry_backoff: Duration::from_millis(100),
             tcp_user_timeout: None,
             keepalives: true,
             #[cfg(not(target_arch = "wasm32"))]
@@ -267,8 +304,20 @@ impl Config {
                 retries: None,
             },
             target_session_attrs: TargetSessionAttrs::Any,
+            validation_query: None,
             channel_binding: ChannelBinding::Prefer,
             load_balance_hosts: LoadBalanceHosts::Disable,
+            query_rewriter: None,
+            max_result_rows: None,
+            fetch_count: None,
+            statement_cache_capacity: 0,
+            response_buffer_capacity: 1,
+            type_cache_capacity: None,
+            max_buffered_notifications: None,
+            #[cfg(feature = "message-tracing")]
+            message_tracer: None,
+            #[cfg(feature = "gssapi")]
+            gss_provider: None,
         }
     }
 
@@ -365,6 +414,61 @@ impl Config {
         self.ssl_negotiation
     }
 
+    /// Sets the path to a file containing the certificate authorities used to verify the server's certificate.
+    pub fn ssl_root_cert<T>(&mut self, ssl_root_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_root_cert = Some(ssl_root_cert.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the root certificate file, if one has been configured with the `ssl_root_cert` method.
+    pub fn get_ssl_root_cert(&self) -> Option<&Path> {
+        self.ssl_root_cert.as_deref()
+    }
+
+    /// Sets the path to a file containing the client's certificate.
+    pub fn ssl_cert<T>(&mut self, ssl_cert: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_cert = Some(ssl_cert.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the client certificate file, if one has been configured with the `ssl_cert` method.
+    pub fn get_ssl_cert(&self) -> Option<&Path> {
+        self.ssl_cert.as_deref()
+    }
+
+    /// Sets the path to a file containing the client's private key.
+    pub fn ssl_key<T>(&mut self, ssl_key: T) -> &mut Config
+    where
+        T: AsRef<Path>,
+    {
+        self.ssl_key = Some(ssl_key.as_ref().to_path_buf());
+        self
+    }
+
+    /// Gets the path to the client private key file, if one has been configured with the `ssl_key` method.
+    pub fn get_ssl_key(&self) -> Option<&Path> {
+        self.ssl_key.as_deref()
+    }
+
+    /// Sets the Kerberos service name used to build the service principal name for GSSAPI authentication.
+    ///
+    /// Defaults to `postgres`.
+    pub fn krbsrvname(&mut self, krbsrvname: impl Into<String>) -> &mut Config {
+        self.krbsrvname = Some(krbsrvname.into());
+        self
+    }
+
+    /// Gets the Kerberos service name, if one has been configured with the `krbsrvname` method.
+    pub fn get_krbsrvname(&self) -> Option<&str> {
+        self.krbsrvname.as_deref()
+    }
+
     /// Adds a host to the configuration.
     ///
     /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
@@ -445,6 +549,35 @@ impl Config {
         self.connect_timeout.as_ref()
     }
 
+    /// Sets the number of times a failed connection attempt will be retried.
+    ///
+    /// Only transient failures are retried: failures to establish the socket, such as a refused connection or a
+    /// timeout, and the server reporting that it is not yet accepting connections. Other errors, such as
+    /// authentication failures, are returned immediately. If every attempt fails, the error from the last attempt is
+    /// returned. Defaults to 0.
+    pub fn connect_retries(&mut self, connect_retries: u32) -> &mut Config {
+        self.connect_retries = connect_retries;
+        self
+    }
+
+    /// Gets the number of connection retries.
+    pub fn get_connect_retries(&self) -> u32 {
+        self.connect_retries
+    }
+
+    /// Sets the delay before the first connection retry.
+    ///
+    /// The delay doubles after each subsequent failed attempt. Defaults to 100 milliseconds.
+    pub fn connect_retry_backoff(&mut self, connect_retry_backoff: Duration) -> &mut Config {
+        self.connect_retry_backoff = connect_retry_backoff;
+        self
+    }
+
+    /// Gets the delay before the first connection retry.
+    pub fn get_connect_retry_backoff(&self) -> Duration {
+        self.connect_retry_backoff
+    }
+
     /// Sets the TCP user timeout.
     ///
     /// This is ignored for Unix domain socket connections. It is only supported on systems where
@@ -521,6 +654,12 @@ impl Config {
         self.keepalive_config.retries
     }
 
+    /// Like `keepalives_retries`, under the name used by libpq.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn keepalives_count(&mut self, keepalives_count: u32) -> &mut Config {
+        self.keepalives_retries(keepalives_count)
+    }
+
     /// Sets the requirements of the session.
     ///
     /// This can be used to connect to the primary server in a clustered database rather than one of the read-only
@@ -538,6 +677,23 @@ impl Config {
         self.target_session_attrs
     }
 
+    /// Sets a query used to decide whether a server is acceptable.
+    ///
+    /// The query is run on each new connection after the `target_session_attrs` check, before the connection is
+    /// returned. It must produce a single boolean value. If it returns `false`, no rows, or fails, the server is
+    /// rejected and the next host is tried. This can be used for checks the built-in session requirements cannot
+    /// express, such as limiting the replication lag of a standby.
+    pub fn validation_query(&mut self, validation_query: impl Into<String>) -> &mut Config {
+        self.validation_query = Some(validation_query.into());
+        self
+    }
+
+    /// Gets the query used to decide whether a server is acceptable, if one has been configured with the
+    /// `validation_query` method.
+    pub fn get_validation_query(&self) -> Option<&str> {
+        self.validation_query.as_deref()
+    }
+
     /// Sets the channel binding behavior.
     ///
     /// Defaults to `prefer`.
@@ -564,6 +720,227 @@ impl Config {
         self.load_balance_hosts
     }
 
+    /// Sets a function which rewrites the text of queries before they are sent to the server.
+    ///
+    /// The rewriter is applied whenever the client prepares a statement, including the implicit preparation performed
+    /// when a query string is passed to methods like `Client::query`, and to the queries of `Client::query_typed` and
+    /// related methods. This makes it a central place for cross-cutting transformations such as adding a tenant
+    /// filter. The rewritten text is what the server parses, so parameter placeholders must remain valid.
+    ///
+    /// Queries sent with the simple query protocol, by `Client::simple_query` and `Client::batch_execute`, are not
+    /// rewritten, and neither are the queries the client issues internally, such as those loading type information or
+    /// starting transactions.
+    ///
+    /// The rewriter runs synchronously on the calling task each time a statement is prepared, so it should be cheap.
+    /// Statements prepared up front with `Client::prepare` are rewritten once, and are not rewritten again when they
+    /// are executed.
+    #[allow(clippy::type_complexity)]
+    pub fn query_rewriter(
+        &mut self,
+        rewriter: Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>,
+    ) -> &mut Config {
+        self.query_rewriter = Some(ByIdentity(rewriter));
+        self
+    }
+
+    /// Gets the query rewriter, if one has been configured with the `query_rewriter` method.
+    #[allow(clippy::type_complexity)]
+    pub fn get_query_rewriter(&self) -> Option<&Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>> {
+        self.query_rewriter.as_ref().map(|r| &r.0)
+    }
+
+    /// Sets the maximum number of rows that methods collecting a query's results into memory will buffer.
+    ///
+    /// This applies to `Client::query`, `Client::query_typed`, and the methods built on them. If a query returns more
+    /// rows than the limit, the rows read so far are discarded, a cancellation request for the query is sent to the
+    /// server, and once the server has stopped and is ready for the next query, an error for which
+    /// `Error::is_result_too_large` returns `true` is returned. The cancellation request is sent without TLS, and
+    /// requires the `runtime` Cargo feature; if it cannot be delivered, the rest of the result is read and discarded
+    /// before the error is returned. Inside of a transaction block, where cancelling the query would abort the
+    /// transaction, no cancellation request is sent, and the rest of the result is always read and discarded so that
+    /// the transaction can continue. Streaming methods like `Client::query_raw` are not limited.
+    ///
+    /// Defaults to no limit.
+    pub fn max_result_rows(&mut self, max_result_rows: usize) -> &mut Config {
+        self.max_result_rows = Some(max_result_rows);
+        self
+    }
+
+    /// Gets the maximum number of rows buffered by methods collecting a query's results, if one has been set.
+    pub fn get_max_result_rows(&self) -> Option<usize> {
+        self.max_result_rows
+    }
+
+    /// Sets the number of rows to fetch at a time from queries which return rows.
+    ///
+    /// Like `FETCH_COUNT` in `psql`, this makes `Client::query`, `Client::query_raw`, and the methods built on them
+    /// read the results of any statement which returns rows, such as a `SELECT`, through a portal in batches of
+    /// `fetch_count` rows, rather than having the server send the whole result at once. This bounds the number of rows
+    /// buffered between the server and the client, without changing any call sites, at the cost of a round trip per
+    /// batch.
+    ///
+    /// Portals only exist within a transaction, so unless the server reports that the session is already inside of a
+    /// transaction block, each such query is run in a transaction of its own, which is committed once all rows have
+    /// been read, and rolled back if the query fails or its `RowStream` is dropped early. Until then, any other
+    /// request made with the client fails with an error for which `Error::is_cursor_transaction` returns `true`,
+    /// rather than being run inside of that transaction, so the rows of such a query must be read in full or the
+    /// stream dropped before the client is used again.
+    ///
+    /// Unlike `psql`, which only does this for `SELECT` and `VALUES` queries, batching applies to any statement which
+    /// returns rows. A data-modifying statement with a `RETURNING` clause is therefore also run in a transaction of its
+    /// own, and its changes are rolled back if its `RowStream` is dropped before all of its rows have been read. Run
+    /// such statements inside of a `Transaction`, or read their rows in full, to keep their changes.
+    ///
+    /// Defaults to fetching all rows at once. A value of 0 also disables batching.
+    pub fn fetch_count(&mut self, fetch_count: usize) -> &mut Config {
+        self.fetch_count = Some(fetch_count);
+        self
+    }
+
+    /// Gets the number of rows fetched at a time from queries which return rows, if one has been set.
+    pub fn get_fetch_count(&self) -> Option<usize> {
+        self.fetch_count
+    }
+
+    /// Sets the number of prepared statements the client caches by their query text.
+    ///
+    /// When set, `Client::prepare_cached` and the methods which accept a query string rather than a `Statement`, such
+    /// as `Client::query`, reuse statements previously prepared for the same query, rather than preparing them again.
+    ///
+    /// Defaults to 0, which disables the cache.
+    pub fn statement_cache_capacity(&mut self, statement_cache_capacity: usize) -> &mut Config {
+        self.statement_cache_capacity = statement_cache_capacity;
+        self
+    }
+
+    /// Gets the number of prepared statements the client caches by their query text.
+    pub fn get_statement_cache_capacity(&self) -> usize {
+        self.statement_cache_capacity
+    }
+
+    /// Sets the number of user-defined types the client caches the definitions of.
+    ///
+    /// The definitions of types which are not built in are loaded from the database the first time they are used, and
+    /// cached for the lifetime of the client. A client which encounters many different types, such as one used with
+    /// many schemas, can cap the memory this uses by setting a capacity, in which case the least recently used type is
+    /// evicted once the cache is full, and loaded again if it is used later.
+    ///
+    /// Defaults to no limit.
+    pub fn type_cache_capacity(&mut self, type_cache_capacity: usize) -> &mut Config {
+        self.type_cache_capacity = Some(type_cache_capacity);
+        self
+    }
+
+    /// Gets the number of user-defined types the client caches the definitions of, if a limit has been set.
+    pub fn get_type_cache_capacity(&self) -> Option<usize> {
+        self.type_cache_capacity
+    }
+
+    /// Sets the number of batches of response messages the connection buffers for each request ahead of its consumer.
+    ///
+    /// The connection reads responses from the socket in batches, and hands each batch to the stream of the request
+    /// it belongs to. Once that stream has this many batches waiting to be consumed, the connection stops reading
+    /// from the socket until it catches up, so reading from the network is largely serialized with processing the
+    /// rows of a large query or `COPY` at the default capacity. Raising it lets more of the response be read ahead,
+    /// at the cost of holding more unprocessed batches in memory.
+    ///
+    /// Responses are read from a single socket in order, so a slow consumer still holds up the responses to later
+    /// requests.
+    ///
+    /// Defaults to 1.
+    pub fn response_buffer_capacity(&mut self, response_buffer_capacity: usize) -> &mut Config {
+        self.response_buffer_capacity = response_buffer_capacity;
+        self
+    }
+
+    /// Gets the number of batches of response messages the connection buffers for each request.
+    pub fn get_response_buffer_capacity(&self) -> usize {
+        self.response_buffer_capacity
+    }
+
+    /// Sets the maximum number of notifications buffered for each stream returned by `Client::notifications`.
+    ///
+    /// The connection buffers the notifications it receives until they are read from the stream, so a stream which
+    /// receives notifications faster than it is read would otherwise buffer an unbounded number of them. Once the
+    /// limit is reached, the oldest buffered notification is discarded to make room for each new one, and the number
+    /// of notifications discarded is available from `NotificationStream::dropped`. The synchronous `postgres` client
+    /// applies the same limit to its own buffer.
+    ///
+    /// Defaults to no limit.
+    pub fn max_buffered_notifications(&mut self, max_buffered_notifications: usize) -> &mut Config {
+        self.max_buffered_notifications = Some(max_buffered_notifications);
+        self
+    }
+
+    /// Gets the maximum number of notifications buffered for each notification stream, if one has been set.
+    pub fn get_max_buffered_notifications(&self) -> Option<usize> {
+        self.max_buffered_notifications
+    }
+
+    /// Sets a callback invoked for every protocol message exchanged with the server.
+    ///
+    /// The callback receives the direction of the message, its type byte, and its size in bytes including the type
+    /// byte and length header. It is called from the connection task, so it should return quickly. Messages exchanged
+    /// before TLS is established, such as the `SSLRequest`, are not reported.
+    ///
+    /// This is intended for debugging protocol issues and incompatibilities with connection poolers.
+    ///
+    /// Requires the `message-tracing` Cargo feature.
+    #[cfg(feature = "message-tracing")]
+    pub fn message_tracer(
+        &mut self,
+        tracer: Arc<dyn Fn(Direction, MessageTag, usize) + Send + Sync>,
+    ) -> &mut Config {
+        self.message_tracer = Some(ByIdentity(tracer));
+        self
+    }
+
+    /// Gets the message tracer, if one has been configured with the `message_tracer` method.
+    #[cfg(feature = "message-tracing")]
+    #[allow(clippy::type_complexity)]
+    pub fn get_message_tracer(
+        &self,
+    ) -> Option<&Arc<dyn Fn(Direction, MessageTag, usize) + Send + Sync>> {
+        self.message_tracer.as_ref().map(|t| &t.0)
+    }
+
+    /// Sets a callback creating the GSSAPI security context used when the server requests GSSAPI (Kerberos)
+    /// authentication.
+    ///
+    /// The callback receives the host-based service principal name of the server, formed from the `krbsrvname` and the
+    /// hostname being connected to, such as `postgres@db.example.com`. Connections without a hostname, such as those
+    /// made over Unix sockets, with only a `hostaddr`, or with `connect_raw`, cannot use GSSAPI authentication.
+    ///
+    /// Requires the `gssapi` Cargo feature.
+    #[cfg(feature = "gssapi")]
+    #[allow(clippy::type_complexity)]
+    pub fn gss_provider(
+        &mut self,
+        provider: Arc<
+            dyn Fn(&str) -> Result<Box<dyn GssContext>, Box<dyn error::Error + Sync + Send>>
+                + Send
+                + Sync,
+        >,
+    ) -> &mut Config {
+        self.gss_provider = Some(ByIdentity(provider));
+        self
+    }
+
+    /// Gets the GSSAPI context provider, if one has been configured with the `gss_provider` method.
+    #[cfg(feature = "gssapi")]
+    #[allow(clippy::type_complexity)]
+    pub fn get_gss_provider(
+        &self,
+    ) -> Option<
+        &Arc<
+            dyn Fn(&str) -> Result<Box<dyn GssContext>, Box<dyn error::Error + Sync + Send>>
+                + Send
+                + Sync,
+        >,
+    > {
+        self.gss_provider.as_ref().map(|p| &p.0)
+    }
+
     fn param(&mut self, key: &str, value: &str) -> Result<(), Error> {
         match key {
             "user" => {
@@ -602,6 +979,18 @@ impl Config {
                 };
                 self.ssl_negotiation(mode);
             }
+            "sslrootcert" => {
+                self.ssl_root_cert(value);
+            }
+            "sslcert" => {
+                self.ssl_cert(value);
+            }
+            "sslkey" => {
+                self.ssl_key(value);
+            }
+            "krbsrvname" => {
+                self.krbsrvname(value);
+            }
             "host" => {
                 for host in value.split(',') {
                     self.host(host);
@@ -674,11 +1063,19 @@ impl Config {
                 })?;
                 self.keepalives_retries(keepalives_retries);
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            "keepalives_count" => {
+                let keepalives_count = value
+                    .parse::<u32>()
+                    .map_err(|_| Error::config_parse(Box::new(InvalidValue("keepalives_count"))))?;
+                self.keepalives_count(keepalives_count);
+            }
             "target_session_attrs" => {
                 let target_session_attrs = match value {
                     "any" => TargetSessionAttrs::Any,
                     "read-write" => TargetSessionAttrs::ReadWrite,
                     "read-only" => TargetSessionAttrs::ReadOnly,
+                    "prefer-standby" => TargetSessionAttrs::PreferStandby,
                     _ => {
                         return Err(Error::config_parse(Box::new(InvalidValue(
                             "target_session_attrs",
@@ -745,7 +1142,7 @@ impl Config {
         S: AsyncRead + AsyncWrite + Unpin,
         T: TlsConnect<S>,
     {
-        connect_raw(stream, tls, true, self).await
+        connect_raw(stream, tls, true, None, self).await
     }
 }
 
@@ -778,10 +1175,17 @@ impl fmt::Debug for Config {
             .field("options", &self.options)
             .field("application_name", &self.application_name)
             .field("ssl_mode", &self.ssl_mode)
+            .field("ssl_negotiation", &self.ssl_negotiation)
+            .field("ssl_root_cert", &self.ssl_root_cert)
+            .field("ssl_cert", &self.ssl_cert)
+            .field("ssl_key", &self.ssl_key)
+            .field("krbsrvname", &self.krbsrvname)
             .field("host", &self.host)
             .field("hostaddr", &self.hostaddr)
             .field("port", &self.port)
             .field("connect_timeout", &self.connect_timeout)
+            .field("connect_retries", &self.connect_retries)
+            .field("connect_retry_backoff", &self.connect_retry_backoff)
             .field("tcp_user_timeout", &self.tcp_user_timeout)
             .field("keepalives", &self.keepalives);
 
@@ -793,14 +1197,64 @@ impl fmt::Debug for Config {
                 .field("keepalives_retries", &self.keepalive_config.retries);
         }
 
-        config_dbg
+        config_dbg = config_dbg
             .field("target_session_attrs", &self.target_session_attrs)
+            .field("validation_query", &self.validation_query)
             .field("channel_binding", &self.channel_binding)
             .field("load_balance_hosts", &self.load_balance_hosts)
-            .finish()
+            .field("query_rewriter", &self.query_rewriter)
+            .field("max_result_rows", &self.max_result_rows)
+            .field("fetch_count", &self.fetch_count)
+            .field("statement_cache_capacity", &self.statement_cache_capacity)
+            .field("response_buffer_capacity", &self.response_buffer_capacity)
+            .field("type_cache_capacity", &self.type_cache_capacity)
+            .field(
+                "max_buffered_notifications",
+                &self.max_buffered_notifications,
+            );
+
+        #[cfg(feature = "message-tracing")]
+        {
+            config_dbg = config_dbg.field("message_tracer", &self.message_tracer);
+        }
+
+        #[cfg(feature = "gssapi")]
+        {
+            config_dbg = config_dbg.field("gss_provider", &self.gss_provider);
+        }
+
+        config_dbg.finish()
     }
 }
 
+/// A shared value, such as a callback, which is compared by identity so that `Config` can remain `PartialEq`.
+pub(crate) struct ByIdentity<T: ?Sized>(pub(crate) Arc<T>);
+
+impl<T: ?Sized> Clone for ByIdentity<T> {
+    fn clone(&self) -> ByIdentity<T> {
+        ByIdentity(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> PartialEq for ByIdentity<T> {
+    fn eq(&self, other: &ByIdentity<T>) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: ?Sized> Eq for ByIdentity<T> {}
+
+impl<T: ?Sized> fmt::Debug for ByIdentity<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("ByIdentity(..)")
+    }
+}
+
+/// A callback rewriting the text of user queries.
+pub(crate) type QueryRewriteFn = dyn Fn(&str) -> Cow<'_, str> + Send + Sync;
+
+pub(crate) type QueryRewriter = ByIdentity<QueryRewriteFn>;
+
 #[derive(Debug)]
 struct UnknownOption(String);
 
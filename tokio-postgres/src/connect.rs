@@ -3,17 +3,41 @@ use crate::config::{Host, LoadBalanceHosts, TargetSessionAttrs};
 use crate::connect_raw::connect_raw;
 use crate::connect_socket::connect_socket;
 use crate::tls::MakeTlsConnect;
-use crate::{Client, Config, Connection, Error, SimpleQueryMessage, Socket};
+use crate::{Client, Config, Connection, Error, SimpleQueryMessage, SimpleQueryRow, Socket};
 use futures_util::{future, pin_mut, Future, FutureExt, Stream};
 use rand::seq::SliceRandom;
 use std::task::Poll;
 use std::{cmp, io};
-use tokio::net;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::{net, time};
 
 pub async fn connect<T>(
     mut tls: T,
     config: &Config,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    let mut attempt = 0;
+    loop {
+        match connect_attempt(&mut tls, config).await {
+            Ok(r) => return Ok(r),
+            Err(e) if attempt < config.connect_retries && e.is_transient_connect() => {
+                let backoff = config
+                    .connect_retry_backoff
+                    .saturating_mul(2u32.saturating_pow(attempt));
+                time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn connect_attempt<T>(
+    tls: &mut T,
+    config: &Config,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
 where
     T: MakeTlsConnect<Socket>,
 {
@@ -47,8 +71,27 @@ where
         indices.shuffle(&mut rand::rng());
     }
 
+    if config.target_session_attrs == TargetSessionAttrs::PreferStandby {
+        if let Ok(r) = connect_hosts(&indices, tls, config, TargetSessionAttrs::ReadOnly).await {
+            return Ok(r);
+        }
+        return connect_hosts(&indices, tls, config, TargetSessionAttrs::Any).await;
+    }
+
+    connect_hosts(&indices, tls, config, config.target_session_attrs).await
+}
+
+async fn connect_hosts<T>(
+    indices: &[usize],
+    tls: &mut T,
+    config: &Config,
+    target_session_attrs: TargetSessionAttrs,
+) -> Result<(Client, Connection<Socket, T::Stream>), Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
     let mut error = None;
-    for i in indices {
+    for &i in indices {
         let host = config.host.get(i);
         let hostaddr = config.hostaddr.get(i);
         let port = config
@@ -74,7 +117,7 @@ where
             None => host.cloned().unwrap(),
         };
 
-        match connect_host(addr, hostname, port, &mut tls, config).await {
+        match connect_host(addr, hostname, port, tls, config, target_session_attrs).await {
             Ok((client, connection)) => return Ok((client, connection)),
             Err(e) => error = Some(e),
         }
@@ -89,6 +132,7 @@ async fn connect_host<T>(
     port: u16,
     tls: &mut T,
     config: &Config,
+    target_session_attrs: TargetSessionAttrs,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
 where
     T: MakeTlsConnect<Socket>,
@@ -106,8 +150,15 @@ where
 
             let mut last_err = None;
             for addr in addrs {
-                match connect_once(Addr::Tcp(addr.ip()), hostname.as_deref(), port, tls, config)
-                    .await
+                match connect_once(
+                    Addr::Tcp(addr.ip()),
+                    hostname.as_deref(),
+                    port,
+                    tls,
+                    config,
+                    target_session_attrs,
+                )
+                .await
                 {
                     Ok(stream) => return Ok(stream),
                     Err(e) => {
@@ -126,7 +177,15 @@ where
         }
         #[cfg(unix)]
         Host::Unix(path) => {
-            connect_once(Addr::Unix(path), hostname.as_deref(), port, tls, config).await
+            connect_once(
+                Addr::Unix(path),
+                hostname.as_deref(),
+                port,
+                tls,
+                config,
+                target_session_attrs,
+            )
+            .await
         }
     }
 }
@@ -137,6 +196,7 @@ async fn connect_once<T>(
     port: u16,
     tls: &mut T,
     config: &Config,
+    target_session_attrs: TargetSessionAttrs,
 ) -> Result<(Client, Connection<Socket, T::Stream>), Error>
 where
     T: MakeTlsConnect<Socket>,
@@ -158,55 +218,40 @@ where
         .make_tls_connect(hostname.unwrap_or(""))
         .map_err(|e| Error::tls(e.into()))?;
     let has_hostname = hostname.is_some();
-    let (mut client, mut connection) = connect_raw(socket, tls, has_hostname, config).await?;
+    let (mut client, mut connection) =
+        connect_raw(socket, tls, has_hostname, hostname, config).await?;
 
-    if config.target_session_attrs != TargetSessionAttrs::Any {
-        let rows = client.simple_query_raw("SHOW transaction_read_only");
-        pin_mut!(rows);
-
-        let rows = future::poll_fn(|cx| {
-            if connection.poll_unpin(cx)?.is_ready() {
-                return Poll::Ready(Err(Error::closed()));
-            }
+    if target_session_attrs != TargetSessionAttrs::Any {
+        let row = query_first_row(&client, &mut connection, "SHOW transaction_read_only")
+            .await?
+            .ok_or_else(Error::unexpected_message)?;
+        let read_only_result = row.try_get(0)?;
+        if read_only_result == Some("on") && target_session_attrs == TargetSessionAttrs::ReadWrite {
+            return Err(Error::connect(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "database does not allow writes",
+            )));
+        } else if read_only_result == Some("off")
+            && target_session_attrs == TargetSessionAttrs::ReadOnly
+        {
+            return Err(Error::connect(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "database is not read only",
+            )));
+        }
+    }
 
-            rows.as_mut().poll(cx)
-        })
-        .await?;
-        pin_mut!(rows);
-
-        loop {
-            let next = future::poll_fn(|cx| {
-                if connection.poll_unpin(cx)?.is_ready() {
-                    return Poll::Ready(Some(Err(Error::closed())));
-                }
-
-                rows.as_mut().poll_next(cx)
-            });
-
-            match next.await.transpose()? {
-                Some(SimpleQueryMessage::Row(row)) => {
-                    let read_only_result = row.try_get(0)?;
-                    if read_only_result == Some("on")
-                        && config.target_session_attrs == TargetSessionAttrs::ReadWrite
-                    {
-                        return Err(Error::connect(io::Error::new(
-                            io::ErrorKind::PermissionDenied,
-                            "database does not allow writes",
-                        )));
-                    } else if read_only_result == Some("off")
-                        && config.target_session_attrs == TargetSessionAttrs::ReadOnly
-                    {
-                        return Err(Error::connect(io::Error::new(
-                            io::ErrorKind::PermissionDenied,
-                            "database is not read only",
-                        )));
-                    } else {
-                        break;
-                    }
-                }
-                Some(_) => {}
-                None => return Err(Error::unexpected_message()),
-            }
+    if let Some(validation_query) = &config.validation_query {
+        let row = query_first_row(&client, &mut connection, validation_query).await?;
+        let valid = match &row {
+            Some(row) => row.try_get(0)? == Some("t"),
+            None => false,
+        };
+        if !valid {
+            return Err(Error::connect(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "validation query rejected the server",
+            )));
         }
     }
 
@@ -225,3 +270,43 @@ where
 
     Ok((client, connection))
 }
+
+// Runs a simple query while driving the connection, returning the first row of its results.
+async fn query_first_row<S, T>(
+    client: &Client,
+    connection: &mut Connection<S, T>,
+    query: &str,
+) -> Result<Option<SimpleQueryRow>, Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let rows = client.simple_query_raw(query);
+    pin_mut!(rows);
+
+    let rows = future::poll_fn(|cx| {
+        if connection.poll_unpin(cx)?.is_ready() {
+            return Poll::Ready(Err(Error::closed()));
+        }
+
+        rows.as_mut().poll(cx)
+    })
+    .await?;
+    pin_mut!(rows);
+
+    loop {
+        let next = future::poll_fn(|cx| {
+            if connection.poll_unpin(cx)?.is_ready() {
+                return Poll::Ready(Some(Err(Error::closed())));
+            }
+
+            rows.as_mut().poll_next(cx)
+        });
+
+        match next.await.transpose()? {
+            Some(SimpleQueryMessage::Row(row)) => return Ok(Some(row)),
+            Some(_) => {}
+            None => return Ok(None),
+        }
+    }
+}
@@ -1,22 +1,30 @@
+use crate::auth_info::{AuthInfo, AuthMethod};
+use crate::client::BackendKey;
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
 use crate::config::{self, Config};
 use crate::connect_tls::connect_tls;
+use crate::connection::RequestStatus;
 use crate::maybe_tls_stream::MaybeTlsStream;
+use crate::notifications::Subscribers;
 use crate::tls::{TlsConnect, TlsStream};
 use crate::{Client, Connection, Error};
 use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
 use futures_channel::mpsc;
 use futures_util::{ready, Sink, SinkExt, Stream, TryStreamExt};
+use parking_lot::Mutex;
 use postgres_protocol::authentication;
 use postgres_protocol::authentication::sasl;
 use postgres_protocol::authentication::sasl::ScramSha256;
+#[cfg(feature = "gssapi")]
+use postgres_protocol::message::backend::AuthenticationGssContinueBody;
 use postgres_protocol::message::backend::{AuthenticationSaslBody, Message};
 use postgres_protocol::message::frontend;
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::io;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::codec::Framed;
@@ -83,6 +91,7 @@ pub async fn connect_raw<S, T>(
     stream: S,
     tls: T,
     has_hostname: bool,
+    hostname: Option<&str>,
     config: &Config,
 ) -> Result<(Client, Connection<S, T::Stream>), Error>
 where
@@ -98,8 +107,12 @@ where
     )
     .await?;
 
+    let codec = PostgresCodec::new();
+    #[cfg(feature = "message-tracing")]
+    let codec = codec.with_tracer(config.message_tracer.clone());
+
     let mut stream = StartupStream {
-        inner: Framed::new(stream, PostgresCodec),
+        inner: Framed::new(stream, codec),
         buf: BackendMessages::empty(),
         delayed: VecDeque::new(),
     };
@@ -110,18 +123,39 @@ where
         .map_or_else(|| Cow::Owned(whoami::username()), Cow::Borrowed);
 
     startup(&mut stream, config, &user).await?;
-    authenticate(&mut stream, config, &user).await?;
+    let auth_info = authenticate(&mut stream, config, &user, hostname).await?;
     let (process_id, secret_key, parameters) = read_info(&mut stream).await?;
 
+    let backend_key = Arc::new(Mutex::new(BackendKey {
+        process_id,
+        secret_key,
+    }));
+
+    let status = Arc::new(RequestStatus::new());
+    let server_parameters = Arc::new(Mutex::new(parameters));
+    let subscribers = Arc::new(Mutex::new(Subscribers::new(
+        config.max_buffered_notifications,
+    )));
+
     let (sender, receiver) = mpsc::unbounded();
     let client = Client::new(
         sender,
-        config.ssl_mode,
-        config.ssl_negotiation,
-        process_id,
-        secret_key,
+        config,
+        backend_key.clone(),
+        server_parameters.clone(),
+        subscribers.clone(),
+        status.clone(),
+        auth_info,
+    );
+    let connection = Connection::new(
+        stream.inner,
+        stream.delayed,
+        server_parameters,
+        backend_key,
+        subscribers,
+        status,
+        receiver,
     );
-    let connection = Connection::new(stream.inner, stream.delayed, parameters, receiver);
 
     Ok((client, connection))
 }
@@ -156,19 +190,21 @@ where
         .map_err(Error::io)
 }
 
+#[cfg_attr(not(feature = "gssapi"), allow(unused_variables))]
 async fn authenticate<S, T>(
     stream: &mut StartupStream<S, T>,
     config: &Config,
     user: &str,
-) -> Result<(), Error>
+    hostname: Option<&str>,
+) -> Result<AuthInfo, Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsStream + Unpin,
 {
-    match stream.try_next().await.map_err(Error::io)? {
+    let auth_info = match stream.try_next().await.map_err(Error::io)? {
         Some(Message::AuthenticationOk) => {
             can_skip_channel_binding(config)?;
-            return Ok(());
+            return Ok(AuthInfo::new(AuthMethod::None));
         }
         Some(Message::AuthenticationCleartextPassword) => {
             can_skip_channel_binding(config)?;
@@ -179,6 +215,7 @@ where
                 .ok_or_else(|| Error::config("password missing".into()))?;
 
             authenticate_password(stream, pass).await?;
+            AuthInfo::new(AuthMethod::Password)
         }
         Some(Message::AuthenticationMd5Password(body)) => {
             can_skip_channel_binding(config)?;
@@ -190,13 +227,25 @@ where
 
             let output = authentication::md5_hash(user.as_bytes(), pass, body.salt());
             authenticate_password(stream, output.as_bytes()).await?;
+            AuthInfo::new(AuthMethod::Md5)
         }
-        Some(Message::AuthenticationSasl(body)) => {
-            authenticate_sasl(stream, body, config).await?;
+        Some(Message::AuthenticationSasl(body)) => authenticate_sasl(stream, body, config).await?,
+        #[cfg(feature = "gssapi")]
+        Some(Message::AuthenticationGss) => {
+            can_skip_channel_binding(config)?;
+
+            authenticate_gss(stream, config, hostname).await?;
+            // `authenticate_gss` has already consumed the `AuthenticationOk` message.
+            return Ok(AuthInfo::new(AuthMethod::Gss));
+        }
+        #[cfg(not(feature = "gssapi"))]
+        Some(Message::AuthenticationGss) => {
+            return Err(Error::authentication(
+                "unsupported authentication method".into(),
+            ))
         }
         Some(Message::AuthenticationKerberosV5)
         | Some(Message::AuthenticationScmCredential)
-        | Some(Message::AuthenticationGss)
         | Some(Message::AuthenticationSspi) => {
             return Err(Error::authentication(
                 "unsupported authentication method".into(),
@@ -205,10 +254,10 @@ where
         Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
         Some(_) => return Err(Error::unexpected_message()),
         None => return Err(Error::closed()),
-    }
+    };
 
     match stream.try_next().await.map_err(Error::io)? {
-        Some(Message::AuthenticationOk) => Ok(()),
+        Some(Message::AuthenticationOk) => Ok(auth_info),
         Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
         Some(_) => Err(Error::unexpected_message()),
         None => Err(Error::closed()),
@@ -245,7 +294,7 @@ async fn authenticate_sasl<S, T>(
     stream: &mut StartupStream<S, T>,
     body: AuthenticationSaslBody,
     config: &Config,
-) -> Result<(), Error>
+) -> Result<AuthInfo, Error>
 where
     S: AsyncRead + AsyncWrite + Unpin,
     T: TlsStream + Unpin,
@@ -330,7 +379,65 @@ where
         .finish(body.data())
         .map_err(|e| Error::authentication(e.into()))?;
 
-    Ok(())
+    let method = if mechanism == sasl::SCRAM_SHA_256_PLUS {
+        AuthMethod::ScramSha256Plus
+    } else {
+        AuthMethod::ScramSha256
+    };
+    Ok(AuthInfo {
+        method,
+        scram_iterations: scram.iteration_count(),
+    })
+}
+
+#[cfg(feature = "gssapi")]
+async fn authenticate_gss<S, T>(
+    stream: &mut StartupStream<S, T>,
+    config: &Config,
+    hostname: Option<&str>,
+) -> Result<(), Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let provider = config.gss_provider.as_ref().ok_or_else(|| {
+        Error::authentication(
+            "server requested GSSAPI authentication but no provider is configured".into(),
+        )
+    })?;
+    let hostname = hostname
+        .ok_or_else(|| Error::authentication("GSSAPI authentication requires a hostname".into()))?;
+    let service = config.krbsrvname.as_deref().unwrap_or("postgres");
+
+    let mut context = (provider.0)(&format!("{}@{}", service, hostname)).map_err(|e| {
+        Error::authentication(format!("could not create GSSAPI context: {}", e).into())
+    })?;
+    let mut token = None;
+
+    loop {
+        let output = context
+            .step(token.as_ref().map(AuthenticationGssContinueBody::data))
+            .map_err(|e| {
+                Error::authentication(format!("could not establish GSSAPI context: {}", e).into())
+            })?;
+
+        if let Some(output) = output.filter(|output| !output.is_empty()) {
+            let mut buf = BytesMut::new();
+            frontend::gss_response(&output, &mut buf).map_err(Error::encode)?;
+            stream
+                .send(FrontendMessage::Raw(buf.freeze()))
+                .await
+                .map_err(Error::io)?;
+        }
+
+        match stream.try_next().await.map_err(Error::io)? {
+            Some(Message::AuthenticationGssContinue(body)) => token = Some(body),
+            Some(Message::AuthenticationOk) => return Ok(()),
+            Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
+            Some(_) => return Err(Error::unexpected_message()),
+            None => return Err(Error::closed()),
+        }
+    }
 }
 
 async fn read_info<S, T>(
@@ -1,18 +1,23 @@
+use crate::client::BackendKey;
 use crate::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
 use crate::copy_in::CopyInReceiver;
 use crate::error::DbError;
 use crate::maybe_tls_stream::MaybeTlsStream;
+use crate::notifications::Subscribers;
 use crate::{AsyncMessage, Error, Notification};
 use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
 use futures_channel::mpsc;
 use futures_util::{ready, stream::FusedStream, Sink, Stream, StreamExt};
 use log::{info, trace};
+use parking_lot::Mutex;
 use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
 use std::collections::{HashMap, VecDeque};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::codec::Framed;
@@ -25,10 +30,49 @@ pub enum RequestMessages {
 pub struct Request {
     pub messages: RequestMessages,
     pub sender: mpsc::Sender<BackendMessages>,
+    /// Receives the notices emitted while the request runs, rather than them being returned as `AsyncMessage`s.
+    pub notices: Option<mpsc::UnboundedSender<DbError>>,
 }
 
 pub struct Response {
     sender: mpsc::Sender<BackendMessages>,
+    notices: Option<mpsc::UnboundedSender<DbError>>,
+}
+
+/// The progress of a connection's requests, shared with the `Client`.
+#[derive(Debug)]
+pub(crate) struct RequestStatus {
+    /// The number of requests the connection has received all responses for.
+    completed_requests: AtomicU64,
+    /// The transaction status reported by the last `ReadyForQuery` message.
+    transaction_status: AtomicU8,
+}
+
+impl RequestStatus {
+    pub(crate) fn new() -> RequestStatus {
+        RequestStatus {
+            completed_requests: AtomicU64::new(0),
+            transaction_status: AtomicU8::new(b'I'),
+        }
+    }
+
+    pub(crate) fn completed_requests(&self) -> u64 {
+        self.completed_requests.load(Ordering::Acquire)
+    }
+
+    /// Determines if the server reported the session to be inside of a transaction block, failed or not, when it
+    /// last became ready for a query.
+    pub(crate) fn in_transaction(&self) -> bool {
+        self.transaction_status.load(Ordering::Acquire) != b'I'
+    }
+
+    fn complete_request(&self) {
+        self.completed_requests.fetch_add(1, Ordering::Release);
+    }
+
+    fn set_transaction_status(&self, status: u8) {
+        self.transaction_status.store(status, Ordering::Release);
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -48,7 +92,12 @@ enum State {
 #[must_use = "futures do nothing unless polled"]
 pub struct Connection<S, T> {
     stream: Framed<MaybeTlsStream<S, T>, PostgresCodec>,
-    parameters: HashMap<String, String>,
+    /// The parameters reported by the server, shared with the `Client`.
+    server_parameters: Arc<Mutex<HashMap<String, String>>>,
+    backend_key: Arc<Mutex<BackendKey>>,
+    /// The notification streams created by the `Client`.
+    subscribers: Arc<Mutex<Subscribers>>,
+    status: Arc<RequestStatus>,
     receiver: mpsc::UnboundedReceiver<Request>,
     pending_request: Option<RequestMessages>,
     pending_responses: VecDeque<BackendMessage>,
@@ -64,12 +113,18 @@ where
     pub(crate) fn new(
         stream: Framed<MaybeTlsStream<S, T>, PostgresCodec>,
         pending_responses: VecDeque<BackendMessage>,
-        parameters: HashMap<String, String>,
+        server_parameters: Arc<Mutex<HashMap<String, String>>>,
+        backend_key: Arc<Mutex<BackendKey>>,
+        subscribers: Arc<Mutex<Subscribers>>,
+        status: Arc<RequestStatus>,
         receiver: mpsc::UnboundedReceiver<Request>,
     ) -> Connection<S, T> {
         Connection {
             stream,
-            parameters,
+            server_parameters,
+            backend_key,
+            subscribers,
+            status,
             receiver,
             pending_request: None,
             pending_responses,
@@ -111,6 +166,15 @@ where
             let (mut messages, request_complete) = match message {
                 BackendMessage::Async(Message::NoticeResponse(body)) => {
                     let error = DbError::parse(&mut body.fields()).map_err(Error::parse)?;
+                    // Responses are received in order, so the notice belongs to the oldest outstanding request.
+                    let notices = self.responses.front().and_then(|r| r.notices.as_ref());
+                    let error = match notices {
+                        Some(notices) => match notices.unbounded_send(error) {
+                            Ok(()) => continue,
+                            Err(e) => e.into_inner(),
+                        },
+                        None => error,
+                    };
                     return Ok(Some(AsyncMessage::Notice(error)));
                 }
                 BackendMessage::Async(Message::NotificationResponse(body)) => {
@@ -119,13 +183,20 @@ where
                         channel: body.channel().map_err(Error::parse)?.to_string(),
                         payload: body.message().map_err(Error::parse)?.to_string(),
                     };
+                    self.subscribers.lock().notify(&notification);
                     return Ok(Some(AsyncMessage::Notification(notification)));
                 }
                 BackendMessage::Async(Message::ParameterStatus(body)) => {
-                    self.parameters.insert(
-                        body.name().map_err(Error::parse)?.to_string(),
-                        body.value().map_err(Error::parse)?.to_string(),
-                    );
+                    let name = body.name().map_err(Error::parse)?.to_string();
+                    let value = body.value().map_err(Error::parse)?.to_string();
+                    self.server_parameters.lock().insert(name, value);
+                    continue;
+                }
+                BackendMessage::Async(Message::BackendKeyData(body)) => {
+                    *self.backend_key.lock() = BackendKey {
+                        process_id: body.process_id(),
+                        secret_key: body.secret_key(),
+                    };
                     continue;
                 }
                 BackendMessage::Async(_) => unreachable!(),
@@ -143,16 +214,27 @@ where
                 },
             };
 
+            if request_complete {
+                // Record the status before the client sees the response, so it is current once the request resolves.
+                if let Some(status) = messages.transaction_status() {
+                    self.status.set_transaction_status(status);
+                }
+            }
+
             match response.sender.poll_ready(cx) {
                 Poll::Ready(Ok(())) => {
                     let _ = response.sender.start_send(messages);
-                    if !request_complete {
+                    if request_complete {
+                        self.status.complete_request();
+                    } else {
                         self.responses.push_front(response);
                     }
                 }
                 Poll::Ready(Err(_)) => {
                     // we need to keep paging through the rest of the messages even if the receiver's hung up
-                    if !request_complete {
+                    if request_complete {
+                        self.status.complete_request();
+                    } else {
                         self.responses.push_front(response);
                     }
                 }
@@ -184,6 +266,7 @@ where
                 trace!("polled new request");
                 self.responses.push_back(Response {
                     sender: request.sender,
+                    notices: request.notices,
                 });
                 Poll::Ready(Some(request.messages))
             }
@@ -294,8 +377,10 @@ where
     }
 
     /// Returns the value of a runtime parameter for this connection.
-    pub fn parameter(&self, name: &str) -> Option<&str> {
-        self.parameters.get(name).map(|s| &**s)
+    ///
+    /// This reads the same parameters as `Client::server_parameter`, so the value is copied for the same reason.
+    pub fn parameter(&self, name: &str) -> Option<String> {
+        self.server_parameters.lock().get(name).cloned()
     }
 
     /// Polls for asynchronous messages from the server.
@@ -308,6 +393,17 @@ where
     pub fn poll_message(
         &mut self,
         cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<AsyncMessage, Error>>> {
+        let r = self.poll_message_inner(cx);
+        if let Poll::Ready(None | Some(Err(_))) = r {
+            self.subscribers.lock().close();
+        }
+        r
+    }
+
+    fn poll_message_inner(
+        &mut self,
+        cx: &mut Context<'_>,
     ) -> Poll<Option<Result<AsyncMessage, Error>>> {
         let message = self.poll_read(cx)?;
         let want_flush = self.poll_write(cx)?;
@@ -325,6 +421,12 @@ where
     }
 }
 
+impl<S, T> Drop for Connection<S, T> {
+    fn drop(&mut self) {
+        self.subscribers.lock().close();
+    }
+}
+
 impl<S, T> Future for Connection<S, T>
 where
     S: AsyncRead + AsyncWrite + Unpin,
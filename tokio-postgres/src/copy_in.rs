@@ -1,6 +1,7 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
+use crate::error::CopyProgress;
 use crate::query::extract_row_affected;
 use crate::{query, slice_iter, Error, Statement};
 use bytes::{Buf, BufMut, BytesMut};
@@ -14,10 +15,16 @@ use postgres_protocol::message::frontend::CopyData;
 use std::marker::{PhantomData, PhantomPinned};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+#[cfg(feature = "runtime")]
+use std::time::Duration;
+#[cfg(feature = "runtime")]
+use tokio::time::Instant;
 
-enum CopyInMessage {
+pub(crate) enum CopyInMessage {
     Message(FrontendMessage),
     Done,
+    /// The server never entered copy mode, so there is nothing to terminate.
+    Abandon,
 }
 
 pub struct CopyInReceiver {
@@ -26,7 +33,7 @@ pub struct CopyInReceiver {
 }
 
 impl CopyInReceiver {
-    fn new(receiver: mpsc::Receiver<CopyInMessage>) -> CopyInReceiver {
+    pub(crate) fn new(receiver: mpsc::Receiver<CopyInMessage>) -> CopyInReceiver {
         CopyInReceiver {
             receiver,
             done: false,
@@ -51,6 +58,10 @@ impl Stream for CopyInReceiver {
                 frontend::sync(&mut buf);
                 Poll::Ready(Some(FrontendMessage::Raw(buf.freeze())))
             }
+            Some(CopyInMessage::Abandon) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
             None => {
                 self.done = true;
                 let mut buf = BytesMut::new();
@@ -62,7 +73,52 @@ impl Stream for CopyInReceiver {
     }
 }
 
-enum SinkState {
+pub(crate) const DEFAULT_FLUSH_THRESHOLD: usize = 4096;
+
+#[cfg(feature = "runtime")]
+struct FlushInterval {
+    interval: Duration,
+    // When the oldest data in the buffer must be sent by, if there is any.
+    deadline: Option<Instant>,
+}
+
+#[cfg(feature = "runtime")]
+impl FlushInterval {
+    fn buffered(&mut self) {
+        if self.deadline.is_none() {
+            self.deadline = Some(Instant::now() + self.interval);
+        }
+    }
+
+    fn sent(&mut self) {
+        self.deadline = None;
+    }
+
+    fn is_due(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| deadline <= Instant::now())
+    }
+}
+
+#[cfg(not(feature = "runtime"))]
+enum FlushInterval {}
+
+#[cfg(not(feature = "runtime"))]
+impl FlushInterval {
+    fn buffered(&mut self) {
+        match *self {}
+    }
+
+    fn sent(&mut self) {
+        match *self {}
+    }
+
+    fn is_due(&self) -> bool {
+        match *self {}
+    }
+}
+
+pub(crate) enum SinkState {
     Active,
     Closing,
     Reading,
@@ -78,7 +134,12 @@ pin_project! {
         sender: mpsc::Sender<CopyInMessage>,
         responses: Responses,
         buf: BytesMut,
+        flush_threshold: usize,
+        flush_interval: Option<FlushInterval>,
         state: SinkState,
+        bytes_sent: u64,
+        rows_sent: u64,
+        buffered_rows: u64,
         #[pin]
         _p: PhantomPinned,
         _p2: PhantomData<T>,
@@ -91,6 +152,14 @@ where
 {
     /// A poll-based version of `finish`.
     pub fn poll_finish(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<u64, Error>> {
+        let r = ready!(self.as_mut().poll_finish_inner(cx));
+        Poll::Ready(r.map_err(|e| e.with_copy_progress(self.progress())))
+    }
+
+    fn poll_finish_inner(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<u64, Error>> {
         loop {
             match self.state {
                 SinkState::Active => {
@@ -123,64 +192,162 @@ where
 
     /// Completes the copy, returning the number of rows inserted.
     ///
+    /// If the copy fails, the progress it had made is available from the error via `Error::copy_progress`. The same
+    /// applies to errors returned while writing to or flushing the sink.
+    ///
     /// The `Sink::close` method is equivalent to `finish`, except that it does not return the
     /// number of rows.
     pub async fn finish(mut self: Pin<&mut Self>) -> Result<u64, Error> {
         future::poll_fn(|cx| self.as_mut().poll_finish(cx)).await
     }
-}
 
-impl<T> Sink<T> for CopyInSink<T>
-where
-    T: Buf + 'static + Send,
-{
-    type Error = Error;
+    /// Sends any buffered data to the server.
+    ///
+    /// Small items are normally buffered until more than the flush threshold has accumulated, which favors throughput.
+    /// Producers which generate data incrementally can call this method to make sure what they have written so far
+    /// reaches the server promptly. It is equivalent to `Sink::poll_flush`.
+    pub async fn flush(mut self: Pin<&mut Self>) -> Result<(), Error> {
+        future::poll_fn(|cx| Sink::<T>::poll_flush(self.as_mut(), cx)).await
+    }
 
-    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
-        self.project()
-            .sender
-            .poll_ready(cx)
-            .map_err(|_| Error::closed())
+    /// Sets the number of bytes which may be buffered before data is sent to the server.
+    ///
+    /// Items are accumulated in a buffer until it holds more than `threshold` bytes, at which point it is sent
+    /// automatically. Items larger than the threshold are sent without being copied into the buffer. A threshold of
+    /// 0 sends every item as soon as it is written, trading throughput for latency.
+    ///
+    /// Defaults to 4096.
+    pub fn set_flush_threshold(self: Pin<&mut Self>, threshold: usize) {
+        *self.project().flush_threshold = threshold;
+    }
+
+    /// Sets the longest time data may be buffered before it is sent to the server.
+    ///
+    /// The flush threshold bounds how much data is buffered, but a producer which writes small items slowly may take a
+    /// long time to fill it. With an interval set, the buffer is also sent once its oldest data has been held for
+    /// `interval`. The interval is checked whenever the sink is written to, so while the producer is idle, buffered
+    /// data is only sent by a flush. `SinkExt::send_all` flushes the sink whenever its source stream has no item ready.
+    ///
+    /// Defaults to `None`, which only sends buffered data once the threshold is exceeded or the sink is flushed.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn set_flush_interval(self: Pin<&mut Self>, interval: Option<Duration>) {
+        *self.project().flush_interval = interval.map(|interval| FlushInterval {
+            interval,
+            deadline: None,
+        });
+    }
+
+    fn progress(&self) -> CopyProgress {
+        CopyProgress::new(self.bytes_sent, self.rows_sent)
+    }
+
+    fn flush_due(&self) -> bool {
+        self.flush_interval.as_ref().is_some_and(|i| i.is_due())
+    }
+
+    /// Sends the buffered data to the server, if there is any.
+    fn poll_send_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let mut this = self.project();
+
+        if !this.buf.is_empty() {
+            ready!(this.sender.as_mut().poll_ready(cx)).map_err(|_| Error::closed())?;
+            let data: Box<dyn Buf + Send> = Box::new(this.buf.split().freeze());
+            let rows = std::mem::take(this.buffered_rows);
+            let len = data.remaining() as u64;
+            let data = CopyData::new(data).map_err(Error::encode)?;
+            this.sender
+                .start_send(CopyInMessage::Message(FrontendMessage::CopyData(data)))
+                .map_err(|_| Error::closed())?;
+            *this.bytes_sent += len;
+            *this.rows_sent += rows;
+        }
+        if let Some(flush_interval) = this.flush_interval {
+            flush_interval.sent();
+        }
+
+        Poll::Ready(Ok(()))
     }
 
-    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Error> {
+    fn start_send_inner(self: Pin<&mut Self>, item: T) -> Result<(), Error> {
+        let flush_due = self.flush_due();
         let this = self.project();
 
-        let data: Box<dyn Buf + Send> = if item.remaining() > 4096 {
+        let (data, rows): (Box<dyn Buf + Send>, u64) = if item.remaining() > *this.flush_threshold {
             if this.buf.is_empty() {
-                Box::new(item)
+                (Box::new(item), 1)
             } else {
-                Box::new(this.buf.split().freeze().chain(item))
+                let data = this.buf.split().freeze().chain(item);
+                (Box::new(data), *this.buffered_rows + 1)
             }
         } else {
             this.buf.put(item);
-            if this.buf.len() > 4096 {
-                Box::new(this.buf.split().freeze())
+            *this.buffered_rows += 1;
+            if this.buf.len() > *this.flush_threshold || flush_due {
+                (Box::new(this.buf.split().freeze()), *this.buffered_rows)
             } else {
+                if let Some(flush_interval) = this.flush_interval {
+                    flush_interval.buffered();
+                }
                 return Ok(());
             }
         };
+        *this.buffered_rows = 0;
+        if let Some(flush_interval) = this.flush_interval {
+            flush_interval.sent();
+        }
 
+        let len = data.remaining() as u64;
         let data = CopyData::new(data).map_err(Error::encode)?;
         this.sender
             .start_send(CopyInMessage::Message(FrontendMessage::CopyData(data)))
+            .map_err(|_| Error::closed())?;
+        *this.bytes_sent += len;
+        *this.rows_sent += rows;
+        Ok(())
+    }
+
+    fn poll_ready_inner(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        if self.flush_due() {
+            ready!(self.as_mut().poll_send_buf(cx))?;
+        }
+
+        self.project()
+            .sender
+            .poll_ready(cx)
             .map_err(|_| Error::closed())
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
-        let mut this = self.project();
+    fn poll_flush_inner(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        ready!(self.as_mut().poll_send_buf(cx))?;
 
-        if !this.buf.is_empty() {
-            ready!(this.sender.as_mut().poll_ready(cx)).map_err(|_| Error::closed())?;
-            let data: Box<dyn Buf + Send> = Box::new(this.buf.split().freeze());
-            let data = CopyData::new(data).map_err(Error::encode)?;
-            this.sender
-                .as_mut()
-                .start_send(CopyInMessage::Message(FrontendMessage::CopyData(data)))
-                .map_err(|_| Error::closed())?;
-        }
+        self.project()
+            .sender
+            .poll_flush(cx)
+            .map_err(|_| Error::closed())
+    }
+}
+
+impl<T> Sink<T> for CopyInSink<T>
+where
+    T: Buf + 'static + Send,
+{
+    type Error = Error;
 
-        this.sender.poll_flush(cx).map_err(|_| Error::closed())
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let r = ready!(self.as_mut().poll_ready_inner(cx));
+        Poll::Ready(r.map_err(|e| e.with_copy_progress(self.progress())))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: T) -> Result<(), Error> {
+        let r = self.as_mut().start_send_inner(item);
+        r.map_err(|e| e.with_copy_progress(self.progress()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        let r = ready!(self.as_mut().poll_flush_inner(cx));
+        Poll::Ready(r.map_err(|e| e.with_copy_progress(self.progress())))
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
@@ -205,22 +372,34 @@ where
         .await
         .map_err(|_| Error::closed())?;
 
-    match responses.next().await? {
-        Message::BindComplete => {}
-        _ => return Err(Error::unexpected_message()),
-    }
-
-    match responses.next().await? {
-        Message::CopyInResponse(_) => {}
-        _ => return Err(Error::unexpected_message()),
+    if let Err(e) = start(&mut responses).await {
+        let _ = sender.send(CopyInMessage::Abandon).await;
+        return Err(e);
     }
 
     Ok(CopyInSink {
         sender,
         responses,
         buf: BytesMut::new(),
+        flush_threshold: DEFAULT_FLUSH_THRESHOLD,
+        flush_interval: None,
         state: SinkState::Active,
+        bytes_sent: 0,
+        rows_sent: 0,
+        buffered_rows: 0,
         _p: PhantomPinned,
         _p2: PhantomData,
     })
 }
+
+pub(crate) async fn start(responses: &mut Responses) -> Result<(), Error> {
+    match responses.next().await? {
+        Message::BindComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    match responses.next().await? {
+        Message::CopyInResponse(_) => Ok(()),
+        _ => Err(Error::copy_expected()),
+    }
+}
@@ -3,12 +3,18 @@ use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::{query, slice_iter, Error, Statement};
 use bytes::Bytes;
+#[cfg(feature = "digest")]
+use digest::{Digest, Output};
 use futures_util::{ready, Stream};
 use log::debug;
+#[cfg(feature = "digest")]
+use parking_lot::Mutex;
 use pin_project_lite::pin_project;
 use postgres_protocol::message::backend::Message;
 use std::marker::PhantomPinned;
 use std::pin::Pin;
+#[cfg(feature = "digest")]
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 pub async fn copy_out(client: &InnerClient, statement: Statement) -> Result<CopyOutStream, Error> {
@@ -32,7 +38,7 @@ async fn start(client: &InnerClient, buf: Bytes) -> Result<Responses, Error> {
 
     match responses.next().await? {
         Message::CopyOutResponse(_) => {}
-        _ => return Err(Error::unexpected_message()),
+        _ => return Err(Error::copy_expected()),
     }
 
     Ok(responses)
@@ -60,3 +66,98 @@ impl Stream for CopyOutStream {
         }
     }
 }
+
+impl CopyOutStream {
+    /// Wraps the stream so that each chunk of data is fed into a digest of type `D`, such as a SHA-256 hasher.
+    ///
+    /// The returned `DigestHandle` provides the final hash once the returned stream has been consumed to completion.
+    /// If the stream ends with an error or is dropped early, no hash is produced.
+    ///
+    /// Requires the `digest` feature.
+    #[cfg(feature = "digest")]
+    pub fn with_digest<D>(self) -> (DigestStream<D>, DigestHandle<D>)
+    where
+        D: Digest,
+    {
+        let handle = DigestHandle {
+            output: Arc::new(Mutex::new(None)),
+        };
+        let stream = DigestStream {
+            stream: self,
+            digest: Some(D::new()),
+            output: handle.output.clone(),
+        };
+        (stream, handle)
+    }
+}
+
+#[cfg(feature = "digest")]
+pin_project! {
+    /// A stream of `COPY ... TO STDOUT` query data which computes a digest of the data as it is read.
+    ///
+    /// Created by `CopyOutStream::with_digest`.
+    pub struct DigestStream<D>
+    where
+        D: Digest,
+    {
+        #[pin]
+        stream: CopyOutStream,
+        digest: Option<D>,
+        output: Arc<Mutex<Option<Output<D>>>>,
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<D> Stream for DigestStream<D>
+where
+    D: Digest,
+{
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        match ready!(this.stream.poll_next(cx)) {
+            Some(Ok(chunk)) => {
+                if let Some(digest) = this.digest {
+                    digest.update(&chunk);
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Some(Err(e)) => {
+                *this.digest = None;
+                Poll::Ready(Some(Err(e)))
+            }
+            None => {
+                if let Some(digest) = this.digest.take() {
+                    *this.output.lock() = Some(digest.finalize());
+                }
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+/// A handle to the digest computed by a `DigestStream`.
+///
+/// Requires the `digest` feature.
+#[cfg(feature = "digest")]
+pub struct DigestHandle<D>
+where
+    D: Digest,
+{
+    output: Arc<Mutex<Option<Output<D>>>>,
+}
+
+#[cfg(feature = "digest")]
+impl<D> DigestHandle<D>
+where
+    D: Digest,
+{
+    /// Returns the digest of all data read from the stream.
+    ///
+    /// Returns `None` until the stream has been consumed to completion, and forever if it ended with an error.
+    pub fn digest(&self) -> Option<Output<D>> {
+        self.output.lock().clone()
+    }
+}
@@ -0,0 +1,158 @@
+//! CSV export of query results.
+//!
+//! See [`RowStream::write_csv`] for details.
+//!
+//! [`RowStream::write_csv`]: crate::RowStream::write_csv
+
+use crate::types::Type;
+use crate::{Column, Error, Row};
+use std::borrow::Cow;
+use std::fmt::{self, Write as _};
+use std::io;
+
+pub(crate) fn write_header<W>(w: &mut W, columns: &[Column]) -> Result<(), Error>
+where
+    W: io::Write,
+{
+    let mut record = vec![];
+    for (idx, column) in columns.iter().enumerate() {
+        if idx > 0 {
+            record.push(b',');
+        }
+        push_field(&mut record, column.name().as_bytes());
+    }
+    record.push(b'\n');
+
+    w.write_all(&record).map_err(Error::io)
+}
+
+pub(crate) fn write_row<W>(w: &mut W, row: &Row, record: &mut Vec<u8>) -> Result<(), Error>
+where
+    W: io::Write,
+{
+    record.clear();
+    for idx in 0..row.len() {
+        if idx > 0 {
+            record.push(b',');
+        }
+        // NULL is written as an empty unquoted field, the same as Postgres's own CSV format.
+        if let Some(text) = value_text(row, idx)? {
+            push_field(record, &text);
+        }
+    }
+    record.push(b'\n');
+
+    w.write_all(record).map_err(Error::io)
+}
+
+fn push_field(record: &mut Vec<u8>, field: &[u8]) {
+    // Empty strings are quoted to distinguish them from NULL.
+    let quote = field.is_empty()
+        || field
+            .iter()
+            .any(|b| matches!(b, b',' | b'"' | b'\r' | b'\n'));
+
+    if !quote {
+        record.extend_from_slice(field);
+        return;
+    }
+
+    record.push(b'"');
+    for &b in field {
+        if b == b'"' {
+            record.push(b'"');
+        }
+        record.push(b);
+    }
+    record.push(b'"');
+}
+
+fn value_text(row: &Row, idx: usize) -> Result<Option<Cow<'_, [u8]>>, Error> {
+    let raw = match row.col_buffer(idx) {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
+
+    let text = match *row.columns()[idx].type_() {
+        Type::BOOL => Cow::Borrowed(if row.try_get::<_, bool>(idx)? {
+            &b"t"[..]
+        } else {
+            &b"f"[..]
+        }),
+        Type::INT2 => display(row.try_get::<_, i16>(idx)?),
+        Type::INT4 => display(row.try_get::<_, i32>(idx)?),
+        Type::INT8 => display(row.try_get::<_, i64>(idx)?),
+        Type::OID => display(row.try_get::<_, u32>(idx)?),
+        // FLT_DIG and DBL_DIG, past which Postgres switches to exponent notation.
+        Type::FLOAT4 => float(row.try_get::<_, f32>(idx)?, 6),
+        Type::FLOAT8 => float(row.try_get::<_, f64>(idx)?, 15),
+        // The binary representations of these types are their text.
+        Type::TEXT
+        | Type::VARCHAR
+        | Type::BPCHAR
+        | Type::NAME
+        | Type::CHAR
+        | Type::UNKNOWN
+        | Type::JSON
+        | Type::XML => Cow::Borrowed(raw),
+        Type::JSONB => match raw.split_first() {
+            Some((1, json)) => Cow::Borrowed(json),
+            _ => {
+                return Err(Error::from_sql(
+                    "unsupported JSONB encoding version".into(),
+                    idx,
+                ))
+            }
+        },
+        Type::BYTEA => {
+            let mut hex = String::with_capacity(2 + raw.len() * 2);
+            hex.push_str("\\x");
+            for b in raw {
+                write!(hex, "{:02x}", b).unwrap();
+            }
+            Cow::Owned(hex.into_bytes())
+        }
+        ref ty => {
+            return Err(Error::from_sql(
+                format!("type {} cannot be written as CSV", ty).into(),
+                idx,
+            ))
+        }
+    };
+
+    Ok(Some(text))
+}
+
+fn display<T>(value: T) -> Cow<'static, [u8]>
+where
+    T: fmt::Display,
+{
+    Cow::Owned(value.to_string().into_bytes())
+}
+
+/// Formats a float the way Postgres does with its default `extra_float_digits` setting.
+///
+/// Both use the shortest representation which round-trips, but Postgres switches to exponent notation, with at least
+/// two exponent digits, when the decimal exponent is below -4 or at least `digits`.
+fn float<T>(value: T, digits: i32) -> Cow<'static, [u8]>
+where
+    T: fmt::Display + fmt::LowerExp,
+{
+    let exp = format!("{:e}", value);
+    let text = match exp.split_once('e') {
+        Some((mantissa, exponent)) => {
+            let exponent = exponent.parse::<i32>().unwrap();
+            if exponent < -4 || exponent >= digits {
+                let sign = if exponent < 0 { '-' } else { '+' };
+                format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+            } else {
+                value.to_string()
+            }
+        }
+        // Match the spelling Postgres uses for the special values.
+        None if exp == "inf" => "Infinity".to_string(),
+        None if exp == "-inf" => "-Infinity".to_string(),
+        None => exp,
+    };
+    Cow::Owned(text.into_bytes())
+}
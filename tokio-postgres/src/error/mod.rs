@@ -239,6 +239,8 @@ impl DbError {
 
     /// An optional error cursor position into either the original query string
     /// or an internally generated query.
+    ///
+    /// Positions are 1-based indexes measured in characters, not bytes.
     pub fn position(&self) -> Option<&ErrorPosition> {
         self.position.as_ref()
     }
@@ -317,6 +319,14 @@ impl fmt::Display for DbError {
         if let Some(hint) = &self.hint {
             write!(fmt, "\nHINT: {}", hint)?;
         }
+        match &self.position {
+            Some(ErrorPosition::Original(position)) => write!(fmt, "\nPOSITION: {}", position)?,
+            Some(ErrorPosition::Internal { position, query }) => {
+                write!(fmt, "\nINTERNAL QUERY: {}", query)?;
+                write!(fmt, "\nINTERNAL POSITION: {}", position)?;
+            }
+            None => {}
+        }
         Ok(())
     }
 }
@@ -330,7 +340,7 @@ pub enum ErrorPosition {
     Original(u32),
     /// A position in an internally generated query.
     Internal {
-        /// The byte position.
+        /// The character position.
         position: u32,
         /// A query generated by the Postgres server.
         query: String,
@@ -346,6 +356,7 @@ enum Kind {
     FromSql(usize),
     Column(String),
     Parameters(usize, usize),
+    ColumnCount(usize, usize),
     Closed,
     Db,
     Parse,
@@ -354,14 +365,55 @@ enum Kind {
     ConfigParse,
     Config,
     RowCount,
+    ResultTooLarge(usize),
+    DuplicateKey(String),
+    CopyExpected,
+    UnknownType(String),
+    CursorTransaction,
     #[cfg(feature = "runtime")]
     Connect,
     Timeout,
+    #[cfg(feature = "runtime")]
+    RowTimeout,
 }
 
 struct ErrorInner {
     kind: Kind,
     cause: Option<Box<dyn error::Error + Sync + Send>>,
+    copy_progress: Option<CopyProgress>,
+    statement_index: Option<usize>,
+}
+
+/// The progress a `COPY ... FROM STDIN` operation had made when it failed.
+///
+/// Postgres applies a `COPY` atomically, so none of the data counted here has been committed. The counts only indicate
+/// how far through its input the sink had gotten, which loaders can use to locate the failure in their source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CopyProgress {
+    bytes_sent: u64,
+    rows_sent: u64,
+}
+
+impl CopyProgress {
+    pub(crate) fn new(bytes_sent: u64, rows_sent: u64) -> CopyProgress {
+        CopyProgress {
+            bytes_sent,
+            rows_sent,
+        }
+    }
+
+    /// The number of bytes of copy data sent to the server before the failure.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// The number of items written to the sink which had been sent to the server before the failure.
+    ///
+    /// Items still buffered in the sink, or written by a call which failed, are not counted. This corresponds to the
+    /// number of rows when each item written to the sink holds exactly one row.
+    pub fn rows_sent(&self) -> u64 {
+        self.rows_sent
+    }
 }
 
 /// An error communicating with the Postgres server.
@@ -372,6 +424,8 @@ impl fmt::Debug for Error {
         fmt.debug_struct("Error")
             .field("kind", &self.0.kind)
             .field("cause", &self.0.cause)
+            .field("copy_progress", &self.0.copy_progress)
+            .field("statement_index", &self.0.statement_index)
             .finish()
     }
 }
@@ -388,6 +442,9 @@ impl fmt::Display for Error {
             Kind::Parameters(real, expected) => {
                 write!(fmt, "expected {expected} parameters but got {real}")?
             }
+            Kind::ColumnCount(real, expected) => {
+                write!(fmt, "expected {expected} columns but got {real}")?
+            }
             Kind::Closed => fmt.write_str("connection closed")?,
             Kind::Db => fmt.write_str("db error")?,
             Kind::Parse => fmt.write_str("error parsing response from server")?,
@@ -396,9 +453,22 @@ impl fmt::Display for Error {
             Kind::ConfigParse => fmt.write_str("invalid connection string")?,
             Kind::Config => fmt.write_str("invalid configuration")?,
             Kind::RowCount => fmt.write_str("query returned an unexpected number of rows")?,
+            Kind::ResultTooLarge(max) => write!(fmt, "query returned more than {} rows", max)?,
+            Kind::DuplicateKey(column) => {
+                write!(fmt, "query returned a duplicate key in column `{}`", column)?
+            }
+            Kind::CopyExpected => {
+                fmt.write_str("statement did not start a COPY of the expected direction")?
+            }
+            Kind::UnknownType(name) => write!(fmt, "unknown type `{}`", name)?,
+            Kind::CursorTransaction => fmt.write_str(
+                "the connection is busy fetching the rows of a query in batches in its own transaction",
+            )?,
             #[cfg(feature = "runtime")]
             Kind::Connect => fmt.write_str("error connecting to server")?,
             Kind::Timeout => fmt.write_str("timeout waiting for server")?,
+            #[cfg(feature = "runtime")]
+            Kind::RowTimeout => fmt.write_str("timeout waiting for the next row")?,
         };
         if let Some(ref cause) = self.0.cause {
             write!(fmt, ": {}", cause)?;
@@ -431,15 +501,111 @@ impl Error {
         self.0.kind == Kind::Closed
     }
 
+    /// Determines if the error was caused by a client-side timeout or deadline expiring.
+    pub fn is_timeout(&self) -> bool {
+        self.0.kind == Kind::Timeout
+    }
+
+    /// Determines if the error was caused by a row stream's row timeout expiring.
+    ///
+    /// See `RowStream::with_row_timeout`.
+    #[cfg(feature = "runtime")]
+    pub fn is_row_timeout(&self) -> bool {
+        self.0.kind == Kind::RowTimeout
+    }
+
+    /// Determines if the error was caused by a query returning more rows than the configured `max_result_rows`.
+    pub fn is_result_too_large(&self) -> bool {
+        matches!(self.0.kind, Kind::ResultTooLarge(_))
+    }
+
+    /// Determines if the error was caused by a request being made while a `RowStream` was fetching its rows in
+    /// batches in a transaction of its own.
+    ///
+    /// See `Config::fetch_count`.
+    pub fn is_cursor_transaction(&self) -> bool {
+        self.0.kind == Kind::CursorTransaction
+    }
+
+    /// Determines if the operation that produced the error is worth retrying.
+    ///
+    /// The classification is deliberately conservative. Only the following errors are considered retryable:
+    ///
+    /// * the connection closed (`is_closed`),
+    /// * a client-side timeout or deadline expired (`is_timeout`),
+    /// * `40001` (`serialization_failure`),
+    /// * `40P01` (`deadlock_detected`),
+    /// * `57P01` (`admin_shutdown`).
+    ///
+    /// Everything else, including syntax errors and constraint violations, is not. Note that the errors relating to
+    /// the connection can only be recovered from on a new connection, and the serialization errors require the entire
+    /// transaction to be retried rather than just the failing statement.
+    pub fn retryable(&self) -> bool {
+        match self.0.kind {
+            Kind::Closed | Kind::Timeout => true,
+            Kind::Db => matches!(
+                self.code(),
+                Some(&SqlState::T_R_SERIALIZATION_FAILURE)
+                    | Some(&SqlState::T_R_DEADLOCK_DETECTED)
+                    | Some(&SqlState::ADMIN_SHUTDOWN)
+            ),
+            _ => false,
+        }
+    }
+
     /// Returns the SQLSTATE error code associated with the error.
     ///
-    /// This is a convenience method that downcasts the cause to a `DbError` and returns its code.
+    /// This is a convenience method that downcasts the cause to a `DbError` and returns its code. The code can be
+    /// compared against the constants on `SqlState`, such as `SqlState::UNIQUE_VIOLATION`, and `SqlState::code`
+    /// returns its raw five-character form.
     pub fn code(&self) -> Option<&SqlState> {
         self.as_db_error().map(DbError::code)
     }
 
+    /// Returns how far a `COPY ... FROM STDIN` operation had progressed when it failed.
+    ///
+    /// This is only set on errors returned by a `CopyInSink`.
+    pub fn copy_progress(&self) -> Option<CopyProgress> {
+        self.0.copy_progress
+    }
+
+    /// Returns the 0-based index of the statement that failed within a batch of statements.
+    ///
+    /// This is only set on errors returned by `batch_execute` and `prepare_all`.
+    pub fn statement_index(&self) -> Option<usize> {
+        self.0.statement_index
+    }
+
     fn new(kind: Kind, cause: Option<Box<dyn error::Error + Sync + Send>>) -> Error {
-        Error(Box::new(ErrorInner { kind, cause }))
+        Error(Box::new(ErrorInner {
+            kind,
+            cause,
+            copy_progress: None,
+            statement_index: None,
+        }))
+    }
+
+    /// Determines if the error is a transient failure to connect which may succeed if retried.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn is_transient_connect(&self) -> bool {
+        match self.0.kind {
+            Kind::Connect => !matches!(
+                self.source().and_then(|e| e.downcast_ref::<io::Error>()),
+                Some(e) if e.kind() == io::ErrorKind::PermissionDenied
+            ),
+            Kind::Db => self.code() == Some(&SqlState::CANNOT_CONNECT_NOW),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn with_copy_progress(mut self, progress: CopyProgress) -> Error {
+        self.0.copy_progress = Some(progress);
+        self
+    }
+
+    pub(crate) fn with_statement_index(mut self, index: usize) -> Error {
+        self.0.statement_index = Some(index);
+        self
     }
 
     pub(crate) fn closed() -> Error {
@@ -483,6 +649,10 @@ impl Error {
         Error::new(Kind::Parameters(real, expected), None)
     }
 
+    pub(crate) fn column_count(real: usize, expected: usize) -> Error {
+        Error::new(Kind::ColumnCount(real, expected), None)
+    }
+
     pub(crate) fn tls(e: Box<dyn error::Error + Sync + Send>) -> Error {
         Error::new(Kind::Tls, Some(e))
     }
@@ -503,15 +673,40 @@ impl Error {
         Error::new(Kind::Config, Some(e))
     }
 
+    pub(crate) fn copy_expected() -> Error {
+        Error::new(Kind::CopyExpected, None)
+    }
+
+    pub(crate) fn unknown_type(name: String) -> Error {
+        Error::new(Kind::UnknownType(name), None)
+    }
+
     pub(crate) fn row_count() -> Error {
         Error::new(Kind::RowCount, None)
     }
 
+    pub(crate) fn result_too_large(max: usize) -> Error {
+        Error::new(Kind::ResultTooLarge(max), None)
+    }
+
+    pub(crate) fn cursor_transaction() -> Error {
+        Error::new(Kind::CursorTransaction, None)
+    }
+
+    pub(crate) fn duplicate_key(column: String) -> Error {
+        Error::new(Kind::DuplicateKey(column), None)
+    }
+
     #[cfg(feature = "runtime")]
     pub(crate) fn connect(e: io::Error) -> Error {
         Error::new(Kind::Connect, Some(Box::new(e)))
     }
 
+    #[cfg(feature = "runtime")]
+    pub(crate) fn row_timeout() -> Error {
+        Error::new(Kind::RowTimeout, None)
+    }
+
     #[doc(hidden)]
     pub fn __private_api_timeout() -> Error {
         Error::new(Kind::Timeout, None)
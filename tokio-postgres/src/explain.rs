@@ -0,0 +1,109 @@
+use crate::types::ToSql;
+use crate::{Client, Error};
+use std::fmt::Write;
+use std::str;
+
+/// The output format of an `EXPLAIN`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ExplainFormat {
+    /// The human-readable text format.
+    #[default]
+    Text,
+    /// A JSON document describing the plan nodes.
+    Json,
+}
+
+/// Options for `Client::explain`.
+#[derive(Debug, Clone, Default)]
+pub struct ExplainOptions {
+    format: ExplainFormat,
+    analyze: bool,
+    buffers: bool,
+    verbose: bool,
+}
+
+impl ExplainOptions {
+    /// Creates a new set of options, which produces a text plan without executing the statement.
+    pub fn new() -> ExplainOptions {
+        ExplainOptions::default()
+    }
+
+    /// Sets the output format of the plan.
+    ///
+    /// Defaults to `ExplainFormat::Text`.
+    pub fn format(mut self, format: ExplainFormat) -> ExplainOptions {
+        self.format = format;
+        self
+    }
+
+    /// Sets whether the statement is actually executed to collect run times and row counts (`ANALYZE`).
+    ///
+    /// Note that any side effects of the statement will take place. Run it inside a transaction which is rolled back
+    /// to avoid that.
+    ///
+    /// Defaults to `false`.
+    pub fn analyze(mut self, analyze: bool) -> ExplainOptions {
+        self.analyze = analyze;
+        self
+    }
+
+    /// Sets whether buffer usage is included in the plan (`BUFFERS`).
+    ///
+    /// Defaults to `false`.
+    pub fn buffers(mut self, buffers: bool) -> ExplainOptions {
+        self.buffers = buffers;
+        self
+    }
+
+    /// Sets whether additional details such as output column lists are included in the plan (`VERBOSE`).
+    ///
+    /// Defaults to `false`.
+    pub fn verbose(mut self, verbose: bool) -> ExplainOptions {
+        self.verbose = verbose;
+        self
+    }
+
+    fn to_query(&self, statement: &str) -> String {
+        let mut query = String::from("EXPLAIN (FORMAT ");
+        query.push_str(match self.format {
+            ExplainFormat::Text => "TEXT",
+            ExplainFormat::Json => "JSON",
+        });
+        if self.analyze {
+            query.push_str(", ANALYZE");
+        }
+        if self.buffers {
+            query.push_str(", BUFFERS");
+        }
+        if self.verbose {
+            query.push_str(", VERBOSE");
+        }
+        write!(query, ") {}", statement).unwrap();
+        query
+    }
+}
+
+pub async fn explain(
+    client: &Client,
+    statement: &str,
+    params: &[&(dyn ToSql + Sync)],
+    options: &ExplainOptions,
+) -> Result<String, Error> {
+    let query = options.to_query(statement);
+    let rows = client.query(&*query, params).await?;
+
+    // The text format returns one row per line of the plan, and the JSON format a single row. Read the raw column
+    // since it is text for the former and json for the latter.
+    let mut plan = String::new();
+    for row in rows {
+        let line = row.col_buffer(0).unwrap_or_default();
+        let line = str::from_utf8(line).map_err(|e| Error::from_sql(Box::new(e), 0))?;
+        if !plan.is_empty() {
+            plan.push('\n');
+        }
+        plan.push_str(line);
+    }
+
+    Ok(plan)
+}
@@ -0,0 +1,27 @@
+//! GSSAPI authentication.
+
+use crate::config::ByIdentity;
+use std::error::Error;
+
+/// A client-side GSSAPI security context used to authenticate with the server.
+///
+/// This is implemented on top of a GSSAPI library such as `libgssapi` or `cross-krb5`, typically by calling
+/// `gss_init_sec_context` with the token received from the server.
+///
+/// Requires the `gssapi` Cargo feature.
+pub trait GssContext: Send {
+    /// Advances the security context.
+    ///
+    /// `token` is `None` on the first call, and otherwise contains the token sent by the server. Returns the token to
+    /// send back to the server, if any.
+    fn step(
+        &mut self,
+        token: Option<&[u8]>,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error + Sync + Send>>;
+}
+
+/// A callback creating a security context for a host-based service principal name such as `postgres@db.example.com`.
+pub(crate) type GssProviderFn =
+    dyn Fn(&str) -> Result<Box<dyn GssContext>, Box<dyn Error + Sync + Send>> + Send + Sync;
+
+pub(crate) type GssProvider = ByIdentity<GssProviderFn>;
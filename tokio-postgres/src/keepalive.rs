@@ -12,23 +12,38 @@ impl From<&KeepaliveConfig> for TcpKeepalive {
     fn from(keepalive_config: &KeepaliveConfig) -> Self {
         let mut tcp_keepalive = Self::new().with_time(keepalive_config.idle);
 
-        #[cfg(not(any(
-            target_os = "aix",
-            target_os = "redox",
-            target_os = "solaris",
-            target_os = "openbsd"
-        )))]
+        // Platforms without support for a setting fall back to the system default for it.
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "illumos",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "tvos",
+            target_os = "watchos",
+            target_os = "windows",
+        ))]
         if let Some(interval) = keepalive_config.interval {
             tcp_keepalive = tcp_keepalive.with_interval(interval);
         }
 
-        #[cfg(not(any(
-            target_os = "aix",
-            target_os = "redox",
-            target_os = "solaris",
-            target_os = "windows",
-            target_os = "openbsd"
-        )))]
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "illumos",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "tvos",
+            target_os = "watchos",
+        ))]
         if let Some(retries) = keepalive_config.retries {
             tcp_keepalive = tcp_keepalive.with_retries(retries);
         }
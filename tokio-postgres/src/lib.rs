@@ -104,7 +104,13 @@
 //! | Feature | Description | Extra dependencies | Default |
 //! | ------- | ----------- | ------------------ | ------- |
 //! | `runtime` | Enable convenience API for the connection process based on the `tokio` crate. | [tokio](https://crates.io/crates/tokio) 1.0 with the features `net` and `time` | yes |
+//! | `csv` | Enable writing query results as CSV with `RowStream::write_csv`. | - | no |
+//! | `digest` | Enable computing digests of `COPY ... TO STDOUT` data with `CopyOutStream::with_digest`. | [digest](https://crates.io/crates/digest) 0.10 | no |
+//! | `gssapi` | Enable GSSAPI (Kerberos) authentication with `Config::gss_provider`. | - | no |
+//! | `tracing` | Enable instrumentation of `Client::prepare`, `Client::query`, and `Client::execute` with `tracing` spans. | [tracing](https://crates.io/crates/tracing) 0.1 | no |
+//! | `message-tracing` | Enable tracing of individual protocol messages with `Config::message_tracer`. | - | no |
 //! | `array-impls` | Enables `ToSql` and `FromSql` trait impls for arrays | - | no |
+//! | `with-arrow-57` | Enable decoding query results into Arrow record batches with `Client::copy_out_arrow`. | [arrow-array](https://crates.io/crates/arrow-array) 57 | no |
 //! | `with-bit-vec-0_6` | Enable support for the `bit-vec` crate. | [bit-vec](https://crates.io/crates/bit-vec) 0.6 | no |
 //! | `with-chrono-0_4` | Enable support for the `chrono` crate. | [chrono](https://crates.io/crates/chrono) 0.4 | no |
 //! | `with-eui48-0_4` | Enable support for the 0.4 version of the `eui48` crate. This is deprecated and will be removed. | [eui48](https://crates.io/crates/eui48) 0.4 | no |
@@ -119,18 +125,29 @@
 //! | `with-time-0_3` | Enable support for the 0.3 version of the `time` crate. | [time](https://crates.io/crates/time/0.3.0) 0.3 | no |
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
-pub use crate::cancel_token::CancelToken;
+pub use crate::auth_info::{AuthInfo, AuthMethod};
+pub use crate::cancel_token::{CancelToken, QueryHandle};
 pub use crate::client::Client;
 pub use crate::config::Config;
 pub use crate::connection::Connection;
 pub use crate::copy_in::CopyInSink;
 pub use crate::copy_out::CopyOutStream;
+#[cfg(feature = "digest")]
+pub use crate::copy_out::{DigestHandle, DigestStream};
 use crate::error::DbError;
 pub use crate::error::Error;
+pub use crate::explain::{ExplainFormat, ExplainOptions};
 pub use crate::generic_client::GenericClient;
+#[cfg(feature = "gssapi")]
+pub use crate::gss::GssContext;
+#[cfg(feature = "message-tracing")]
+pub use crate::message_trace::{Direction, MessageTag};
+pub use crate::notifications::NotificationStream;
 pub use crate::portal::Portal;
-pub use crate::query::RowStream;
-pub use crate::row::{Row, SimpleQueryRow};
+pub use crate::query::{ResultSetMessage, ResultSetStream, RowStream};
+#[cfg(feature = "runtime")]
+pub use crate::reconnect::{ReconnectingClient, ReconnectingConnection};
+pub use crate::row::{CachedRow, ResultSet, ResultSetRow, Row, SimpleQueryRow};
 pub use crate::simple_query::{SimpleColumn, SimpleQueryStream};
 #[cfg(feature = "runtime")]
 pub use crate::socket::Socket;
@@ -140,10 +157,13 @@ use crate::tls::MakeTlsConnect;
 pub use crate::tls::NoTls;
 pub use crate::to_statement::ToStatement;
 pub use crate::transaction::Transaction;
-pub use crate::transaction_builder::{IsolationLevel, TransactionBuilder};
+pub use crate::transaction_builder::{DropBehavior, IsolationLevel, TransactionBuilder};
 use crate::types::ToSql;
 use std::sync::Arc;
 
+#[cfg(feature = "with-arrow-57")]
+pub mod arrow;
+mod auth_info;
 pub mod binary_copy;
 mod bind;
 #[cfg(feature = "runtime")]
@@ -152,6 +172,7 @@ mod cancel_query_raw;
 mod cancel_token;
 mod client;
 mod codec;
+pub mod columnar;
 pub mod config;
 #[cfg(feature = "runtime")]
 mod connect;
@@ -162,14 +183,25 @@ mod connect_tls;
 mod connection;
 mod copy_in;
 mod copy_out;
+#[cfg(feature = "csv")]
+mod csv;
 pub mod error;
+mod explain;
 mod generic_client;
+#[cfg(feature = "gssapi")]
+mod gss;
 #[cfg(not(target_arch = "wasm32"))]
 mod keepalive;
+mod lru;
 mod maybe_tls_stream;
+#[cfg(feature = "message-tracing")]
+mod message_trace;
+mod notifications;
 mod portal;
 mod prepare;
 mod query;
+#[cfg(feature = "runtime")]
+mod reconnect;
 pub mod row;
 mod simple_query;
 #[cfg(feature = "runtime")]
@@ -177,6 +209,8 @@ mod socket;
 mod statement;
 pub mod tls;
 mod to_statement;
+#[cfg(feature = "tracing")]
+mod trace;
 mod transaction;
 mod transaction_builder;
 pub mod types;
@@ -256,6 +290,16 @@ pub enum SimpleQueryMessage {
     RowDescription(Arc<[SimpleColumn]>),
 }
 
+/// How `Client::query_map_by` handles rows with the same key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicateKeys {
+    /// Later rows replace earlier rows with the same key.
+    LastWins,
+    /// Return an error if more than one row has the same key.
+    Error,
+}
+
 fn slice_iter<'a>(
     s: &'a [&'a (dyn ToSql + Sync)],
 ) -> impl ExactSizeIterator<Item = &'a dyn ToSql> + 'a {
@@ -0,0 +1,102 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A map which evicts its least recently used entry once it holds `capacity` entries.
+///
+/// Without a capacity, entries are never evicted.
+pub(crate) struct LruCache<K, V> {
+    capacity: Option<usize>,
+    /// The entries, along with the tick of their most recent use.
+    entries: HashMap<K, (V, u64)>,
+    /// The keys of the entries, ordered by the tick of their most recent use.
+    order: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub(crate) fn new(capacity: Option<usize>) -> LruCache<K, V> {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the value of an entry, marking it as the most recently used.
+    pub(crate) fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (value, last_used) = self.entries.get_mut(key)?;
+        let key = self.order.remove(last_used)?;
+        self.tick += 1;
+        *last_used = self.tick;
+        self.order.insert(self.tick, key);
+        Some(value)
+    }
+
+    /// Inserts an entry, evicting the least recently used one if the cache is full.
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        if self.capacity == Some(0) {
+            return;
+        }
+
+        match self.entries.get(&key) {
+            Some((_, last_used)) => {
+                self.order.remove(last_used);
+            }
+            None => {
+                if self.capacity.is_some_and(|c| self.entries.len() >= c) {
+                    if let Some((_, oldest)) = self.order.pop_first() {
+                        self.entries.remove(&oldest);
+                    }
+                }
+            }
+        }
+
+        self.tick += 1;
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+    }
+
+    /// Changes the capacity, evicting the least recently used entries which no longer fit.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = Some(capacity);
+        while self.entries.len() > capacity {
+            match self.order.pop_first() {
+                Some((_, oldest)) => self.entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    /// Returns the entries, from least to most recently used.
+    #[cfg(feature = "runtime")]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.order
+            .values()
+            .filter_map(move |key| self.entries.get(key).map(|(value, _)| (key, value)))
+    }
+
+    pub(crate) fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (value, last_used) = self.entries.remove(key)?;
+        self.order.remove(&last_used);
+        Some(value)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
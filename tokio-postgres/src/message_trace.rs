@@ -0,0 +1,65 @@
+use crate::config::ByIdentity;
+use std::fmt;
+
+/// The direction in which a traced protocol message travelled.
+///
+/// Requires the `message-tracing` Cargo feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// A message sent by the client to the server.
+    Frontend,
+    /// A message received by the client from the server.
+    Backend,
+}
+
+/// The type byte identifying a protocol message.
+///
+/// The startup message, which has no type byte, is reported with a tag of `0`.
+///
+/// Requires the `message-tracing` Cargo feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MessageTag(pub u8);
+
+impl fmt::Display for MessageTag {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => fmt.write_str("startup"),
+            tag => write!(fmt, "{}", tag as char),
+        }
+    }
+}
+
+/// A callback invoked for each traced protocol message.
+pub(crate) type TraceFn = dyn Fn(Direction, MessageTag, usize) + Send + Sync;
+
+pub(crate) type MessageTracer = ByIdentity<TraceFn>;
+
+impl MessageTracer {
+    /// Reports each complete message in `buf`, which starts at a message boundary.
+    ///
+    /// If `startup` is set, the first message is the untagged startup message.
+    pub(crate) fn trace(&self, direction: Direction, mut buf: &[u8], mut startup: bool) {
+        loop {
+            let (tag, header) = if startup {
+                (0, 0)
+            } else {
+                match buf.first() {
+                    Some(&tag) => (tag, 1),
+                    None => return,
+                }
+            };
+            startup = false;
+
+            let len = match buf.get(header..header + 4) {
+                Some(len) => u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize + header,
+                None => return,
+            };
+            if len == 0 || len > buf.len() {
+                return;
+            }
+
+            (self.0)(direction, MessageTag(tag), len);
+            buf = &buf[len..];
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::Notification;
+use futures_util::{stream::FusedStream, Stream};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::task::{Context, Poll, Waker};
+
+/// The notifications buffered for a `NotificationStream`.
+#[derive(Default)]
+struct Queue {
+    notifications: VecDeque<Notification>,
+    dropped: u64,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl Queue {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The queues of the `NotificationStream`s of a connection, shared between the `Client` and the `Connection`.
+pub(crate) struct Subscribers {
+    queues: Vec<Weak<Mutex<Queue>>>,
+    max_buffered: Option<usize>,
+    closed: bool,
+}
+
+impl Subscribers {
+    pub(crate) fn new(max_buffered: Option<usize>) -> Subscribers {
+        Subscribers {
+            queues: vec![],
+            max_buffered,
+            closed: false,
+        }
+    }
+
+    /// Creates a new stream, which immediately ends if the connection has already closed.
+    pub(crate) fn subscribe(subscribers: &Arc<Mutex<Subscribers>>) -> NotificationStream {
+        let queue = Arc::new(Mutex::new(Queue::default()));
+        let mut subscribers = subscribers.lock();
+        if subscribers.closed {
+            queue.lock().closed = true;
+        } else {
+            subscribers.queues.push(Arc::downgrade(&queue));
+        }
+        NotificationStream { queue }
+    }
+
+    /// Sends a notification to every stream, forgetting the streams which have been dropped.
+    ///
+    /// A stream which already buffers the maximum number of notifications discards its oldest one.
+    pub(crate) fn notify(&mut self, notification: &Notification) {
+        let max_buffered = self.max_buffered;
+        self.queues.retain(|queue| {
+            let queue = match queue.upgrade() {
+                Some(queue) => queue,
+                None => return false,
+            };
+            let mut queue = queue.lock();
+            queue.notifications.push_back(notification.clone());
+            if max_buffered.is_some_and(|max| queue.notifications.len() > max) {
+                queue.notifications.pop_front();
+                queue.dropped += 1;
+            }
+            queue.wake();
+            true
+        });
+    }
+
+    /// Ends every stream, and any created later.
+    pub(crate) fn close(&mut self) {
+        for queue in self.queues.drain(..) {
+            if let Some(queue) = queue.upgrade() {
+                let mut queue = queue.lock();
+                queue.closed = true;
+                queue.wake();
+            }
+        }
+        self.closed = true;
+    }
+}
+
+/// A stream of asynchronous notifications received by a connection.
+///
+/// Returned by `Client::notifications`. The stream ends when the connection closes.
+pub struct NotificationStream {
+    queue: Arc<Mutex<Queue>>,
+}
+
+impl NotificationStream {
+    /// Returns the number of notifications which have been discarded because the stream's buffer was full.
+    ///
+    /// See `Config::max_buffered_notifications` for details.
+    pub fn dropped(&self) -> u64 {
+        self.queue.lock().dropped
+    }
+}
+
+impl Stream for NotificationStream {
+    type Item = Notification;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Notification>> {
+        let mut queue = self.queue.lock();
+        if let Some(notification) = queue.notifications.pop_front() {
+            return Poll::Ready(Some(notification));
+        }
+        if queue.closed {
+            return Poll::Ready(None);
+        }
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl FusedStream for NotificationStream {
+    fn is_terminated(&self) -> bool {
+        let queue = self.queue.lock();
+        queue.closed && queue.notifications.is_empty()
+    }
+}
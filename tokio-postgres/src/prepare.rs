@@ -9,9 +9,10 @@ use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use futures_util::{pin_mut, TryStreamExt};
 use log::debug;
-use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::backend::{Message, ParameterDescriptionBody, RowDescriptionBody};
 use postgres_protocol::message::frontend;
 use std::future::Future;
+use std::iter;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -56,16 +57,41 @@ AND attnum > 0
 ORDER BY attnum
 ";
 
-pub(crate) static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+// Multirange types weren't added until Postgres 14, but the query is only run for a multirange type
+const TYPEINFO_MULTIRANGE_QUERY: &str = "\
+SELECT rngsubtype
+FROM pg_catalog.pg_range
+WHERE rngmultitypid = $1
+";
+
+const TYPEINFO_NAME_QUERY: &str = "\
+SELECT t.oid
+FROM pg_catalog.pg_type t
+INNER JOIN pg_catalog.pg_namespace n ON t.typnamespace = n.oid
+WHERE t.typname = $2
+AND CASE WHEN $1::TEXT IS NULL THEN pg_catalog.pg_type_is_visible(t.oid) ELSE n.nspname = $1 END
+";
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Generates a unique name for a statement prepared on `client`.
+pub(crate) fn next_statement_name(client: &InnerClient) -> String {
+    format!(
+        "s_{}_{}",
+        client.statement_prefix(),
+        NEXT_ID.fetch_add(1, Ordering::SeqCst)
+    )
+}
 
 pub async fn prepare(
     client: &Arc<InnerClient>,
     query: &str,
     types: &[Type],
 ) -> Result<Statement, Error> {
-    let name = format!("s{}", NEXT_ID.fetch_add(1, Ordering::SeqCst));
+    let name = next_statement_name(client);
     let buf = encode(client, &name, query, types)?;
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let guard = PrepareGuard::new(client, &name);
 
     match responses.next().await? {
         Message::ParseComplete => {}
@@ -83,6 +109,90 @@ pub async fn prepare(
         _ => return Err(Error::unexpected_message()),
     };
 
+    let statement =
+        new_statement(client, name.clone(), parameter_description, row_description).await?;
+    guard.disarm();
+    Ok(statement)
+}
+
+/// Prepares several statements in a single round trip.
+///
+/// The Parse and Describe messages for every query are pipelined ahead of a single Sync, so if one query fails to
+/// prepare, the server skips the rest. Any statements which were prepared before the failure are closed again, and
+/// the returned error carries the index of the failing query.
+pub async fn prepare_all<S>(
+    client: &Arc<InnerClient>,
+    queries: &[S],
+) -> Result<Vec<Statement>, Error>
+where
+    S: AsRef<str>,
+{
+    let names = queries
+        .iter()
+        .map(|_| next_statement_name(client))
+        .collect::<Vec<_>>();
+
+    let buf = client.with_buf(|buf| {
+        for (name, query) in names.iter().zip(queries) {
+            debug!("preparing query {}: {}", name, query.as_ref());
+            frontend::parse(name, query.as_ref(), iter::empty(), buf).map_err(Error::encode)?;
+            frontend::describe(b'S', name, buf).map_err(Error::encode)?;
+        }
+        frontend::sync(buf);
+        Ok(buf.split().freeze())
+    })?;
+    let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    let guards = names
+        .iter()
+        .map(|name| PrepareGuard::new(client, name))
+        .collect::<Vec<_>>();
+
+    let mut descriptions = Vec::with_capacity(names.len());
+    for idx in 0..names.len() {
+        let description = async {
+            match responses.next().await? {
+                Message::ParseComplete => {}
+                _ => return Err(Error::unexpected_message()),
+            }
+
+            let parameter_description = match responses.next().await? {
+                Message::ParameterDescription(body) => body,
+                _ => return Err(Error::unexpected_message()),
+            };
+
+            let row_description = match responses.next().await? {
+                Message::RowDescription(body) => Some(body),
+                Message::NoData => None,
+                _ => return Err(Error::unexpected_message()),
+            };
+
+            Ok((parameter_description, row_description))
+        };
+        descriptions.push(description.await.map_err(|e| e.with_statement_index(idx))?);
+    }
+
+    let mut statements = Vec::with_capacity(names.len());
+    for (idx, (name, (parameter_description, row_description))) in
+        names.iter().zip(descriptions).enumerate()
+    {
+        let statement = new_statement(client, name.clone(), parameter_description, row_description)
+            .await
+            .map_err(|e| e.with_statement_index(idx))?;
+        statements.push(statement);
+    }
+
+    for guard in guards {
+        guard.disarm();
+    }
+    Ok(statements)
+}
+
+async fn new_statement(
+    client: &Arc<InnerClient>,
+    name: String,
+    parameter_description: ParameterDescriptionBody,
+    row_description: Option<RowDescriptionBody>,
+) -> Result<Statement, Error> {
     let mut parameters = vec![];
     let mut it = parameter_description.parameters();
     while let Some(oid) = it.next().map_err(Error::parse)? {
@@ -108,6 +218,47 @@ pub async fn prepare(
     Ok(Statement::new(client, name, parameters, columns))
 }
 
+/// Closes a tentatively prepared statement if `prepare` is cancelled or fails partway through.
+///
+/// Once the Parse message has been sent, the server may or may not end up with a statement of the chosen name, so we
+/// schedule a Close for it through the connection unless the `Statement` that takes ownership of it is created.
+pub(crate) struct PrepareGuard<'a> {
+    client: &'a InnerClient,
+    name: &'a str,
+    armed: bool,
+}
+
+impl<'a> PrepareGuard<'a> {
+    pub(crate) fn new(client: &'a InnerClient, name: &'a str) -> PrepareGuard<'a> {
+        PrepareGuard {
+            client,
+            name,
+            armed: true,
+        }
+    }
+
+    pub(crate) fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for PrepareGuard<'_> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        debug!("closing abandoned statement {}", self.name);
+        let buf = self.client.with_buf(|buf| {
+            frontend::close(b'S', self.name, buf).unwrap();
+            frontend::sync(buf);
+            buf.split().freeze()
+        });
+        let _ = self
+            .client
+            .send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+    }
+}
+
 fn prepare_rec<'a>(
     client: &'a Arc<InnerClient>,
     query: &'a str,
@@ -175,6 +326,9 @@ pub(crate) async fn get_type(client: &Arc<InnerClient>, oid: Oid) -> Result<Type
     } else if let Some(rngsubtype) = rngsubtype {
         let type_ = get_type_rec(client, rngsubtype).await?;
         Kind::Range(type_)
+    } else if type_ == b'm' as i8 {
+        let type_ = get_multirange_subtype(client, oid).await?;
+        Kind::Multirange(type_)
     } else {
         Kind::Simple
     };
@@ -185,6 +339,41 @@ pub(crate) async fn get_type(client: &Arc<InnerClient>, oid: Oid) -> Result<Type
     Ok(type_)
 }
 
+pub(crate) async fn get_type_by_name(
+    client: &Arc<InnerClient>,
+    schema: Option<&str>,
+    name: &str,
+) -> Result<Type, Error> {
+    let stmt = typeinfo_name_statement(client).await?;
+
+    let rows = query::query(client, stmt, slice_iter(&[&schema, &name])).await?;
+    pin_mut!(rows);
+
+    let oid: Oid = match rows.try_next().await? {
+        Some(row) => row.try_get(0)?,
+        None => {
+            let name = match schema {
+                Some(schema) => format!("{}.{}", schema, name),
+                None => name.to_string(),
+            };
+            return Err(Error::unknown_type(name));
+        }
+    };
+
+    get_type(client, oid).await
+}
+
+async fn typeinfo_name_statement(client: &Arc<InnerClient>) -> Result<Statement, Error> {
+    if let Some(stmt) = client.typeinfo_name() {
+        return Ok(stmt);
+    }
+
+    let stmt = prepare_rec(client, TYPEINFO_NAME_QUERY, &[]).await?;
+
+    client.set_typeinfo_name(&stmt);
+    Ok(stmt)
+}
+
 fn get_type_rec<'a>(
     client: &'a Arc<InnerClient>,
     oid: Oid,
@@ -265,3 +454,28 @@ async fn typeinfo_composite_statement(client: &Arc<InnerClient>) -> Result<State
     client.set_typeinfo_composite(&stmt);
     Ok(stmt)
 }
+
+async fn get_multirange_subtype(client: &Arc<InnerClient>, oid: Oid) -> Result<Type, Error> {
+    let stmt = typeinfo_multirange_statement(client).await?;
+
+    let rows = query::query(client, stmt, slice_iter(&[&oid])).await?;
+    pin_mut!(rows);
+
+    let subtype: Oid = match rows.try_next().await? {
+        Some(row) => row.try_get(0)?,
+        None => return Err(Error::unexpected_message()),
+    };
+
+    get_type_rec(client, subtype).await
+}
+
+async fn typeinfo_multirange_statement(client: &Arc<InnerClient>) -> Result<Statement, Error> {
+    if let Some(stmt) = client.typeinfo_multirange() {
+        return Ok(stmt);
+    }
+
+    let stmt = prepare_rec(client, TYPEINFO_MULTIRANGE_QUERY, &[]).await?;
+
+    client.set_typeinfo_multirange(&stmt);
+    Ok(stmt)
+}
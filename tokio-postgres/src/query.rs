@@ -1,22 +1,41 @@
 use crate::client::{InnerClient, Responses};
 use crate::codec::FrontendMessage;
+use crate::columnar::ColumnStore;
 use crate::connection::RequestMessages;
+use crate::error::DbError;
 use crate::prepare::get_type;
-use crate::types::{BorrowToSql, IsNull};
-use crate::{Column, Error, Portal, Row, Statement};
+use crate::row::RangePool;
+#[cfg(feature = "runtime")]
+use crate::tls::{MakeTlsConnect, TlsConnect};
+use crate::types::{BorrowToSql, Format, IsNull};
+use crate::{bind, simple_query, Column, Error, Portal, QueryHandle, Row, Statement};
+#[cfg(feature = "runtime")]
+use crate::{CancelToken, Socket};
 use bytes::{Bytes, BytesMut};
 use fallible_iterator::FallibleIterator;
-use futures_util::{ready, Stream};
+use futures_channel::mpsc;
+#[cfg(feature = "runtime")]
+use futures_util::future::BoxFuture;
+use futures_util::{pin_mut, ready, Stream, TryStreamExt};
 use log::{debug, log_enabled, Level};
+#[cfg(feature = "runtime")]
+use parking_lot::Mutex;
 use pin_project_lite::pin_project;
 use postgres_protocol::message::backend::{CommandCompleteBody, Message};
 use postgres_protocol::message::frontend;
 use postgres_types::Type;
+use std::collections::VecDeque;
 use std::fmt;
+#[cfg(feature = "runtime")]
+use std::future::Future;
 use std::marker::PhantomPinned;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+#[cfg(feature = "runtime")]
+use std::time::Duration;
+#[cfg(feature = "runtime")]
+use tokio::time::{self, Instant, Sleep};
 
 struct BorrowToSqlParamsDebug<'a, T>(&'a [T]);
 
@@ -57,6 +76,83 @@ where
         statement,
         responses,
         rows_affected: None,
+        pool: RangePool::default(),
+        prefetch: 0,
+        prefetched: VecDeque::new(),
+        done: false,
+        row_timeout: None,
+        cursor: None,
+        _p: PhantomPinned,
+    })
+}
+
+/// Like `query`, but routes the notices emitted while the statement runs to `notices`.
+pub async fn query_with_notices<P, I>(
+    client: &InnerClient,
+    statement: Statement,
+    params: I,
+    notices: mpsc::UnboundedSender<DbError>,
+) -> Result<RowStream, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let buf = encode(client, &statement, params)?;
+    let mut responses =
+        client.send_with_notices(RequestMessages::Single(FrontendMessage::Raw(buf)), notices)?;
+
+    match responses.next().await? {
+        Message::BindComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    Ok(RowStream {
+        statement,
+        responses,
+        rows_affected: None,
+        pool: RangePool::default(),
+        prefetch: 0,
+        prefetched: VecDeque::new(),
+        done: false,
+        row_timeout: None,
+        cursor: None,
+        _p: PhantomPinned,
+    })
+}
+
+pub async fn query_text_params<P, I>(
+    client: &InnerClient,
+    statement: Statement,
+    params: I,
+) -> Result<RowStream, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let buf = if log_enabled!(Level::Debug) {
+        let params = params.into_iter().collect::<Vec<_>>();
+        debug!(
+            "executing statement {} with text parameters: {:?}",
+            statement.name(),
+            BorrowToSqlParamsDebug(params.as_slice()),
+        );
+        encode_text_params(client, &statement, params)?
+    } else {
+        encode_text_params(client, &statement, params)?
+    };
+    let responses = start(client, buf).await?;
+    Ok(RowStream {
+        statement,
+        responses,
+        rows_affected: None,
+        pool: RangePool::default(),
+        prefetch: 0,
+        prefetched: VecDeque::new(),
+        done: false,
+        row_timeout: None,
+        cursor: None,
         _p: PhantomPinned,
     })
 }
@@ -76,7 +172,7 @@ where
 
         client.with_buf(|buf| {
             frontend::parse("", query, param_oids.into_iter(), buf).map_err(Error::parse)?;
-            encode_bind_raw("", params, "", buf)?;
+            encode_bind_raw("", params, "", false, buf)?;
             frontend::describe(b'S', "", buf).map_err(Error::encode)?;
             frontend::execute("", 0, buf).map_err(Error::encode)?;
             frontend::sync(buf);
@@ -95,6 +191,12 @@ where
                     statement: Statement::unnamed(vec![], vec![]),
                     responses,
                     rows_affected: None,
+                    pool: RangePool::default(),
+                    prefetch: 0,
+                    prefetched: VecDeque::new(),
+                    done: false,
+                    row_timeout: None,
+                    cursor: None,
                     _p: PhantomPinned,
                 });
             }
@@ -115,6 +217,12 @@ where
                     statement: Statement::unnamed(vec![], columns),
                     responses,
                     rows_affected: None,
+                    pool: RangePool::default(),
+                    prefetch: 0,
+                    prefetched: VecDeque::new(),
+                    done: false,
+                    row_timeout: None,
+                    cursor: None,
                     _p: PhantomPinned,
                 });
             }
@@ -140,6 +248,87 @@ pub async fn query_portal(
         statement: portal.statement().clone(),
         responses,
         rows_affected: None,
+        pool: RangePool::default(),
+        prefetch: 0,
+        prefetched: VecDeque::new(),
+        done: false,
+        row_timeout: None,
+        cursor: None,
+        _p: PhantomPinned,
+    })
+}
+
+/// Starts executing a statement through a portal, fetching its rows in batches of `batch_size`.
+///
+/// Portals only live as long as the transaction they are created in, so if `begin` is set, the portal is created in a
+/// new transaction. The stream commits it once all rows have been fetched, and it is rolled back if the stream fails
+/// or is dropped early. The client refuses other requests until then, since they would run inside the transaction.
+pub async fn query_batched<P, I>(
+    client: &Arc<InnerClient>,
+    statement: Statement,
+    params: I,
+    batch_size: i32,
+    begin: bool,
+) -> Result<RowStream, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let transaction = if begin {
+        client.begin_cursor_transaction()?;
+        // Created before `BEGIN` is sent so that the client accepts other requests again if it fails.
+        let transaction = CursorTransaction {
+            client: client.clone(),
+            committed: false,
+        };
+        let buf = simple_query::encode(client, "BEGIN")?;
+        let mut responses = client
+            .send_in_cursor_transaction(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+        loop {
+            match responses.next().await? {
+                Message::ReadyForQuery(_) => break,
+                Message::CommandComplete(_) => {}
+                _ => return Err(Error::unexpected_message()),
+            }
+        }
+        Some(transaction)
+    } else {
+        None
+    };
+
+    let name = bind::next_name();
+    let buf = client.with_buf(|buf| {
+        encode_bind(&statement, params, &name, buf)?;
+        frontend::sync(buf);
+        Ok(buf.split().freeze())
+    })?;
+    let mut cursor = Cursor {
+        client: client.clone(),
+        portal: Portal::new(client, name, statement),
+        batch_size,
+        state: CursorState::Fetching,
+        rows: 0,
+        transaction,
+    };
+    let mut responses = cursor.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
+    match responses.next().await? {
+        Message::BindComplete => {}
+        _ => return Err(Error::unexpected_message()),
+    }
+
+    let responses = cursor.fetch()?;
+
+    Ok(RowStream {
+        statement: cursor.portal.statement().clone(),
+        responses,
+        rows_affected: None,
+        pool: RangePool::default(),
+        prefetch: 0,
+        prefetched: VecDeque::new(),
+        done: false,
+        row_timeout: None,
+        cursor: Some(Box::new(cursor)),
         _p: PhantomPinned,
     })
 }
@@ -219,12 +408,45 @@ where
     })
 }
 
+fn encode_text_params<P, I>(
+    client: &InnerClient,
+    statement: &Statement,
+    params: I,
+) -> Result<Bytes, Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    client.with_buf(|buf| {
+        encode_bind_with_format(statement, params, "", true, buf)?;
+        frontend::execute("", 0, buf).map_err(Error::encode)?;
+        frontend::sync(buf);
+        Ok(buf.split().freeze())
+    })
+}
+
 pub fn encode_bind<P, I>(
     statement: &Statement,
     params: I,
     portal: &str,
     buf: &mut BytesMut,
 ) -> Result<(), Error>
+where
+    P: BorrowToSql,
+    I: IntoIterator<Item = P>,
+    I::IntoIter: ExactSizeIterator,
+{
+    encode_bind_with_format(statement, params, portal, false, buf)
+}
+
+fn encode_bind_with_format<P, I>(
+    statement: &Statement,
+    params: I,
+    portal: &str,
+    text_params: bool,
+    buf: &mut BytesMut,
+) -> Result<(), Error>
 where
     P: BorrowToSql,
     I: IntoIterator<Item = P>,
@@ -239,6 +461,7 @@ where
         statement.name(),
         params.zip(statement.params().iter().cloned()),
         portal,
+        text_params,
         buf,
     )
 }
@@ -247,6 +470,7 @@ fn encode_bind_raw<P, I>(
     statement_name: &str,
     params: I,
     portal: &str,
+    text_params: bool,
     buf: &mut BytesMut,
 ) -> Result<(), Error>
 where
@@ -256,7 +480,14 @@ where
 {
     let (param_formats, params): (Vec<_>, Vec<_>) = params
         .into_iter()
-        .map(|(p, ty)| (p.borrow_to_sql().encode_format(&ty) as i16, (p, ty)))
+        .map(|(p, ty)| {
+            let format = if text_params {
+                Format::Text
+            } else {
+                p.borrow_to_sql().encode_format(&ty)
+            };
+            (format as i16, (p, ty))
+        })
         .unzip();
 
     let mut error_idx = 0;
@@ -289,6 +520,12 @@ pin_project! {
         statement: Statement,
         responses: Responses,
         rows_affected: Option<u64>,
+        pool: RangePool,
+        prefetch: usize,
+        prefetched: VecDeque<Result<ResultSetMessage, Error>>,
+        done: bool,
+        row_timeout: Option<Box<RowTimeout>>,
+        cursor: Option<Box<Cursor>>,
         #[pin]
         _p: PhantomPinned,
     }
@@ -297,29 +534,440 @@ pin_project! {
 impl Stream for RowStream {
     type Item = Result<Row, Error>;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match ready!(self.as_mut().poll_timed(cx)) {
+                Some(Ok(ResultSetMessage::Row(row))) => return Poll::Ready(Some(Ok(row))),
+                Some(Ok(ResultSetMessage::ResultSetEnd(_))) => {}
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl RowStream {
+    fn poll_timed(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ResultSetMessage, Error>>> {
+        #[cfg(feature = "runtime")]
+        if self.row_timeout.is_some() {
+            return self.poll_row_timeout(cx);
+        }
+
+        self.poll_prefetched(cx)
+    }
+
+    #[cfg(feature = "runtime")]
+    fn poll_row_timeout(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ResultSetMessage, Error>>> {
+        let timed_out = self.row_timeout.as_ref().is_some_and(|t| t.timed_out);
+        if !timed_out {
+            if let Poll::Ready(message) = self.as_mut().poll_prefetched(cx) {
+                if let Some(timeout) = self.as_mut().project().row_timeout {
+                    timeout.waiting = false;
+                }
+                return Poll::Ready(message);
+            }
+        }
+
+        let timeout = match self.project().row_timeout {
+            Some(timeout) => timeout,
+            None => return Poll::Pending,
+        };
+
+        if !timeout.timed_out {
+            // The timer starts when the consumer begins waiting, so time spent processing earlier rows doesn't count.
+            if !timeout.waiting {
+                timeout.waiting = true;
+                let deadline = Instant::now() + timeout.duration;
+                timeout.sleep.as_mut().reset(deadline);
+            }
+            ready!(timeout.sleep.as_mut().poll(cx));
+            timeout.timed_out = true;
+        }
+
+        match timeout.cancel.get_mut() {
+            Some(cancel) => {
+                ready!(cancel.as_mut().poll(cx));
+                *timeout.cancel.get_mut() = None;
+                Poll::Ready(Some(Err(Error::row_timeout())))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn poll_prefetched(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ResultSetMessage, Error>>> {
+        if self.prefetch == 0 {
+            return self.poll_message(cx);
+        }
+
+        // Pull in whatever rows have already arrived so the connection can keep reading from the socket while the
+        // consumer is busy with the rows we hand out.
+        while !self.done && self.prefetched.len() < self.prefetch {
+            let row = match self.as_mut().poll_message(cx) {
+                Poll::Ready(Some(row)) => row,
+                Poll::Ready(None) => {
+                    *self.as_mut().project().done = true;
+                    break;
+                }
+                Poll::Pending => break,
+            };
+            let this = self.as_mut().project();
+            *this.done = row.is_err();
+            this.prefetched.push_back(row);
+        }
+
+        let this = self.project();
+        match this.prefetched.pop_front() {
+            Some(row) => Poll::Ready(Some(row)),
+            None if *this.done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+
+    fn poll_message(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<ResultSetMessage, Error>>> {
         let this = self.project();
         loop {
             match ready!(this.responses.poll_next(cx)?) {
                 Message::DataRow(body) => {
-                    return Poll::Ready(Some(Ok(Row::new(this.statement.clone(), body)?)))
+                    let row = Row::new(this.statement.clone(), body, this.pool)?;
+                    if let Some(cursor) = this.cursor {
+                        cursor.rows += 1;
+                    }
+                    return Poll::Ready(Some(Ok(ResultSetMessage::Row(row))));
                 }
                 Message::CommandComplete(body) => {
-                    *this.rows_affected = Some(extract_row_affected(&body)?);
+                    let rows = match this.cursor {
+                        // This is the completion of the `COMMIT`, which has already been reported.
+                        Some(cursor) if cursor.state == CursorState::Committing => continue,
+                        // The tag only counts the rows returned by the last batch.
+                        Some(cursor) => {
+                            cursor.state = CursorState::Complete;
+                            cursor.rows
+                        }
+                        None => extract_row_affected(&body)?,
+                    };
+                    *this.rows_affected = Some(rows);
+                    return Poll::Ready(Some(Ok(ResultSetMessage::ResultSetEnd(rows))));
                 }
-                Message::EmptyQueryResponse | Message::PortalSuspended => {}
-                Message::ReadyForQuery(_) => return Poll::Ready(None),
+                Message::PortalSuspended => {
+                    if let Some(cursor) = this.cursor {
+                        cursor.state = CursorState::Suspended;
+                    }
+                }
+                Message::EmptyQueryResponse => {}
+                Message::ReadyForQuery(_) => match this.cursor {
+                    Some(cursor) => match cursor.advance()? {
+                        Some(responses) => *this.responses = responses,
+                        None => return Poll::Ready(None),
+                    },
+                    None => return Poll::Ready(None),
+                },
                 _ => return Poll::Ready(Some(Err(Error::unexpected_message()))),
             }
         }
     }
-}
 
-impl RowStream {
+    /// Enables prefetching of up to `n` rows.
+    ///
+    /// By default, rows are only pulled from the connection as the stream is polled, so the connection stops reading
+    /// from the socket once its small internal buffer fills up while the consumer is busy processing a row. With
+    /// prefetching enabled, each poll of the stream also moves any rows that have already arrived into a buffer of
+    /// up to `n` rows, allowing the connection to keep receiving data in the meantime. This can smooth throughput for
+    /// consumers that process each row slowly over high-latency links, at the cost of holding up to `n` decoded rows
+    /// in memory.
+    ///
+    /// A value of 0, the default, disables prefetching.
+    pub fn with_prefetch(mut self, n: usize) -> RowStream {
+        self.prefetch = n;
+        self
+    }
+
+    /// Fails the stream if no new row arrives within `timeout` of the consumer starting to wait for one.
+    ///
+    /// This detects stalled queries, such as a server-side function which stops producing rows, without limiting the
+    /// total run time of a query which keeps making progress. The timer only runs while the stream is being polled,
+    /// so time the consumer spends processing earlier rows is not counted.
+    ///
+    /// When the timeout expires, a cancellation request for the query is sent to the server using `cancel_token` and
+    /// `tls`, and the stream then yields an error for which `Error::is_row_timeout` returns `true`, followed by the end
+    /// of the stream. The remainder of the query's response is discarded once the stream is dropped.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn with_row_timeout<T>(
+        mut self,
+        timeout: Duration,
+        cancel_token: CancelToken,
+        tls: T,
+    ) -> RowStream
+    where
+        T: MakeTlsConnect<Socket> + 'static + Send,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let handle = self.query_handle();
+        let cancel = async move {
+            // If the query has already finished, there is nothing left to cancel.
+            let _ = cancel_token.cancel_query_handle(&handle, tls).await;
+        };
+        self.row_timeout = Some(Box::new(RowTimeout {
+            duration: timeout,
+            sleep: Box::pin(time::sleep(timeout)),
+            waiting: false,
+            timed_out: false,
+            cancel: Mutex::new(Some(Box::pin(cancel))),
+        }));
+        self
+    }
+
+    /// Converts the stream into one which also reports the end of each result set.
+    ///
+    /// The returned stream yields a [`ResultSetMessage::ResultSetEnd`] carrying the command's row count once the rows
+    /// of the statement have been read. A `RowStream` carries the result of exactly one statement, so the returned
+    /// stream yields exactly one `ResultSetEnd`, as its last item, including for statements which return no rows, and
+    /// even when `Config::fetch_count` makes the rows arrive in several batches. Prefetching, if enabled, applies to
+    /// the returned stream as well.
+    pub fn split_at_command_boundary(self) -> ResultSetStream {
+        ResultSetStream { rows: self }
+    }
+
+    /// Returns a handle identifying the query, which can be used to cancel it with
+    /// `CancelToken::cancel_query_handle`.
+    ///
+    /// Note that the stream is only returned once the server has started sending results, which for queries producing
+    /// few rows may not be until the query has finished.
+    pub fn query_handle(&self) -> QueryHandle {
+        self.responses.handle().clone()
+    }
+
     /// Returns the number of rows affected by the query.
     ///
     /// This function will return `None` until the stream has been exhausted.
     pub fn rows_affected(&self) -> Option<u64> {
         self.rows_affected
     }
+
+    /// Collects the rows of the stream into a column-major layout.
+    ///
+    /// `types` specifies the expected type of each column, and must match the columns returned by the query. Only
+    /// simple scalar types are supported; see [`ColumnData`] for the supported types and their representations.
+    /// `NULL` values are tracked in a validity bitmap for each column.
+    ///
+    /// [`ColumnData`]: crate::columnar::ColumnData
+    pub async fn collect_columnar(self, types: &[Type]) -> Result<ColumnStore, Error> {
+        let mut store = ColumnStore::new(self.statement.columns(), types)?;
+
+        let stream = self;
+        pin_mut!(stream);
+        while let Some(row) = stream.try_next().await? {
+            store.push(&row)?;
+        }
+
+        Ok(store)
+    }
+
+    /// Writes the rows of the stream to `w` as CSV, returning the number of rows written.
+    ///
+    /// Each row is written as a record of the text representations of its values, using the same conventions as
+    /// Postgres's `COPY ... WITH (FORMAT csv)`: fields are quoted when they contain a comma, quote, or line break,
+    /// empty strings are written as `""`, and `NULL` values as empty unquoted fields. If `headers` is true, a record
+    /// of the column names is written first.
+    ///
+    /// Values are converted to text locally, so only columns of boolean, integer, floating point, textual, JSON, and
+    /// `BYTEA` types are supported; cast other columns to `TEXT` in the query. Floating point values are written as
+    /// Postgres writes them with the default `extra_float_digits` setting, including its use of exponent notation for
+    /// very large and very small values.
+    ///
+    /// Requires the `csv` feature.
+    #[cfg(feature = "csv")]
+    pub async fn write_csv<W>(self, w: &mut W, headers: bool) -> Result<u64, Error>
+    where
+        W: std::io::Write,
+    {
+        if headers {
+            crate::csv::write_header(w, self.statement.columns())?;
+        }
+
+        let stream = self;
+        pin_mut!(stream);
+        let mut record = vec![];
+        let mut rows = 0;
+        while let Some(row) = stream.try_next().await? {
+            crate::csv::write_row(w, &row, &mut record)?;
+            rows += 1;
+        }
+
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CursorState {
+    /// A batch of rows is being fetched.
+    Fetching,
+    /// The last batch was full, so more rows may remain in the portal.
+    Suspended,
+    /// All rows have been fetched.
+    Complete,
+    /// All rows have been fetched and the transaction holding the portal is being committed.
+    Committing,
+    /// The stream has finished.
+    Done,
+}
+
+/// The state of a `RowStream` which fetches its rows from a portal in batches.
+struct Cursor {
+    // Declared first so that the transaction ends before the portal and its statement are closed, since the client
+    // refuses to send their `Close` messages until then.
+    transaction: Option<CursorTransaction>,
+    client: Arc<InnerClient>,
+    portal: Portal,
+    batch_size: i32,
+    state: CursorState,
+    rows: u64,
+}
+
+impl Cursor {
+    fn send(&self, messages: RequestMessages) -> Result<Responses, Error> {
+        match self.transaction {
+            Some(_) => self.client.send_in_cursor_transaction(messages),
+            None => self.client.send(messages),
+        }
+    }
+
+    fn fetch(&mut self) -> Result<Responses, Error> {
+        let buf = self.client.with_buf(|buf| {
+            frontend::execute(self.portal.name(), self.batch_size, buf).map_err(Error::encode)?;
+            frontend::sync(buf);
+            Ok(buf.split().freeze())
+        })?;
+        self.state = CursorState::Fetching;
+        self.send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+    }
+
+    /// Moves on to the next request once the current one has finished, returning its responses.
+    ///
+    /// Returns `None` once there is nothing left to do, including after an error.
+    fn advance(&mut self) -> Result<Option<Responses>, Error> {
+        match self.state {
+            CursorState::Suspended => self.fetch().map(Some),
+            CursorState::Complete if self.transaction.is_some() => {
+                let buf = self.client.with_buf(|buf| {
+                    frontend::query("COMMIT", buf).map_err(Error::encode)?;
+                    Ok(buf.split().freeze())
+                })?;
+                self.state = CursorState::Committing;
+                self.send(RequestMessages::Single(FrontendMessage::Raw(buf)))
+                    .map(Some)
+            }
+            CursorState::Complete => {
+                self.state = CursorState::Done;
+                Ok(None)
+            }
+            CursorState::Committing => {
+                if let Some(transaction) = &mut self.transaction {
+                    transaction.committed = true;
+                    self.client.end_cursor_transaction();
+                }
+                self.state = CursorState::Done;
+                Ok(None)
+            }
+            CursorState::Fetching | CursorState::Done => Ok(None),
+        }
+    }
+}
+
+/// A transaction begun to hold the portal of a `Cursor`, which is rolled back when dropped unless it was committed.
+///
+/// The client refuses other requests until the transaction ends.
+struct CursorTransaction {
+    client: Arc<InnerClient>,
+    committed: bool,
+}
+
+impl Drop for CursorTransaction {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+
+        let buf = self.client.with_buf(|buf| {
+            frontend::query("ROLLBACK", buf).unwrap();
+            buf.split().freeze()
+        });
+        let _ = self
+            .client
+            .send_in_cursor_transaction(RequestMessages::Single(FrontendMessage::Raw(buf)));
+        self.client.end_cursor_transaction();
+    }
+}
+
+#[cfg(feature = "runtime")]
+struct RowTimeout {
+    duration: Duration,
+    sleep: Pin<Box<Sleep>>,
+    waiting: bool,
+    timed_out: bool,
+    // The mutex only keeps `RowStream` `Sync`; the future is always accessed through `get_mut`.
+    cancel: Mutex<Option<BoxFuture<'static, ()>>>,
+}
+
+#[cfg(not(feature = "runtime"))]
+enum RowTimeout {}
+
+/// Message returned by the `ResultSetStream` stream.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ResultSetMessage {
+    /// A row of data.
+    Row(Row),
+    /// A command has completed, ending its result set.
+    ///
+    /// The number of rows modified or selected is returned.
+    ResultSetEnd(u64),
+}
+
+pin_project! {
+    /// A stream of table rows, followed by a marker at the end of the result set.
+    ///
+    /// Created by `RowStream::split_at_command_boundary`.
+    pub struct ResultSetStream {
+        #[pin]
+        rows: RowStream,
+    }
+}
+
+impl Stream for ResultSetStream {
+    type Item = Result<ResultSetMessage, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().rows.poll_timed(cx)
+    }
+}
+
+impl ResultSetStream {
+    /// Returns a handle identifying the query, which can be used to cancel it with
+    /// `CancelToken::cancel_query_handle`.
+    pub fn query_handle(&self) -> QueryHandle {
+        self.rows.query_handle()
+    }
+
+    /// Returns the number of rows affected by the statement.
+    ///
+    /// This function will return `None` until the stream has yielded its `ResultSetEnd`.
+    pub fn rows_affected(&self) -> Option<u64> {
+        self.rows.rows_affected()
+    }
 }
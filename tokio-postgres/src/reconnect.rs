@@ -0,0 +1,327 @@
+use crate::lru::LruCache;
+use crate::tls::{MakeTlsConnect, TlsConnect};
+use crate::types::ToSql;
+use crate::{Client, Config, Error, Row, Socket, Statement};
+use futures_channel::mpsc;
+use futures_util::future::BoxFuture;
+use futures_util::lock::{Mutex, MutexGuard};
+use futures_util::stream::FuturesUnordered;
+use futures_util::{FutureExt, Stream};
+use log::info;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time;
+
+const DEFAULT_RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 100;
+
+struct State {
+    client: Arc<Client>,
+    statements: LruCache<String, Statement>,
+    /// Set when the connection was lost inside of a transaction block, until that transaction is ended.
+    transaction_lost: bool,
+}
+
+impl State {
+    /// Fails the statements of a transaction which was lost along with its connection, rather than running them on
+    /// the new connection outside of the transaction.
+    fn check_transaction(&mut self, query: &str) -> Result<(), Error> {
+        if !self.transaction_lost {
+            return Ok(());
+        }
+
+        let mut words = query.split_whitespace().map(|w| w.trim_end_matches(';'));
+        let first = words.next().unwrap_or("");
+        let second = words.next().unwrap_or("");
+        let ends_transaction = ["COMMIT", "END", "ROLLBACK", "ABORT"]
+            .iter()
+            .any(|keyword| first.eq_ignore_ascii_case(keyword))
+            && !second.eq_ignore_ascii_case("TO")
+            && !second.eq_ignore_ascii_case("PREPARED");
+        if !ends_transaction {
+            return Err(Error::closed());
+        }
+
+        self.transaction_lost = false;
+        if first.eq_ignore_ascii_case("COMMIT") || first.eq_ignore_ascii_case("END") {
+            // The server rolled the transaction back when the connection was lost.
+            return Err(Error::closed());
+        }
+        Ok(())
+    }
+}
+
+/// A client which transparently reconnects to the database when its connection is lost.
+///
+/// Each operation first checks whether the current connection has closed, and if it has, opens a new one with the
+/// stored `Config` before proceeding. Connection attempts which fail for transient reasons, such as the server still
+/// starting up, are retried every `connect_retry_backoff` until the reconnect timeout elapses, while other failures,
+/// such as authentication errors, are returned immediately. Operations issued while a reconnect is in progress wait
+/// for it to complete, for up to the reconnect timeout.
+///
+/// Statements run through the `query` family of methods are prepared once and cached by their query text, up to the
+/// statement cache capacity, after which the least recently used statement is evicted. When the client reconnects,
+/// the cached statements are prepared again on the new connection. Statements which can no longer be prepared, for
+/// example because a table they reference was dropped, are removed from the cache.
+///
+/// # In-flight work
+///
+/// Only the connection is restored; session state is not. A statement which is in progress when the connection is
+/// lost fails with an error for which `Error::is_closed` returns `true`, and is not retried, since it cannot be known
+/// whether the server executed it.
+///
+/// In particular, transactions cannot survive a reconnect, since the server rolls the transaction back when the
+/// connection is lost. If the connection was inside of a transaction block, as reported by the server, the methods of
+/// this type fail every later statement with an error for which `Error::is_closed` returns `true` until the
+/// transaction is ended, rather than running them outside of the transaction on the new connection. A `ROLLBACK` or
+/// `ABORT` ends it and succeeds, while a `COMMIT` or `END` ends it and fails. Alternatively, obtain the current
+/// connection's `Client` with the `client` method and issue every statement of the transaction on it. That `Client`
+/// is never reconnected, so if its connection is lost, the remaining statements fail as well.
+///
+/// Like `Client`, this is one half of what is returned when connecting. The other half, a `ReconnectingConnection`,
+/// performs the IO of every connection the client opens, and should be spawned onto an executor.
+///
+/// Requires the `runtime` Cargo feature (enabled by default).
+pub struct ReconnectingClient<T> {
+    config: Config,
+    tls: T,
+    reconnect_timeout: Duration,
+    connections: mpsc::UnboundedSender<BoxFuture<'static, ()>>,
+    state: Mutex<State>,
+}
+
+impl<T> ReconnectingClient<T>
+where
+    T: MakeTlsConnect<Socket> + Clone + 'static + Sync + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    /// Opens a connection to the database, returning a client which will reconnect with the same configuration
+    /// whenever that connection is lost.
+    pub async fn connect(
+        config: Config,
+        tls: T,
+    ) -> Result<(ReconnectingClient<T>, ReconnectingConnection), Error> {
+        let (sender, receiver) = mpsc::unbounded();
+        let client = connect_client(&config, tls.clone(), &sender).await?;
+
+        let client = ReconnectingClient {
+            config,
+            tls,
+            reconnect_timeout: DEFAULT_RECONNECT_TIMEOUT,
+            connections: sender,
+            state: Mutex::new(State {
+                client: Arc::new(client),
+                statements: LruCache::new(Some(DEFAULT_STATEMENT_CACHE_CAPACITY)),
+                transaction_lost: false,
+            }),
+        };
+        let connection = ReconnectingConnection {
+            receiver,
+            connections: FuturesUnordered::new(),
+            done: false,
+        };
+
+        Ok((client, connection))
+    }
+
+    /// Sets how long an operation waits for the connection to be reestablished before failing.
+    ///
+    /// An error for which `Error::is_timeout` returns `true` is returned if the timeout elapses.
+    ///
+    /// Defaults to 30 seconds.
+    pub fn set_reconnect_timeout(&mut self, timeout: Duration) {
+        self.reconnect_timeout = timeout;
+    }
+
+    /// Sets the number of prepared statements cached by their query text.
+    ///
+    /// Defaults to 100.
+    pub fn set_statement_cache_capacity(&mut self, capacity: usize) {
+        self.state.get_mut().statements.set_capacity(capacity);
+    }
+
+    /// Returns the client for the current connection, reconnecting first if it has closed.
+    ///
+    /// The returned client is not itself reconnected if its connection is later lost.
+    pub async fn client(&self) -> Result<Arc<Client>, Error> {
+        let state = self.state().await?;
+        Ok(state.client.clone())
+    }
+
+    /// Like `Client::query`, but prepares and caches the statement for reuse across reconnects.
+    pub async fn query(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error> {
+        let (client, statement) = self.statement(query).await?;
+        client.query(&statement, params).await
+    }
+
+    /// Like `Client::query_one`, but prepares and caches the statement for reuse across reconnects.
+    pub async fn query_one(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, Error> {
+        let (client, statement) = self.statement(query).await?;
+        client.query_one(&statement, params).await
+    }
+
+    /// Like `Client::query_opt`, but prepares and caches the statement for reuse across reconnects.
+    pub async fn query_opt(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, Error> {
+        let (client, statement) = self.statement(query).await?;
+        client.query_opt(&statement, params).await
+    }
+
+    /// Like `Client::execute`, but prepares and caches the statement for reuse across reconnects.
+    pub async fn execute(&self, query: &str, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error> {
+        let (client, statement) = self.statement(query).await?;
+        client.execute(&statement, params).await
+    }
+
+    /// Like `Client::batch_execute`.
+    pub async fn batch_execute(&self, query: &str) -> Result<(), Error> {
+        let client = {
+            let mut state = self.state().await?;
+            state.check_transaction(query)?;
+            state.client.clone()
+        };
+        client.batch_execute(query).await
+    }
+
+    async fn statement(&self, query: &str) -> Result<(Arc<Client>, Statement), Error> {
+        let client = {
+            let mut state = self.state().await?;
+            state.check_transaction(query)?;
+            if let Some(statement) = state.statements.get(query).cloned() {
+                return Ok((state.client.clone(), statement));
+            }
+            state.client.clone()
+        };
+
+        // Prepare without holding the lock so that other operations are not blocked on the round trip.
+        let statement = client.prepare(query).await?;
+
+        let mut state = self.state.lock().await;
+        if Arc::ptr_eq(&state.client, &client) {
+            state
+                .statements
+                .insert(query.to_string(), statement.clone());
+        }
+        Ok((client, statement))
+    }
+
+    async fn state(&self) -> Result<MutexGuard<'_, State>, Error> {
+        let reconnect = async {
+            let mut state = self.state.lock().await;
+            if state.client.is_closed() {
+                info!("connection closed, reconnecting");
+                if state.client.inner().in_transaction() {
+                    state.transaction_lost = true;
+                }
+                self.reconnect(&mut state).await?;
+            }
+            Ok(state)
+        };
+
+        match time::timeout(self.reconnect_timeout, reconnect).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::__private_api_timeout()),
+        }
+    }
+
+    async fn reconnect(&self, state: &mut State) -> Result<(), Error> {
+        // Keep trying until the reconnect timeout cuts us off, since the server may take a while to come back.
+        let client = loop {
+            match connect_client(&self.config, self.tls.clone(), &self.connections).await {
+                Ok(client) => break client,
+                Err(e) if e.is_transient_connect() => {
+                    info!("reconnect failed, retrying: {}", e);
+                    time::sleep(self.config.connect_retry_backoff).await;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let mut statements = vec![];
+        for (query, statement) in state.statements.iter() {
+            match client.prepare_typed(query, statement.params()).await {
+                Ok(statement) => statements.push((query.clone(), statement)),
+                Err(e) => info!("dropping cached statement which failed to prepare: {}", e),
+            }
+        }
+
+        state.client = Arc::new(client);
+        // Reinsert from least to most recently used so that the cache keeps its order.
+        state.statements.clear();
+        for (query, statement) in statements {
+            state.statements.insert(query, statement);
+        }
+        Ok(())
+    }
+}
+
+async fn connect_client<T>(
+    config: &Config,
+    tls: T,
+    connections: &mpsc::UnboundedSender<BoxFuture<'static, ()>>,
+) -> Result<Client, Error>
+where
+    T: MakeTlsConnect<Socket> + 'static + Send,
+    T::TlsConnect: Send,
+    T::Stream: Send,
+    <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    let (client, connection) = config.connect(tls).await?;
+    let connection = connection.map(|r| {
+        if let Err(e) = r {
+            info!("connection error: {}", e);
+        }
+    });
+    // If the `ReconnectingConnection` was dropped, the connection is too, and the client will report itself closed.
+    let _ = connections.unbounded_send(Box::pin(connection));
+    Ok(client)
+}
+
+/// The IO half of a `ReconnectingClient`.
+///
+/// It drives each connection opened by the client, and should generally be spawned off onto an executor to run in the
+/// background. It resolves once the client has been dropped and its last connection has closed.
+#[must_use = "futures do nothing unless polled"]
+pub struct ReconnectingConnection {
+    receiver: mpsc::UnboundedReceiver<BoxFuture<'static, ()>>,
+    connections: FuturesUnordered<BoxFuture<'static, ()>>,
+    done: bool,
+}
+
+impl Future for ReconnectingConnection {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        while !self.done {
+            match Pin::new(&mut self.receiver).poll_next(cx) {
+                Poll::Ready(Some(connection)) => self.connections.push(connection),
+                Poll::Ready(None) => self.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        while let Poll::Ready(Some(())) = Pin::new(&mut self.connections).poll_next(cx) {}
+
+        if self.done && self.connections.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -3,12 +3,15 @@
 use crate::row::sealed::{AsName, Sealed};
 use crate::simple_query::SimpleColumn;
 use crate::statement::Column;
-use crate::types::{FromSql, Type, WrongType};
+use crate::types::{FromSql, FromSqlOwned, Type, WrongType};
 use crate::{Error, Statement};
 use fallible_iterator::FallibleIterator;
+use parking_lot::Mutex;
 use postgres_protocol::message::backend::DataRowBody;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::fmt;
-use std::ops::Range;
+use std::ops::{Index, Range};
 use std::str;
 use std::sync::Arc;
 
@@ -94,12 +97,57 @@ where
     }
 }
 
+/// The positions of a row's values within its body, or `None` for `NULL` values.
+type Ranges = Arc<[Option<Range<usize>>]>;
+
+// Only a handful of rows are typically alive at once when a stream is consumed row by row, so there's no point in
+// tracking more buffers than this.
+const RANGE_POOL_CAPACITY: usize = 4;
+
+/// A free list of the column range buffers of the rows yielded by a `RowStream`.
+///
+/// The pool keeps a reference to the buffers of the rows it most recently produced. Once such a row has been dropped,
+/// the pool holds the only reference to its buffer, which is then reused for the next row rather than allocating a new
+/// one. The buffers of rows which are still alive are left untouched.
+#[derive(Default)]
+pub(crate) struct RangePool {
+    buffers: Vec<Ranges>,
+    scratch: Vec<Option<Range<usize>>>,
+}
+
+impl RangePool {
+    fn take(&mut self, body: &DataRowBody) -> Result<Ranges, Error> {
+        self.scratch.clear();
+        let mut it = body.ranges();
+        while let Some(range) = it.next().map_err(Error::parse)? {
+            self.scratch.push(range);
+        }
+
+        for buffer in &mut self.buffers {
+            if let Some(ranges) = Arc::get_mut(buffer) {
+                if ranges.len() == self.scratch.len() {
+                    ranges.clone_from_slice(&self.scratch);
+                    return Ok(buffer.clone());
+                }
+            }
+        }
+
+        let ranges = Ranges::from(&self.scratch[..]);
+        if self.buffers.len() == RANGE_POOL_CAPACITY {
+            // Every tracked row is still alive, so track the newest one instead of the oldest.
+            self.buffers.remove(0);
+        }
+        self.buffers.push(ranges.clone());
+        Ok(ranges)
+    }
+}
+
 /// A row of data returned from the database by a query.
 #[derive(Clone)]
 pub struct Row {
     statement: Statement,
     body: DataRowBody,
-    ranges: Vec<Option<Range<usize>>>,
+    ranges: Ranges,
 }
 
 impl fmt::Debug for Row {
@@ -111,8 +159,12 @@ impl fmt::Debug for Row {
 }
 
 impl Row {
-    pub(crate) fn new(statement: Statement, body: DataRowBody) -> Result<Row, Error> {
-        let ranges = body.ranges().collect().map_err(Error::parse)?;
+    pub(crate) fn new(
+        statement: Statement,
+        body: DataRowBody,
+        pool: &mut RangePool,
+    ) -> Result<Row, Error> {
+        let ranges = pool.take(&body)?;
         Ok(Row {
             statement,
             body,
@@ -120,6 +172,20 @@ impl Row {
         })
     }
 
+    /// Creates a row from a body whose ranges have already been parsed.
+    #[cfg(feature = "bumpalo")]
+    pub(crate) fn from_parts(
+        statement: Statement,
+        body: DataRowBody,
+        ranges: &[Option<Range<usize>>],
+    ) -> Row {
+        Row {
+            statement,
+            body,
+            ranges: ranges.into(),
+        }
+    }
+
     /// Returns information about the columns of data in the row.
     pub fn columns(&self) -> &[Column] {
         self.statement.columns()
@@ -135,6 +201,15 @@ impl Row {
         self.columns().len()
     }
 
+    /// Returns the index of the column with the given name, if there is one.
+    ///
+    /// Names are matched in the same way as by `get`, preferring an exact match and falling back to an ASCII
+    /// case-insensitive one. When reading the same column from many rows, the index can be resolved once and passed
+    /// to `get` in place of the name to avoid repeating the lookup for every row.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        name.__idx(self.columns())
+    }
+
     /// Deserializes a value from the row.
     ///
     /// The value can be specified either by its numeric index in the row, or by its column name.
@@ -163,32 +238,329 @@ impl Row {
         self.get_inner(&idx)
     }
 
+    /// Decodes a `JSON` or `JSONB` column into a `serde_json::Value`.
+    ///
+    /// Returns `Ok(None)` if the value is `NULL`.
+    #[cfg(feature = "with-serde_json-1")]
+    pub fn json_value<I>(&self, idx: I) -> Result<Option<serde_json_1::Value>, Error>
+    where
+        I: RowIndex + fmt::Display,
+    {
+        self.get_inner(&idx)
+    }
+
     fn get_inner<'a, I, T>(&'a self, idx: &I) -> Result<T, Error>
     where
         I: RowIndex + fmt::Display,
         T: FromSql<'a>,
     {
-        let idx = match idx.__idx(self.columns()) {
+        get_value(self.columns(), &self.body, &self.ranges, idx)
+    }
+
+    /// Get the raw bytes for the column at the given index.
+    pub(crate) fn col_buffer(&self, idx: usize) -> Option<&[u8]> {
+        let range = self.ranges[idx].to_owned()?;
+        Some(&self.body.buffer()[range])
+    }
+
+    /// Detaches the row's data from its statement.
+    fn into_data(self) -> RowData {
+        RowData {
+            body: self.body,
+            ranges: self.ranges,
+        }
+    }
+}
+
+/// Accesses the raw bytes of a value in the row.
+///
+/// The value can be specified either by its numeric index in the row, or by its column name. The bytes are the
+/// value's binary wire encoding, exactly as sent by the server. Use `Row::get` to decode a value instead.
+///
+/// # Panics
+///
+/// Panics if the index is out of bounds or if the value is `NULL`.
+impl<I> Index<I> for Row
+where
+    I: RowIndex + fmt::Display,
+{
+    type Output = [u8];
+
+    #[track_caller]
+    fn index(&self, idx: I) -> &[u8] {
+        let i = match idx.__idx(self.columns()) {
+            Some(i) => i,
+            None => panic!("invalid column `{}`", idx),
+        };
+
+        match self.col_buffer(i) {
+            Some(buf) => buf,
+            None => panic!("column {} is NULL", idx),
+        }
+    }
+}
+
+/// A wrapper around a `Row` which caches decoded values.
+///
+/// `Row::get` runs the type's `FromSql` implementation on every call. `CachedRow` instead decodes each column at most
+/// once per requested type, and returns clones of the cached value on later calls. This avoids redundant work when
+/// values which are expensive to decode, such as arrays or composites, are read repeatedly. Only owned types can be
+/// cached; use `CachedRow::row` to access borrowed values.
+pub struct CachedRow {
+    row: Row,
+    cache: Mutex<HashMap<(usize, TypeId), Box<dyn Any + Send + Sync>>>,
+}
+
+impl fmt::Debug for CachedRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedRow")
+            .field("row", &self.row)
+            .field("cached", &self.cache.lock().len())
+            .finish()
+    }
+}
+
+impl From<Row> for CachedRow {
+    fn from(row: Row) -> CachedRow {
+        CachedRow::new(row)
+    }
+}
+
+impl CachedRow {
+    /// Creates a new `CachedRow` wrapping `row`.
+    pub fn new(row: Row) -> CachedRow {
+        CachedRow {
+            row,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the wrapped row.
+    pub fn row(&self) -> &Row {
+        &self.row
+    }
+
+    /// Consumes the `CachedRow`, returning the wrapped row.
+    pub fn into_inner(self) -> Row {
+        self.row
+    }
+
+    /// Like `Row::get`, but caches the decoded value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the value cannot be converted to the specified type.
+    #[track_caller]
+    pub fn get<I, T>(&self, idx: I) -> T
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSqlOwned + Clone + Send + Sync + 'static,
+    {
+        match self.get_inner(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `CachedRow::get`, but returns a `Result` rather than panicking.
+    ///
+    /// Errors are not cached.
+    pub fn try_get<I, T>(&self, idx: I) -> Result<T, Error>
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSqlOwned + Clone + Send + Sync + 'static,
+    {
+        self.get_inner(&idx)
+    }
+
+    fn get_inner<I, T>(&self, idx: &I) -> Result<T, Error>
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSqlOwned + Clone + Send + Sync + 'static,
+    {
+        let idx = match idx.__idx(self.row.columns()) {
             Some(idx) => idx,
             None => return Err(Error::column(idx.to_string())),
         };
+        let key = (idx, TypeId::of::<T>());
 
-        let ty = self.columns()[idx].type_();
-        if !T::accepts(ty) {
-            return Err(Error::from_sql(
-                Box::new(WrongType::new::<T>(ty.clone())),
-                idx,
-            ));
+        if let Some(value) = self.cache.lock().get(&key) {
+            // The key includes `T`'s `TypeId`, so the downcast can't fail.
+            return Ok(value.downcast_ref::<T>().unwrap().clone());
         }
 
-        FromSql::from_sql_nullable(ty, self.col_buffer(idx)).map_err(|e| Error::from_sql(e, idx))
+        // The lock isn't held while decoding so that `FromSql` implementations can't deadlock on it.
+        let value = self.row.get_inner::<_, T>(&idx)?;
+        self.cache.lock().insert(key, Box::new(value.clone()));
+        Ok(value)
     }
+}
 
-    /// Get the raw bytes for the column at the given index.
-    fn col_buffer(&self, idx: usize) -> Option<&[u8]> {
-        let range = self.ranges[idx].to_owned()?;
-        Some(&self.body.buffer()[range])
+#[derive(Clone)]
+struct RowData {
+    body: DataRowBody,
+    ranges: Ranges,
+}
+
+/// The complete result of a query, independent of the statement that produced it.
+///
+/// Unlike a `Vec<Row>`, a `ResultSet` does not hold on to the query's `Statement`. It owns its column information
+/// and the raw data of its rows, so it can be cached or returned from a function without keeping the statement
+/// alive. Values are decoded when they are accessed through a `ResultSetRow`.
+///
+/// Created by `Client::query_owned`.
+#[derive(Clone)]
+pub struct ResultSet {
+    columns: Vec<Column>,
+    rows: Vec<RowData>,
+}
+
+impl fmt::Debug for ResultSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResultSet")
+            .field("columns", &self.columns)
+            .field("rows", &self.rows.len())
+            .finish()
+    }
+}
+
+impl ResultSet {
+    pub(crate) fn new(columns: Vec<Column>, rows: Vec<Row>) -> ResultSet {
+        ResultSet {
+            columns,
+            rows: rows.into_iter().map(Row::into_data).collect(),
+        }
+    }
+
+    /// Returns information about the columns of the result set.
+    ///
+    /// The columns are available even if the result set contains no rows.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Determines if the result set contains no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns the number of rows in the result set.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the row at the given index, if there is one.
+    pub fn get(&self, idx: usize) -> Option<ResultSetRow<'_>> {
+        self.rows.get(idx).map(|data| ResultSetRow {
+            columns: &self.columns,
+            data,
+        })
+    }
+
+    /// Returns an iterator over the rows of the result set.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = ResultSetRow<'_>> + '_ {
+        self.rows.iter().map(move |data| ResultSetRow {
+            columns: &self.columns,
+            data,
+        })
+    }
+}
+
+/// A row of a `ResultSet`.
+#[derive(Copy, Clone)]
+pub struct ResultSetRow<'a> {
+    columns: &'a [Column],
+    data: &'a RowData,
+}
+
+impl fmt::Debug for ResultSetRow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResultSetRow")
+            .field("columns", &self.columns)
+            .finish()
+    }
+}
+
+impl<'a> ResultSetRow<'a> {
+    /// Returns information about the columns of data in the row.
+    pub fn columns(&self) -> &'a [Column] {
+        self.columns
+    }
+
+    /// Determines if the row contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
+
+    /// Returns the number of values in the row.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Deserializes a value from the row.
+    ///
+    /// The value can be specified either by its numeric index in the row, or by its column name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the value cannot be converted to the specified type.
+    #[track_caller]
+    pub fn get<I, T>(&self, idx: I) -> T
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSql<'a>,
+    {
+        match self.get_inner(&idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving column {}: {}", idx, err),
+        }
+    }
+
+    /// Like `ResultSetRow::get`, but returns a `Result` rather than panicking.
+    pub fn try_get<I, T>(&self, idx: I) -> Result<T, Error>
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSql<'a>,
+    {
+        self.get_inner(&idx)
+    }
+
+    fn get_inner<I, T>(&self, idx: &I) -> Result<T, Error>
+    where
+        I: RowIndex + fmt::Display,
+        T: FromSql<'a>,
+    {
+        get_value(self.columns, &self.data.body, &self.data.ranges, idx)
+    }
+}
+
+/// Decodes a value of a row, checking that its column's type is compatible with `T`.
+fn get_value<'a, I, T>(
+    columns: &[Column],
+    body: &'a DataRowBody,
+    ranges: &[Option<Range<usize>>],
+    idx: &I,
+) -> Result<T, Error>
+where
+    I: RowIndex + fmt::Display,
+    T: FromSql<'a>,
+{
+    let idx = match idx.__idx(columns) {
+        Some(idx) => idx,
+        None => return Err(Error::column(idx.to_string())),
+    };
+
+    let column = &columns[idx];
+    let ty = column.type_();
+    if !T::accepts(ty) {
+        return Err(Error::from_sql(
+            Box::new(WrongType::new::<T>(ty.clone()).with_column(column.name())),
+            idx,
+        ));
+    }
+
+    let buf = ranges[idx].clone().map(|range| &body.buffer()[range]);
+    FromSql::from_sql_nullable(ty, buf).map_err(|e| Error::from_sql(e, idx))
 }
 
 impl AsName for SimpleColumn {
@@ -234,6 +606,15 @@ impl SimpleQueryRow {
         self.columns.len()
     }
 
+    /// Returns the index of the column with the given name, if there is one.
+    ///
+    /// Names are matched in the same way as by `get`, preferring an exact match and falling back to an ASCII
+    /// case-insensitive one. When reading the same column from many rows, the index can be resolved once and passed
+    /// to `get` in place of the name to avoid repeating the lookup for every row.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        name.__idx(&self.columns)
+    }
+
     /// Returns a value from the row.
     ///
     /// The value can be specified either by its numeric index in the row, or by its column name.
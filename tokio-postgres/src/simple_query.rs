@@ -51,19 +51,22 @@ pub async fn batch_execute(client: &InnerClient, query: &str) -> Result<(), Erro
     let buf = encode(client, query)?;
     let mut responses = client.send(RequestMessages::Single(FrontendMessage::Raw(buf)))?;
 
+    let mut completed = 0;
     loop {
-        match responses.next().await? {
+        let message = responses
+            .next()
+            .await
+            .map_err(|e| e.with_statement_index(completed))?;
+        match message {
             Message::ReadyForQuery(_) => return Ok(()),
-            Message::CommandComplete(_)
-            | Message::EmptyQueryResponse
-            | Message::RowDescription(_)
-            | Message::DataRow(_) => {}
+            Message::CommandComplete(_) => completed += 1,
+            Message::EmptyQueryResponse | Message::RowDescription(_) | Message::DataRow(_) => {}
             _ => return Err(Error::unexpected_message()),
         }
     }
 }
 
-fn encode(client: &InnerClient, query: &str) -> Result<Bytes, Error> {
+pub(crate) fn encode(client: &InnerClient, query: &str) -> Result<Bytes, Error> {
     client.with_buf(|buf| {
         frontend::query(query, buf).map_err(Error::encode)?;
         Ok(buf.split().freeze())
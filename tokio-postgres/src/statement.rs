@@ -1,7 +1,8 @@
 use crate::client::InnerClient;
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
-use crate::types::Type;
+use crate::row::RowIndex;
+use crate::types::{Oid, Type};
 use postgres_protocol::message::frontend;
 use std::sync::{Arc, Weak};
 
@@ -68,10 +69,25 @@ impl Statement {
         &self.0.params
     }
 
+    /// Returns the OIDs of the statement's parameter types.
+    ///
+    /// This is equivalent to mapping `params` through `Type::oid`.
+    pub fn parameter_oids(&self) -> Vec<Oid> {
+        self.0.params.iter().map(Type::oid).collect()
+    }
+
     /// Returns information about the columns returned when the statement is queried.
     pub fn columns(&self) -> &[Column] {
         &self.0.columns
     }
+
+    /// Returns the index of the column with the given name, if there is one.
+    ///
+    /// Names are matched in the same way as by `Row::get`, so the index can be resolved once for the statement and
+    /// then used to read the column from each of its rows.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        name.__idx(self.columns())
+    }
 }
 
 impl std::fmt::Debug for Statement {
@@ -85,7 +101,7 @@ impl std::fmt::Debug for Statement {
 }
 
 /// Information about a column of a query.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Column {
     pub(crate) name: String,
     pub(crate) table_oid: Option<u32>,
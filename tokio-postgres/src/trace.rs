@@ -0,0 +1,59 @@
+//! Instrumentation of client methods with `tracing` spans.
+
+use crate::{Error, Statement};
+use std::future::Future;
+use std::time::Instant;
+use tracing::{Instrument, Span};
+
+/// Creates the span for a client method.
+///
+/// The span is named `$name`, and records the process ID of the connection's backend up front. The statement's name
+/// and parameter count, the row count, the elapsed time, and any error are recorded later.
+macro_rules! span {
+    ($name:literal, $client:expr) => {
+        tracing::debug_span!(
+            $name,
+            statement = tracing::field::Empty,
+            params = tracing::field::Empty,
+            pid = $client.backend_pid(),
+            rows = tracing::field::Empty,
+            elapsed_us = tracing::field::Empty,
+            error = tracing::field::Empty,
+        )
+    };
+}
+
+pub(crate) use span;
+
+/// Records the name and parameter count of the statement being executed in `span`.
+pub(crate) fn record_statement(span: &Span, statement: &Statement) {
+    span.record("statement", statement.name());
+    span.record("params", statement.params().len());
+}
+
+/// Runs `future` within `span`, recording its elapsed time and its outcome.
+///
+/// `rows` extracts the number of rows returned or affected from a successful result, if there is one.
+pub(crate) async fn instrument<F, T>(
+    span: Span,
+    future: F,
+    rows: impl FnOnce(&T) -> Option<u64>,
+) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let start = Instant::now();
+    let result = future.instrument(span.clone()).await;
+    span.record("elapsed_us", start.elapsed().as_micros() as u64);
+    match &result {
+        Ok(value) => {
+            if let Some(rows) = rows(value) {
+                span.record("rows", rows);
+            }
+        }
+        Err(e) => {
+            span.record("error", tracing::field::display(e));
+        }
+    }
+    result
+}
@@ -1,29 +1,42 @@
+use crate::binary_copy::BinaryCopyOutRow;
 use crate::codec::FrontendMessage;
 use crate::connection::RequestMessages;
 use crate::copy_out::CopyOutStream;
+use crate::error::DbError;
 use crate::query::RowStream;
+use crate::row::RowIndex;
 #[cfg(feature = "runtime")]
 use crate::tls::MakeTlsConnect;
 use crate::tls::TlsConnect;
-use crate::types::{BorrowToSql, ToSql, Type};
+use crate::types::{BorrowToSql, FromSqlOwned, ToSql, Type};
 #[cfg(feature = "runtime")]
 use crate::Socket;
 use crate::{
-    bind, query, slice_iter, CancelToken, Client, CopyInSink, Error, Portal, Row,
-    SimpleQueryMessage, Statement, ToStatement,
+    bind, query, slice_iter, CancelToken, Client, Column, CopyInSink, DropBehavior, DuplicateKeys,
+    Error, ExplainOptions, Portal, ResultSet, Row, SimpleQueryMessage, Statement, ToStatement,
 };
 use bytes::Buf;
 use futures_util::TryStreamExt;
+use postgres_protocol::escape::{escape_identifier, escape_literal};
 use postgres_protocol::message::frontend;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::thread;
 use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "runtime")]
+use tokio::time::Instant;
 
 /// A representation of a PostgreSQL database transaction.
 ///
-/// Transactions will implicitly roll back when dropped. Use the `commit` method to commit the changes made in the
-/// transaction. Transactions can be nested, with inner transactions implemented via safepoints.
+/// Transactions will implicitly roll back when dropped, unless configured otherwise with
+/// `TransactionBuilder::drop_behavior`. Use the `commit` method to commit the changes made in the transaction.
+/// Transactions can be nested, with inner transactions implemented via safepoints.
 pub struct Transaction<'a> {
     client: &'a mut Client,
     savepoint: Option<Savepoint>,
+    drop_behavior: DropBehavior,
     done: bool,
 }
 
@@ -39,10 +52,13 @@ impl Drop for Transaction<'_> {
             return;
         }
 
-        let query = if let Some(sp) = self.savepoint.as_ref() {
-            format!("ROLLBACK TO {}", sp.name)
-        } else {
-            "ROLLBACK".to_string()
+        // A transaction dropped while unwinding from a panic didn't reach the end of its scope, so it is never committed.
+        let commit = self.drop_behavior == DropBehavior::Commit && !thread::panicking();
+        let query = match (commit, self.savepoint.as_ref()) {
+            (true, Some(sp)) => format!("RELEASE {}", sp.name),
+            (true, None) => "COMMIT".to_string(),
+            (_, Some(sp)) => format!("ROLLBACK TO {}", sp.name),
+            (_, None) => "ROLLBACK".to_string(),
         };
         let buf = self.client.inner().with_buf(|buf| {
             frontend::query(&query, buf).unwrap();
@@ -52,18 +68,28 @@ impl Drop for Transaction<'_> {
             .client
             .inner()
             .send(RequestMessages::Single(FrontendMessage::Raw(buf)));
+
+        if self.drop_behavior == DropBehavior::Panic && !thread::panicking() {
+            panic!("transaction dropped without being committed or rolled back");
+        }
     }
 }
 
 impl<'a> Transaction<'a> {
-    pub(crate) fn new(client: &'a mut Client) -> Transaction<'a> {
+    pub(crate) fn new(client: &'a mut Client, drop_behavior: DropBehavior) -> Transaction<'a> {
         Transaction {
             client,
             savepoint: None,
+            drop_behavior,
             done: false,
         }
     }
 
+    /// Returns what the transaction does when it is dropped without being committed or rolled back.
+    pub fn drop_behavior(&self) -> DropBehavior {
+        self.drop_behavior
+    }
+
     /// Consumes the transaction, committing all changes made within it.
     pub async fn commit(mut self) -> Result<(), Error> {
         self.done = true;
@@ -102,6 +128,16 @@ impl<'a> Transaction<'a> {
         self.client.prepare_typed(query, parameter_types).await
     }
 
+    /// Like `Client::prepare_cached`.
+    pub async fn prepare_cached(&self, query: &str) -> Result<Statement, Error> {
+        self.client.prepare_cached(query).await
+    }
+
+    /// Like `Client::prepare_all`.
+    pub async fn prepare_all(&self, queries: &[&str]) -> Result<Vec<Statement>, Error> {
+        self.client.prepare_all(queries).await
+    }
+
     /// Like `Client::query`.
     pub async fn query<T>(
         &self,
@@ -114,6 +150,60 @@ impl<'a> Transaction<'a> {
         self.client.query(statement, params).await
     }
 
+    /// Like `Client::query_with_schema`.
+    pub async fn query_with_schema<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Column>, Vec<Row>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.query_with_schema(statement, params).await
+    }
+
+    /// Like `Client::query_owned`.
+    pub async fn query_owned<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<ResultSet, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.query_owned(statement, params).await
+    }
+
+    /// Like `Client::explain`.
+    pub async fn explain(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql + Sync)],
+        options: ExplainOptions,
+    ) -> Result<String, Error> {
+        self.client.explain(statement, params, options).await
+    }
+
+    /// Like `Client::query_deadline`.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub async fn query_deadline<T, U>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        deadline: Instant,
+        tls: U,
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket>,
+    {
+        self.client
+            .query_deadline(statement, params, deadline, tls)
+            .await
+    }
+
     /// Like `Client::query_one`.
     pub async fn query_one<T>(
         &self,
@@ -126,6 +216,32 @@ impl<'a> Transaction<'a> {
         self.client.query_one(statement, params).await
     }
 
+    /// Like `Client::query_scalar`.
+    pub async fn query_scalar<T, F>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<F>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.client.query_scalar(statement, params).await
+    }
+
+    /// Like `Client::query_scalar_one`.
+    pub async fn query_scalar_one<T, F>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<F, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.client.query_scalar_one(statement, params).await
+    }
+
     /// Like `Client::query_opt`.
     pub async fn query_opt<T>(
         &self,
@@ -138,6 +254,49 @@ impl<'a> Transaction<'a> {
         self.client.query_opt(statement, params).await
     }
 
+    /// Like `Client::query_map_by`.
+    pub async fn query_map_by<T, I, K>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+        key: I,
+        duplicates: DuplicateKeys,
+    ) -> Result<HashMap<K, Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+        I: RowIndex + fmt::Display,
+        K: FromSqlOwned + Eq + Hash,
+    {
+        self.client
+            .query_map_by(statement, params, key, duplicates)
+            .await
+    }
+
+    /// Like `Client::query_array`.
+    pub async fn query_array<T, F, const N: usize>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<[F; N]>, Error>
+    where
+        T: ?Sized + ToStatement,
+        F: FromSqlOwned,
+    {
+        self.client.query_array(statement, params).await
+    }
+
+    /// Like `Client::query_with_notices`.
+    pub async fn query_with_notices<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<(Vec<Row>, Vec<DbError>), Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.query_with_notices(statement, params).await
+    }
+
     /// Like `Client::query_raw`.
     pub async fn query_raw<T, P, I>(&self, statement: &T, params: I) -> Result<RowStream, Error>
     where
@@ -149,6 +308,21 @@ impl<'a> Transaction<'a> {
         self.client.query_raw(statement, params).await
     }
 
+    /// Like `Client::query_raw_text_params`.
+    pub async fn query_raw_text_params<T, P, I>(
+        &self,
+        statement: &T,
+        params: I,
+    ) -> Result<RowStream, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        self.client.query_raw_text_params(statement, params).await
+    }
+
     /// Like `Client::query_typed`.
     pub async fn query_typed(
         &self,
@@ -223,6 +397,58 @@ impl<'a> Transaction<'a> {
         bind::bind(self.client.inner(), statement, params).await
     }
 
+    /// Like `bind`, but creates the portal with the specified name rather than a generated one.
+    ///
+    /// Portal names must be unique within a transaction: binding a name which is already in use by an open portal
+    /// fails. Names of the form `p<n>` are used for the portals created by `bind`, so should be avoided. The name must
+    /// not be empty, since the empty name refers to the unnamed portal, which is closed at the end of every request;
+    /// an empty name is rejected with an error.
+    ///
+    /// Named portals make it possible to hold several portals open at once and fetch from them in an interleaved
+    /// fashion with `query_portal`, while identifying them by name in server logs and in `pg_cursors`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of parameters provided does not match the number expected.
+    pub async fn bind_named<T>(
+        &self,
+        name: &str,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Portal, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.bind_named_raw(name, statement, slice_iter(params))
+            .await
+    }
+
+    /// A maximally flexible version of [`bind_named`].
+    ///
+    /// [`bind_named`]: #method.bind_named
+    pub async fn bind_named_raw<P, T, I>(
+        &self,
+        name: &str,
+        statement: &T,
+        params: I,
+    ) -> Result<Portal, Error>
+    where
+        T: ?Sized + ToStatement,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        if name.is_empty() {
+            return Err(Error::encode(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "portal name must not be empty",
+            )));
+        }
+
+        let statement = statement.__convert().into_statement(self.client).await?;
+        bind::bind_named(self.client.inner(), name.to_string(), statement, params).await
+    }
+
     /// Continues execution of a portal, returning a stream of the resulting rows.
     ///
     /// Unlike `query`, portals can be incrementally evaluated by limiting the number of rows returned in each call to
@@ -245,6 +471,35 @@ impl<'a> Transaction<'a> {
         query::query_portal(self.client.inner(), portal, max_rows).await
     }
 
+    /// Fetches all remaining rows from the named cursor.
+    ///
+    /// This is typically used with functions returning `refcursor` values, which must be read within the
+    /// transaction that opened them. Columns of type `Type::REFCURSOR` can be read as `&str` or `String` to obtain
+    /// the cursor names.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn async_main(client: &mut tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+    /// use tokio_postgres::types::Type;
+    ///
+    /// let transaction = client.transaction().await?;
+    /// let row = transaction.query_one("SELECT * FROM open_cursors()", &[]).await?;
+    /// for (i, column) in row.columns().iter().enumerate() {
+    ///     if *column.type_() == Type::REFCURSOR {
+    ///         let rows = transaction.fetch_all_cursor(row.get(i)).await?;
+    ///         println!("{}: {} rows", column.name(), rows.len());
+    ///     }
+    /// }
+    /// transaction.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_all_cursor(&self, name: &str) -> Result<Vec<Row>, Error> {
+        let query = format!("FETCH ALL FROM {}", escape_identifier(name));
+        self.query_internal(&query, &[]).await
+    }
+
     /// Like `Client::copy_in`.
     pub async fn copy_in<T, U>(&self, statement: &T) -> Result<CopyInSink<U>, Error>
     where
@@ -262,6 +517,15 @@ impl<'a> Transaction<'a> {
         self.client.copy_out(statement).await
     }
 
+    /// Like `Client::copy_out_rows`.
+    pub async fn copy_out_rows(
+        &self,
+        query: &str,
+        types: &[Type],
+    ) -> Result<Vec<BinaryCopyOutRow>, Error> {
+        self.client.copy_out_rows(query, types).await
+    }
+
     /// Like `Client::simple_query`.
     pub async fn simple_query(&self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.client.simple_query(query).await
@@ -272,6 +536,79 @@ impl<'a> Transaction<'a> {
         self.client.batch_execute(query).await
     }
 
+    /// Exports the transaction's snapshot, returning an identifier which other sessions can import.
+    ///
+    /// Passing the identifier to `TransactionBuilder::snapshot` starts a transaction on another connection which sees
+    /// exactly the same data as this one, which allows a consistent read to be split across several connections. The
+    /// snapshot can only be imported while this transaction remains open.
+    ///
+    /// The transaction should use the `RepeatableRead` or `Serializable` isolation level, since under `ReadCommitted`
+    /// each statement takes a new snapshot.
+    pub async fn export_snapshot(&self) -> Result<String, Error> {
+        let rows = self
+            .query_internal("SELECT pg_export_snapshot()", &[])
+            .await?;
+        let row = rows.first().ok_or_else(Error::row_count)?;
+        row.try_get(0)
+    }
+
+    /// Executes a statement with `search_path` set to `schema`, returning the resulting rows.
+    ///
+    /// The search path is changed with `SET LOCAL` for the duration of the statement, and the previous search path
+    /// is restored once it succeeds, so the change does not leak into later statements of this transaction or of an
+    /// enclosing one. If the statement fails, the transaction is aborted and the search path is left for the rollback
+    /// to reset.
+    ///
+    /// See `Client::query_in_schema` for details.
+    pub async fn query_in_schema<T>(
+        &self,
+        schema: &str,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let query = format!(
+            "SHOW search_path; SELECT pg_catalog.set_config('search_path', {}, true)",
+            escape_literal(&escape_identifier(schema)),
+        );
+        let previous = self
+            .simple_query(&query)
+            .await?
+            .into_iter()
+            .find_map(|message| match message {
+                SimpleQueryMessage::Row(row) => row.get(0).map(str::to_string),
+                _ => None,
+            })
+            .ok_or_else(Error::unexpected_message)?;
+
+        let rows = self.query(statement, params).await?;
+
+        self.query_internal(
+            "SELECT pg_catalog.set_config('search_path', $1, true)",
+            &[(&previous, Type::TEXT)],
+        )
+        .await?;
+        Ok(rows)
+    }
+
+    /// Executes a query built by the transaction itself rather than by the user.
+    ///
+    /// The query is sent as is, bypassing `Config::query_rewriter`, `Config::fetch_count` and
+    /// `Config::max_result_rows`, which only apply to user queries.
+    async fn query_internal(
+        &self,
+        query: &str,
+        params: &[(&(dyn ToSql + Sync), Type)],
+    ) -> Result<Vec<Row>, Error> {
+        let params = params.iter().map(|(v, t)| (*v, t.clone()));
+        query::query_typed(self.client.inner(), query, params)
+            .await?
+            .try_collect()
+            .await
+    }
+
     /// Like `Client::cancel_token`.
     pub fn cancel_token(&self) -> CancelToken {
         self.client.cancel_token()
@@ -321,6 +658,7 @@ impl<'a> Transaction<'a> {
         Ok(Transaction {
             client: self.client,
             savepoint: Some(Savepoint { name, depth }),
+            drop_behavior: self.drop_behavior,
             done: false,
         })
     }
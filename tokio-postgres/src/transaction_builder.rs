@@ -1,3 +1,4 @@
+use postgres_protocol::escape::escape_literal;
 use postgres_protocol::message::frontend;
 
 use crate::{codec::FrontendMessage, connection::RequestMessages, Client, Error, Transaction};
@@ -21,12 +22,39 @@ pub enum IsolationLevel {
     Serializable,
 }
 
+/// What a transaction does when it is dropped without being committed or rolled back.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DropBehavior {
+    /// Roll the transaction back.
+    #[default]
+    Rollback,
+
+    /// Commit the transaction.
+    ///
+    /// Use this with care. A transaction is dropped without being committed when the code using it returns early,
+    /// including via `?` after a failed statement, so partially applied changes will be committed. Since the commit is
+    /// sent in the background, any error it produces, such as a deferred constraint violation, is not reported.
+    ///
+    /// A transaction dropped while the thread is panicking is rolled back instead, since the code using it did not
+    /// complete.
+    Commit,
+
+    /// Roll the transaction back, and then panic.
+    ///
+    /// This is intended to catch transactions which are accidentally left to fall out of scope, for example in tests.
+    /// No panic is raised if the transaction is dropped while the thread is already panicking.
+    Panic,
+}
+
 /// A builder for database transactions.
 pub struct TransactionBuilder<'a> {
     client: &'a mut Client,
     isolation_level: Option<IsolationLevel>,
     read_only: Option<bool>,
     deferrable: Option<bool>,
+    snapshot: Option<String>,
+    drop_behavior: DropBehavior,
 }
 
 impl<'a> TransactionBuilder<'a> {
@@ -36,6 +64,8 @@ impl<'a> TransactionBuilder<'a> {
             isolation_level: None,
             read_only: None,
             deferrable: None,
+            snapshot: None,
+            drop_behavior: DropBehavior::Rollback,
         }
     }
 
@@ -61,9 +91,28 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
+    /// Sets the snapshot the transaction runs with, as returned by `Transaction::export_snapshot` on another
+    /// connection.
+    ///
+    /// The transaction will see the same data as the exporting transaction, which must still be open. The isolation
+    /// level must be `RepeatableRead` or `Serializable`.
+    pub fn snapshot(mut self, snapshot: impl Into<String>) -> Self {
+        self.snapshot = Some(snapshot.into());
+        self
+    }
+
+    /// Sets what the transaction does when it is dropped without being committed or rolled back.
+    ///
+    /// Defaults to `DropBehavior::Rollback`. Nested transactions created from the transaction inherit the behavior.
+    pub fn drop_behavior(mut self, drop_behavior: DropBehavior) -> Self {
+        self.drop_behavior = drop_behavior;
+        self
+    }
+
     /// Begins the transaction.
     ///
-    /// The transaction will roll back by default - use the `commit` method to commit it.
+    /// The transaction will roll back by default - use the `commit` method to commit it, or `drop_behavior` to change
+    /// what happens when it is dropped.
     pub async fn start(self) -> Result<Transaction<'a>, Error> {
         let mut query = "START TRANSACTION".to_string();
         let mut first = true;
@@ -108,6 +157,12 @@ impl<'a> TransactionBuilder<'a> {
             query.push_str(s);
         }
 
+        // The snapshot must be set before the transaction runs any other statement, so send it in the same batch.
+        if let Some(snapshot) = &self.snapshot {
+            query.push_str("; SET TRANSACTION SNAPSHOT ");
+            query.push_str(&escape_literal(snapshot));
+        }
+
         struct RollbackIfNotDone<'me> {
             client: &'me Client,
             done: bool,
@@ -144,6 +199,6 @@ impl<'a> TransactionBuilder<'a> {
             cleaner.done = true;
         }
 
-        Ok(Transaction::new(self.client))
+        Ok(Transaction::new(self.client, self.drop_behavior))
     }
 }
@@ -1,7 +1,8 @@
 use crate::connect;
 use futures_util::{pin_mut, TryStreamExt};
+use std::error::Error;
 use tokio_postgres::binary_copy::{BinaryCopyInWriter, BinaryCopyOutStream};
-use tokio_postgres::types::Type;
+use tokio_postgres::types::{Type, WrongType};
 
 #[tokio::test]
 async fn write_basic() {
@@ -109,6 +110,40 @@ async fn write_big_rows() {
     }
 }
 
+#[tokio::test]
+async fn write_validates_columns() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT, bar TEXT)")
+        .await
+        .unwrap();
+
+    let sink = client
+        .copy_in("COPY foo (id, bar) FROM STDIN WITH (FORMAT binary) WHERE id > 1")
+        .await
+        .unwrap();
+    let writer = BinaryCopyInWriter::with_columns(sink, &[("id", Type::INT4), ("bar", Type::TEXT)]);
+    pin_mut!(writer);
+
+    let err = writer.as_mut().write(&[&1i32]).await.unwrap_err();
+    assert_eq!(err.to_string(), "expected 2 columns but got 1");
+
+    let err = writer.as_mut().write(&[&1i32, &2i32]).await.unwrap_err();
+    let wrong_type = err.source().unwrap().downcast_ref::<WrongType>().unwrap();
+    assert_eq!(wrong_type.column(), Some("bar"));
+
+    // Rejected rows are not sent, so the copy can carry on.
+    writer.as_mut().write(&[&1i32, &"a"]).await.unwrap();
+    writer.as_mut().write(&[&2i32, &"b"]).await.unwrap();
+    assert_eq!(writer.finish().await.unwrap(), 1);
+
+    let rows = client.query("SELECT id, bar FROM foo", &[]).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+    assert_eq!(rows[0].get::<_, &str>(1), "b");
+}
+
 #[tokio::test]
 async fn read_basic() {
     let client = connect("user=postgres").await;
@@ -201,3 +236,46 @@ async fn read_big_rows() {
         assert_eq!(row.get::<&[u8]>(1), &vec![i as u8; 128 * 1024][..]);
     }
 }
+
+#[tokio::test]
+async fn copy_out_rows() {
+    let client = connect("user=postgres").await;
+
+    let rows = client
+        .copy_out_rows(
+            "SELECT i, NULLIF('value ' || i, 'value 2') FROM generate_series(1, 3) i ORDER BY i",
+            &[Type::INT4, Type::TEXT],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+
+    assert_eq!(rows[0].get::<i32>(0), 1);
+    assert_eq!(rows[0].get::<Option<&str>>(1), Some("value 1"));
+    assert_eq!(rows[1].get::<i32>(0), 2);
+    assert_eq!(rows[1].get::<Option<&str>>(1), None);
+    assert_eq!(rows[2].get::<i32>(0), 3);
+    assert_eq!(rows[2].get::<Option<&str>>(1), Some("value 3"));
+
+    let err = client
+        .copy_out_rows("SELECT 1::INT4", &[Type::TEXT])
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("is of type int4 rather than text"),
+        "{}",
+        err
+    );
+
+    let err = client
+        .copy_out_rows("SELECT 1::INT4", &[Type::INT4, Type::TEXT])
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("expected 2 columns but got 1"),
+        "{}",
+        err
+    );
+}
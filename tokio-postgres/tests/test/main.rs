@@ -12,11 +12,13 @@ use std::task::{Context, Poll};
 use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::time;
-use tokio_postgres::error::SqlState;
+use tokio_postgres::columnar::ColumnData;
+use tokio_postgres::error::{ErrorPosition, SqlState};
 use tokio_postgres::tls::{NoTls, NoTlsStream};
-use tokio_postgres::types::{Kind, Type};
+use tokio_postgres::types::{to_sql_checked, IsNull, Kind, ToSql, Type};
 use tokio_postgres::{
-    AsyncMessage, Client, Config, Connection, Error, IsolationLevel, SimpleQueryMessage,
+    AsyncMessage, AuthMethod, Client, Config, Connection, DropBehavior, DuplicateKeys, Error,
+    ExplainFormat, ExplainOptions, IsolationLevel, ResultSetMessage, SimpleQueryMessage,
 };
 
 mod binary_copy;
@@ -100,7 +102,9 @@ async fn plain_password_wrong() {
 
 #[tokio::test]
 async fn plain_password_ok() {
-    connect("user=pass_user password=password dbname=postgres").await;
+    let client = connect("user=pass_user password=password dbname=postgres").await;
+    let auth_info = client.auth_info();
+    assert_eq!(auth_info.method(), AuthMethod::Password);
 }
 
 #[tokio::test]
@@ -144,23 +148,1134 @@ async fn scram_password_wrong() {
 
 #[tokio::test]
 async fn scram_password_ok() {
-    connect("user=scram_user password=password dbname=postgres").await;
+    let client = connect("user=scram_user password=password dbname=postgres").await;
+    let auth_info = client.auth_info();
+    assert_eq!(auth_info.method(), AuthMethod::ScramSha256);
+    assert!(!auth_info.channel_binding());
+    assert_eq!(auth_info.scram_iterations(), Some(4096));
+}
+
+#[tokio::test]
+async fn trust_auth_info() {
+    let client = connect("user=postgres").await;
+    let auth_info = client.auth_info();
+    assert_eq!(auth_info.method(), AuthMethod::None);
+    assert_eq!(auth_info.scram_iterations(), None);
 }
 
 #[tokio::test]
 async fn pipelined_prepare() {
     let client = connect("user=postgres").await;
 
-    let prepare1 = client.prepare("SELECT $1::HSTORE[]");
-    let prepare2 = client.prepare("SELECT $1::BIGINT");
+    let prepare1 = client.prepare("SELECT $1::HSTORE[]");
+    let prepare2 = client.prepare("SELECT $1::BIGINT");
+
+    let (statement1, statement2) = try_join!(prepare1, prepare2).unwrap();
+
+    assert_eq!(statement1.params()[0].name(), "_hstore");
+    assert_eq!(statement1.columns()[0].type_().name(), "_hstore");
+
+    assert_eq!(statement2.params()[0], Type::INT8);
+    assert_eq!(statement2.columns()[0].type_(), &Type::INT8);
+}
+
+#[tokio::test]
+async fn prepare_all() {
+    let client = connect("user=postgres").await;
+
+    let statements = client
+        .prepare_all(&[
+            "SELECT $1::HSTORE[]",
+            "SELECT $1::BIGINT",
+            "SELECT 'prepare_all'",
+        ])
+        .await
+        .unwrap();
+    assert_eq!(statements.len(), 3);
+    assert_eq!(statements[0].params()[0].name(), "_hstore");
+    assert_eq!(statements[1].params()[0], Type::INT8);
+    assert_eq!(statements[2].columns()[0].type_(), &Type::TEXT);
+
+    let row = client.query_one(&statements[1], &[&5i64]).await.unwrap();
+    assert_eq!(row.get::<_, i64>(0), 5);
+
+    let first = "SELECT 'prepare_all_failed'";
+    let err = client
+        .prepare_all(&[first, "SELEKT 1", "SELECT 2"])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::SYNTAX_ERROR));
+    assert_eq!(err.statement_index(), Some(1));
+
+    // Statements prepared ahead of the failing one are closed again.
+    let count = client
+        .query_one(
+            "SELECT count(*) FROM pg_prepared_statements WHERE statement = $1",
+            &[&first],
+        )
+        .await
+        .unwrap()
+        .get::<_, i64>(0);
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn prepare_future_cancellation() {
+    let client = connect("user=postgres").await;
+
+    for i in 0.. {
+        let done = {
+            let fut = Cancellable {
+                fut: client.prepare("SELECT $1::HSTORE[]"),
+                polls_left: i,
+            };
+            fut.await.map(|res| res.expect("prepare failed")).is_some()
+        };
+
+        let count = client
+            .query_one(
+                "SELECT count(*) FROM pg_prepared_statements WHERE statement = 'SELECT $1::HSTORE[]'",
+                &[],
+            )
+            .await
+            .unwrap()
+            .get::<_, i64>(0);
+        assert_eq!(count, 0);
+
+        if done {
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn statement_names_are_client_unique() {
+    async fn statement_name(client: &Client) -> String {
+        let query = "SELECT 'statement_names'";
+        let _statement = client.prepare(query).await.unwrap();
+        client
+            .query_one(
+                "SELECT name FROM pg_prepared_statements WHERE statement = $1",
+                &[&query],
+            )
+            .await
+            .unwrap()
+            .get(0)
+    }
+
+    fn prefix(name: &str) -> &str {
+        let parts = name.split('_').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3, "{}", name);
+        assert_eq!(parts[0], "s");
+        assert!(parts[2].parse::<usize>().is_ok(), "{}", name);
+        parts[1]
+    }
+
+    let client1 = connect("user=postgres").await;
+    let client2 = connect("user=postgres").await;
+
+    let name1 = statement_name(&client1).await;
+    let name2 = statement_name(&client2).await;
+    assert_ne!(prefix(&name1), prefix(&name2));
+
+    let name3 = statement_name(&client1).await;
+    assert_eq!(prefix(&name1), prefix(&name3));
+    assert_ne!(name1, name3);
+}
+
+#[tokio::test]
+async fn statement_parameter_oids() {
+    let client = connect("user=postgres").await;
+
+    let statement = client
+        .prepare("SELECT $1::TEXT, $2::INT8, $3::BOOL[]")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        statement.parameter_oids(),
+        [Type::TEXT.oid(), Type::INT8.oid(), Type::BOOL_ARRAY.oid()]
+    );
+}
+
+#[tokio::test]
+async fn column_index() {
+    let client = connect("user=postgres").await;
+
+    let statement = client
+        .prepare("SELECT 1 AS id, 'a' AS \"Name\", 2 AS \"name\"")
+        .await
+        .unwrap();
+    assert_eq!(statement.column_index("id"), Some(0));
+    assert_eq!(statement.column_index("ID"), Some(0));
+    assert_eq!(statement.column_index("Name"), Some(1));
+    assert_eq!(statement.column_index("name"), Some(2));
+    assert_eq!(statement.column_index("missing"), None);
+
+    let row = client.query_one(&statement, &[]).await.unwrap();
+    let idx = row.column_index("id").unwrap();
+    assert_eq!(row.get::<_, i32>(idx), 1);
+    assert_eq!(row.column_index("NAME"), Some(1));
+    assert_eq!(row.column_index("missing"), None);
+
+    let messages = client.simple_query("SELECT 1 AS id").await.unwrap();
+    match &messages[1] {
+        SimpleQueryMessage::Row(row) => assert_eq!(row.column_index("id"), Some(0)),
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[tokio::test]
+async fn cached_row() {
+    use std::error::Error;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio_postgres::types::FromSql;
+    use tokio_postgres::CachedRow;
+
+    static DECODES: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Counted(Vec<i32>);
+
+    impl<'a> FromSql<'a> for Counted {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Counted, Box<dyn Error + Sync + Send>> {
+            DECODES.fetch_add(1, Ordering::SeqCst);
+            Vec::from_sql(ty, raw).map(Counted)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <Vec<i32> as FromSql>::accepts(ty)
+        }
+    }
+
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT ARRAY[1, 2, 3] AS a, 'x' AS b", &[])
+        .await
+        .unwrap();
+    let row = CachedRow::new(row);
+
+    assert_eq!(row.get::<_, Counted>(0), Counted(vec![1, 2, 3]));
+    assert_eq!(row.get::<_, Counted>("a"), Counted(vec![1, 2, 3]));
+    assert_eq!(DECODES.load(Ordering::SeqCst), 1);
+
+    // Values are cached separately for each type.
+    assert_eq!(row.get::<_, Vec<i32>>(0), [1, 2, 3]);
+    assert_eq!(
+        row.get::<_, Option<Counted>>(0),
+        Some(Counted(vec![1, 2, 3]))
+    );
+    assert_eq!(DECODES.load(Ordering::SeqCst), 2);
+
+    assert!(row.try_get::<_, Counted>(1).is_err());
+    assert!(row.try_get::<_, Counted>("missing").is_err());
+    assert_eq!(row.row().get::<_, &str>(1), "x");
+}
+
+#[tokio::test]
+async fn query_scalar() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INT4,
+                name TEXT
+            );
+
+            INSERT INTO foo VALUES (1, 'alice'), (2, NULL), (3, 'carol');",
+        )
+        .await
+        .unwrap();
+
+    let ids = client
+        .query_scalar::<_, i32>("SELECT id, name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(ids, [1, 2, 3]);
+
+    let names = client
+        .query_scalar::<_, Option<String>>("SELECT name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        names,
+        [Some("alice".to_string()), None, Some("carol".to_string())]
+    );
+
+    let err = client
+        .query_scalar::<_, String>("SELECT name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("column 0"), "{}", err);
+
+    let count = client
+        .query_scalar_one::<_, i64>("SELECT count(*) FROM foo WHERE id > $1", &[&1i32])
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let err = client
+        .query_scalar_one::<_, i32>("SELECT id FROM foo", &[])
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("unexpected number of rows"),
+        "{}",
+        err
+    );
+
+    let err = client
+        .query_scalar::<_, i32>("SELECT FROM foo", &[])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("columns"), "{}", err);
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn query_scalar_in() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let values = client
+        .query_scalar_in::<_, i32>("SELECT generate_series(1, 3)", &[], &arena)
+        .await
+        .unwrap();
+    assert_eq!(&values[..], [1, 2, 3]);
+
+    let value = client
+        .query_scalar_one_in::<_, String>("SELECT $1::TEXT", &[&"hello"], &arena)
+        .await
+        .unwrap();
+    assert_eq!(value, "hello");
+
+    let err = client
+        .query_scalar_one_in::<_, i32>("SELECT generate_series(1, 2)", &[], &arena)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("unexpected number of rows"),
+        "{}",
+        err
+    );
+
+    let err = client
+        .query_scalar_in::<_, i32>("SELECT", &[], &arena)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("columns"), "{}", err);
+}
+
+#[tokio::test]
+async fn query_with_schema() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INT4,
+                name TEXT
+            );
+
+            INSERT INTO foo VALUES (1, 'alice'), (2, 'bob');",
+        )
+        .await
+        .unwrap();
+
+    let (columns, rows) = client
+        .query_with_schema(
+            "SELECT id, name, 1.5::FLOAT8 AS score FROM foo ORDER BY id",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].get::<_, &str>(1), "bob");
+
+    let table_oid = columns[0].table_oid().unwrap();
+    let columns = columns
+        .iter()
+        .map(|c| (c.name(), c.table_oid(), c.column_id(), c.type_().clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        columns,
+        [
+            ("id", Some(table_oid), Some(1), Type::INT4),
+            ("name", Some(table_oid), Some(2), Type::TEXT),
+            ("score", None, None, Type::FLOAT8),
+        ]
+    );
+
+    let (columns, rows) = client
+        .query_with_schema("SELECT id FROM foo WHERE id > $1", &[&10i32])
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+    assert_eq!(columns.len(), 1);
+    assert_eq!(columns[0].name(), "id");
+    assert_eq!(columns[0].type_(), &Type::INT4);
+}
+
+#[cfg(feature = "tracing")]
+#[tokio::test]
+async fn tracing_spans() {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    type Spans = Arc<Mutex<Vec<(&'static str, HashMap<&'static str, String>)>>>;
+
+    struct Recorder(Spans);
+
+    struct Fields<'a>(&'a mut HashMap<&'static str, String>);
+
+    impl Visit for Fields<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name(), format!("{:?}", value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut spans = self.0.lock().unwrap();
+            let mut fields = HashMap::new();
+            attrs.record(&mut Fields(&mut fields));
+            spans.push((attrs.metadata().name(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.0.lock().unwrap();
+            values.record(&mut Fields(&mut spans[span.into_u64() as usize - 1].1));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    let client = connect("user=postgres").await;
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0)
+        .to_string();
+
+    let spans = Spans::default();
+    let _guard = tracing::subscriber::set_default(Recorder(spans.clone()));
+
+    let statement = client
+        .prepare("SELECT generate_series(1, $1)")
+        .await
+        .unwrap();
+    client.query(&statement, &[&3i32]).await.unwrap();
+    client
+        .execute("SELECT generate_series(1, 2)", &[])
+        .await
+        .unwrap();
+    client.query("SELECT nonexistent", &[]).await.unwrap_err();
+
+    let spans = spans.lock().unwrap();
+    let names = spans.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["prepare", "query", "execute", "prepare", "query", "prepare"]
+    );
+
+    let (_, prepare) = &spans[0];
+    assert!(prepare["statement"].starts_with('s'));
+    assert_eq!(prepare["params"], "1");
+    assert_eq!(prepare["pid"], pid);
+    assert!(!prepare.contains_key("rows"));
+
+    let (_, query) = &spans[1];
+    assert_eq!(query["statement"], prepare["statement"]);
+    assert_eq!(query["rows"], "3");
+    assert!(query.contains_key("elapsed_us"));
+
+    // Unprepared statements are prepared within the span of the method executing them.
+    let (_, execute) = &spans[2];
+    assert_eq!(execute["statement"], spans[3].1["statement"]);
+    assert_eq!(execute["params"], "0");
+    assert_eq!(execute["rows"], "2");
+
+    let (_, failed) = &spans[4];
+    assert!(
+        failed["error"].contains("nonexistent"),
+        "{}",
+        failed["error"]
+    );
+    assert!(!failed.contains_key("rows"));
+}
+
+#[tokio::test]
+async fn query_owned() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INT4,
+                name TEXT
+            );
+
+            INSERT INTO foo VALUES (1, 'alice'), (2, NULL);",
+        )
+        .await
+        .unwrap();
+
+    let result_set = {
+        let statement = client
+            .prepare("SELECT id, name FROM foo ORDER BY id")
+            .await
+            .unwrap();
+        client.query_owned(&statement, &[]).await.unwrap()
+    };
+    // The statement has been dropped and closed, but the result set is still usable.
+    client.query("SELECT 1", &[]).await.unwrap();
+
+    assert_eq!(result_set.len(), 2);
+    assert_eq!(result_set.columns()[1].name(), "name");
+
+    let row = result_set.get(0).unwrap();
+    assert_eq!(row.len(), 2);
+    assert_eq!(row.get::<_, i32>("id"), 1);
+    assert_eq!(row.get::<_, &str>(1), "alice");
+    assert!(row.try_get::<_, i32>(1).is_err());
+    assert!(row.try_get::<_, i32>("missing").is_err());
+    assert!(result_set.get(2).is_none());
+
+    let names = result_set
+        .iter()
+        .map(|row| row.get::<_, Option<String>>("name"))
+        .collect::<Vec<_>>();
+    assert_eq!(names, [Some("alice".to_string()), None]);
+
+    let result_set = std::thread::spawn(move || result_set.clone())
+        .join()
+        .unwrap();
+    assert_eq!(result_set.iter().len(), 2);
+
+    let result_set = client
+        .query_owned("SELECT id FROM foo WHERE id > $1", &[&10i32])
+        .await
+        .unwrap();
+    assert!(result_set.is_empty());
+    assert_eq!(result_set.columns()[0].type_(), &Type::INT4);
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_prepare_in_reuses_statements() {
+    async fn prepared(client: &Client) -> i64 {
+        client
+            .query_one(
+                "SELECT count(*) FROM pg_prepared_statements WHERE statement = 'SELECT $1 + 1'",
+                &[],
+            )
+            .await
+            .unwrap()
+            .get(0)
+    }
+
+    let client = connect("user=postgres").await;
+    let first = bumpalo::Bump::new();
+    let second = bumpalo::Bump::new();
+
+    let a = client
+        .prepare_typed_in("SELECT $1 + 1", &[Type::INT4], &first)
+        .await
+        .unwrap();
+    let b = client
+        .prepare_typed_in("SELECT $1 + 1", &[Type::INT4], &second)
+        .await
+        .unwrap();
+    assert_eq!(prepared(&client).await, 1);
+    assert_eq!(b.columns()[0].type_(), &Type::INT4);
+
+    // the shared statement stays prepared until every statement using it is dropped
+    drop(a);
+    let row = client.query_one_in(&b, &[&41i32], &second).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 42);
+    drop(row);
+    drop(b);
+    assert_eq!(prepared(&client).await, 0);
+
+    // different parameter types are prepared separately
+    let a = client.prepare_in("SELECT $1 + 1", &first).await.unwrap();
+    let b = client
+        .prepare_typed_in("SELECT $1 + 1", &[Type::INT8], &second)
+        .await
+        .unwrap();
+    assert_eq!(prepared(&client).await, 2);
+
+    client.clear_statement_cache();
+    let c = client.prepare_in("SELECT $1 + 1", &second).await.unwrap();
+    assert_eq!(prepared(&client).await, 3);
+    drop((a, b, c));
+    assert_eq!(prepared(&client).await, 0);
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_column_type_modifier_and_format() {
+    let client = connect("user=postgres").await;
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (name VARCHAR(10), price NUMERIC(8, 2), id INT4)",
+        )
+        .await
+        .unwrap();
+    let arena = bumpalo::Bump::new();
+
+    let statement = client
+        .prepare_in("SELECT name, price, id FROM foo", &arena)
+        .await
+        .unwrap();
+    let columns = statement.columns();
+    // varchar stores its length plus the 4 byte header, and numeric packs the precision and scale
+    assert_eq!(columns[0].type_modifier(), 14);
+    assert_eq!(columns[1].type_modifier(), ((8 << 16) | 2) + 4);
+    assert_eq!(columns[2].type_modifier(), -1);
+    assert_eq!(columns[0].format(), 0);
+
+    let rows = client
+        .query_typed_raw_in("SELECT 'a'::VARCHAR(3)", Vec::<(i32, Type)>::new(), &arena)
+        .await
+        .unwrap()
+        .collect_in()
+        .await
+        .unwrap();
+    assert_eq!(rows[0].columns()[0].type_modifier(), 7);
+    assert_eq!(rows[0].columns()[0].format(), 0);
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_statement_name_and_query() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let statement = client.prepare_in("SELECT $1::INT4", &arena).await.unwrap();
+    assert_eq!(statement.query(), "SELECT $1::INT4");
+    assert!(!statement.name().is_empty());
+    let debug = format!("{:?}", statement);
+    assert!(debug.contains(statement.name()));
+    assert!(debug.contains("SELECT $1::INT4"));
+
+    let name = client
+        .query_one(
+            "SELECT name FROM pg_prepared_statements WHERE statement = 'SELECT $1::INT4'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get::<_, String>(0);
+    assert_eq!(name, statement.name());
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_statement_reprepare_in() {
+    let client = connect("user=postgres").await;
+    let long = bumpalo::Bump::new();
+
+    let statement = {
+        let short = bumpalo::Bump::new();
+        let statement = client
+            .prepare_typed_in("SELECT $1 + 1", &[Type::INT4], &short)
+            .await
+            .unwrap();
+        statement.reprepare_in(&client, &long).await.unwrap()
+    };
+
+    assert_eq!(statement.params(), [Type::INT4]);
+    assert_eq!(statement.columns()[0].type_(), &Type::INT4);
+    let row = client
+        .query_one_in(&statement, &[&41i32], &long)
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 42);
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn transaction_in() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id SERIAL, name TEXT)")
+        .await
+        .unwrap();
+
+    let arena = bumpalo::Bump::new();
+
+    {
+        let transaction = client.transaction_in(&arena).await.unwrap();
+        transaction
+            .execute_in("INSERT INTO foo (name) VALUES ($1)", &[&"steven"])
+            .await
+            .unwrap();
+        let rows = transaction
+            .query_in("SELECT name FROM foo", &[])
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<_, &str>(0), "steven");
+        transaction.commit().await.unwrap();
+    }
+
+    // dropping the transaction rolls it back
+    {
+        let transaction = client.transaction_in(&arena).await.unwrap();
+        transaction
+            .execute_in("INSERT INTO foo (name) VALUES ($1)", &[&"joe"])
+            .await
+            .unwrap();
+        let row = transaction
+            .query_one_in("SELECT count(*) FROM foo", &[])
+            .await
+            .unwrap();
+        assert_eq!(row.get::<_, i64>(0), 2);
+    }
+
+    let rows = client
+        .query("SELECT name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, &str>(0), "steven");
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_raw_values() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let row = client
+        .query_one_in(
+            "SELECT '\\x00ff'::BYTEA AS data, 'hi'::TEXT, NULL::BYTEA AS missing, 1::INT4",
+            &[],
+            &arena,
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get_bytes("data"), Some(&[0x00, 0xff][..]));
+    assert_eq!(row.get_bytes(1), Some(&b"hi"[..]));
+    assert_eq!(row.get_bytes("missing"), None);
+    assert_eq!(row.get_bytes(3), Some(&1i32.to_be_bytes()[..]));
+    assert!(row.try_get_bytes(4).is_err());
+    assert!(row.try_get_bytes("nope").is_err());
+
+    let raw = row.try_get_raw("data").unwrap().unwrap();
+    assert_eq!(raw.type_(), &Type::BYTEA);
+    assert_eq!(raw.bytes(), [0x00, 0xff]);
+    let raw = row.try_get_raw(3).unwrap().unwrap();
+    assert_eq!(raw.type_(), &Type::INT4);
+    assert_eq!(raw.bytes(), 1i32.to_be_bytes());
+    assert!(row.try_get_raw("missing").unwrap().is_none());
+    assert!(row.try_get_raw("nope").is_err());
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_get_opt() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let row = client
+        .query_one_in("SELECT 1::INT4 AS id, NULL::TEXT AS name", &[], &arena)
+        .await
+        .unwrap();
+    assert_eq!(row.get_opt::<_, i32>("id").unwrap().unwrap(), 1);
+    assert_eq!(
+        row.get_opt::<_, Option<&str>>("name").unwrap().unwrap(),
+        None
+    );
+    assert!(row.get_opt::<_, i32>("missing").is_none());
+    assert!(row.get_opt::<_, i32>(2).is_none());
+    assert!(row.get_opt::<_, &str>("id").unwrap().is_err());
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_into_owned() {
+    let client = connect("user=postgres").await;
+
+    let rows = {
+        let arena = bumpalo::Bump::new();
+        client
+            .query_in(
+                "SELECT x, 'row ' || x AS name FROM generate_series(1, 2) x",
+                &[],
+                &arena,
+            )
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let names = tokio::spawn(async move {
+        rows.iter()
+            .map(|row| (row.get::<_, i32>("x"), row.get::<_, String>("name")))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap();
+    assert_eq!(names, [(1, "row 1".to_string()), (2, "row 2".to_string())]);
+}
 
-    let (statement1, statement2) = try_join!(prepare1, prepare2).unwrap();
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_stream_for_each_in() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+    let mut scratch = bumpalo::Bump::new();
+
+    let stream = client
+        .query_raw_in(
+            "SELECT i, 'row ' || i FROM generate_series(1, 10000) i",
+            &[] as &[i32],
+            &arena,
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    let arena_bytes = arena.allocated_bytes();
+    let mut sum = 0;
+    stream
+        .as_mut()
+        .for_each_in(&mut scratch, |row| {
+            let i = row.get::<_, i32>(0);
+            assert_eq!(row.get::<_, &str>(1), format!("row {}", i));
+            sum += i64::from(i);
+            Ok(())
+        })
+        .await
+        .unwrap();
 
-    assert_eq!(statement1.params()[0].name(), "_hstore");
-    assert_eq!(statement1.columns()[0].type_().name(), "_hstore");
+    assert_eq!(sum, 50_005_000);
+    assert_eq!(stream.rows_affected(), Some(10000));
+    assert_eq!(stream.command_tag(), Some("SELECT 10000"));
+    assert!(arena.allocated_bytes() - arena_bytes < 1024);
+    assert!(scratch.allocated_bytes() < 8192);
+}
 
-    assert_eq!(statement2.params()[0], Type::INT8);
-    assert_eq!(statement2.columns()[0].type_(), &Type::INT8);
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_query_portal_in() {
+    let mut client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let transaction = client.transaction_in(&arena).await.unwrap();
+    let stream = transaction
+        .query_portal_in("SELECT generate_series(1, 10)", &[], 3)
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    let mut values = vec![];
+    while let Some(row) = stream.try_next().await.unwrap() {
+        values.push(row.get::<_, i32>(0));
+    }
+    assert_eq!(values, (1..=10).collect::<Vec<_>>());
+    assert_eq!(stream.rows_affected(), Some(10));
+    assert!(stream.portal().is_complete());
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_portal_fetch() {
+    let mut client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let transaction = client.transaction_in(&arena).await.unwrap();
+    let mut portal = transaction
+        .bind_in("SELECT generate_series(1, $1)", &[&5i32])
+        .await
+        .unwrap();
+
+    let rows = portal.fetch(2).await.unwrap();
+    assert_eq!(
+        rows.iter().map(|r| r.get::<_, i32>(0)).collect::<Vec<_>>(),
+        [1, 2]
+    );
+    assert!(!portal.is_complete());
+
+    let rows = portal.fetch(10).await.unwrap();
+    assert_eq!(
+        rows.iter().map(|r| r.get::<_, i32>(0)).collect::<Vec<_>>(),
+        [3, 4, 5]
+    );
+    assert!(portal.is_complete());
+
+    assert!(portal.fetch(10).await.unwrap().is_empty());
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_query_raw_formats_in() {
+    use tokio_postgres::types::Format;
+
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    // aclitem has no binary output function.
+    let query = "SELECT 'postgres=r/postgres'::aclitem, 42::INT4";
+    let err = client
+        .query_raw_in(query, &[] as &[i32], &arena)
+        .await
+        .unwrap()
+        .collect_in()
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_FUNCTION));
+
+    let stream = client
+        .query_raw_formats_in(query, &[] as &[i32], &[Format::Text], &arena)
+        .await
+        .unwrap();
+    let rows = stream.collect_in().await.unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "postgres=r/postgres");
+    assert_eq!(rows[0].get::<_, String>(1), "42");
+    let err = rows[0].try_get::<_, i32>(1).unwrap_err();
+    assert!(err.to_string().contains("of type text to i32"), "{}", err);
+    let raw = rows[0].try_get_raw(1).unwrap().unwrap();
+    assert!(matches!(raw.format(), Format::Text));
+    assert_eq!(raw.bytes(), b"42");
+
+    let stream = client
+        .query_raw_formats_in(
+            query,
+            &[] as &[i32],
+            &[Format::Text, Format::Binary],
+            &arena,
+        )
+        .await
+        .unwrap();
+    let rows = stream.collect_in().await.unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "postgres=r/postgres");
+    assert_eq!(rows[0].get::<_, i32>(1), 42);
+
+    assert!(client
+        .query_raw_formats_in(
+            query,
+            &[] as &[i32],
+            &[Format::Text, Format::Text, Format::Text],
+            &arena,
+        )
+        .await
+        .is_err());
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_stream_collect_in() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let rows = client
+        .query_raw_in("SELECT generate_series(1, $1)", &[3i32], &arena)
+        .await
+        .unwrap()
+        .collect_in()
+        .await
+        .unwrap();
+    let values = rows.iter().map(|row| row.get(0)).collect::<Vec<i32>>();
+    assert_eq!(values, [1, 2, 3]);
+
+    let err = client
+        .query_raw_in(
+            "SELECT 1 / (3 - generate_series(1, 5))",
+            &[] as &[i32],
+            &arena,
+        )
+        .await
+        .unwrap()
+        .collect_in()
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::DIVISION_BY_ZERO));
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_stream_command_tag() {
+    let client = connect("user=postgres").await;
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id SERIAL, name TEXT)")
+        .await
+        .unwrap();
+    let arena = bumpalo::Bump::new();
+
+    let stream = client
+        .query_raw_in(
+            "INSERT INTO foo (name) VALUES ('steven'), ('joe') RETURNING id",
+            &[] as &[i32],
+            &arena,
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    assert_eq!(stream.command_tag(), None);
+    let mut rows = 0;
+    while stream.try_next().await.unwrap().is_some() {
+        rows += 1;
+    }
+    assert_eq!(rows, 2);
+    assert_eq!(stream.rows_affected(), Some(2));
+    assert_eq!(stream.command_tag(), Some("INSERT 0 2"));
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_column_names_outlive_rows() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+
+    let names = {
+        let row = client
+            .query_one_in("SELECT 1::INT4 AS id, 'joe'::TEXT AS name", &[], &arena)
+            .await
+            .unwrap();
+        assert_eq!(row.columns()[1].type_(), &Type::TEXT);
+        row.columns().iter().map(|c| c.name()).collect::<Vec<_>>()
+    };
+    let statement_name = {
+        let statement = client
+            .prepare_in("SELECT 1::INT8 AS n", &arena)
+            .await
+            .unwrap();
+        assert_eq!(statement.columns()[0].type_(), &Type::INT8);
+        statement.columns()[0].name()
+    };
+
+    assert_eq!(names, ["id", "name"]);
+    assert_eq!(statement_name, "n");
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn arena_row_to_owned() {
+    let client = connect("user=postgres").await;
+
+    let rows = {
+        let arena = bumpalo::Bump::new();
+        let rows = client
+            .query_in(
+                "SELECT x, 'row ' || x AS name, NULL::TEXT AS missing FROM generate_series(1, 3) x",
+                &[],
+                &arena,
+            )
+            .await
+            .unwrap();
+        rows.iter()
+            .filter(|row| row.get::<_, i32>(0) != 2)
+            .map(|row| row.to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].columns()[1].name(), "name");
+    assert_eq!(rows[0].columns()[0].type_(), &Type::INT4);
+    assert_eq!(rows[0].get::<_, i32>("x"), 1);
+    assert_eq!(rows[1].get::<_, &str>("name"), "row 3");
+    assert_eq!(rows[1].get::<_, Option<String>>(2), None);
+}
+
+#[tokio::test]
+async fn query_map_by() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT, code TEXT, name TEXT);
+             INSERT INTO foo VALUES (1, 'a', 'alice'), (2, 'b', 'bob'), (3, 'a', 'anne');",
+        )
+        .await
+        .unwrap();
+
+    let map = client
+        .query_map_by::<_, _, i32>(
+            "SELECT * FROM foo ORDER BY id",
+            &[],
+            "id",
+            DuplicateKeys::Error,
+        )
+        .await
+        .unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&2].get::<_, &str>("name"), "bob");
+
+    let map = client
+        .query_map_by::<_, _, String>(
+            "SELECT * FROM foo ORDER BY id",
+            &[],
+            1,
+            DuplicateKeys::LastWins,
+        )
+        .await
+        .unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["a"].get::<_, &str>("name"), "anne");
+
+    let err = client
+        .query_map_by::<_, _, String>(
+            "SELECT * FROM foo ORDER BY id",
+            &[],
+            "code",
+            DuplicateKeys::Error,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "query returned a duplicate key in column `code`"
+    );
+}
+
+#[tokio::test]
+async fn query_array() {
+    let client = connect("user=postgres").await;
+
+    let rows = client
+        .query_array::<_, f64, 2>(
+            "SELECT i::FLOAT8, i * $1::FLOAT8 FROM generate_series(1, 3) i",
+            &[&0.5f64],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows, [[1., 0.5], [2., 1.], [3., 1.5]]);
+
+    let err = client
+        .query_array::<_, i32, 3>("SELECT 1, 2", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "expected 3 columns but got 2");
+
+    client
+        .query_array::<_, i32, 2>("SELECT 1, 'a'", &[])
+        .await
+        .unwrap_err();
 }
 
 #[tokio::test]
@@ -180,40 +1295,243 @@ async fn insert_select() {
     let select = client.query(&select, &[]);
     let (_, rows) = try_join!(insert, select).unwrap();
 
-    assert_eq!(rows.len(), 2);
-    assert_eq!(rows[0].get::<_, i32>(0), 1);
-    assert_eq!(rows[0].get::<_, &str>(1), "alice");
-    assert_eq!(rows[1].get::<_, i32>(0), 2);
-    assert_eq!(rows[1].get::<_, &str>(1), "bob");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, &str>(1), "alice");
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+    assert_eq!(rows[1].get::<_, &str>(1), "bob");
+}
+
+#[tokio::test]
+async fn custom_enum() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TYPE pg_temp.mood AS ENUM (
+                'sad',
+                'ok',
+                'happy'
+            )",
+        )
+        .await
+        .unwrap();
+
+    let select = client.prepare("SELECT $1::mood").await.unwrap();
+
+    let ty = &select.params()[0];
+    assert_eq!("mood", ty.name());
+    assert_eq!(
+        &Kind::Enum(vec![
+            "sad".to_string(),
+            "ok".to_string(),
+            "happy".to_string(),
+        ]),
+        ty.kind(),
+    );
+}
+
+#[tokio::test]
+async fn type_by_name() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TYPE pg_temp.weather AS ENUM ('sunny', 'rainy')")
+        .await
+        .unwrap();
+    let schema = client
+        .query_one(
+            "SELECT nspname::TEXT FROM pg_namespace WHERE oid = pg_my_temp_schema()",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get::<_, String>(0);
+
+    let ty = client.type_by_name(None, "weather").await.unwrap();
+    assert_eq!(ty.name(), "weather");
+    assert_eq!(ty.schema(), schema);
+    assert_eq!(
+        &Kind::Enum(vec!["sunny".to_string(), "rainy".to_string()]),
+        ty.kind(),
+    );
+
+    let qualified = client.type_by_name(Some(&schema), "weather").await.unwrap();
+    assert_eq!(qualified, ty);
+
+    let builtin = client
+        .type_by_name(Some("pg_catalog"), "int4")
+        .await
+        .unwrap();
+    assert_eq!(builtin, Type::INT4);
+
+    let err = client
+        .type_by_name(Some("public"), "weather")
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "unknown type `public.weather`");
+}
+
+#[tokio::test]
+async fn server_parameters() {
+    let client = connect("user=postgres").await;
+
+    let version = client.server_parameter("server_version").unwrap();
+    let show = client
+        .query_one("SHOW server_version", &[])
+        .await
+        .unwrap()
+        .get::<_, String>(0);
+    assert_eq!(version, show);
+    assert_eq!(client.server_parameter("nonexistent"), None);
+
+    // Changes are reported by the server as they happen.
+    client
+        .batch_execute("SET TimeZone = 'Pacific/Auckland'")
+        .await
+        .unwrap();
+    assert_eq!(
+        client.server_parameter("TimeZone").as_deref(),
+        Some("Pacific/Auckland")
+    );
+
+    let parameters = client.server_parameters();
+    assert_eq!(parameters["client_encoding"], "UTF8");
+    assert_eq!(parameters["TimeZone"], "Pacific/Auckland");
+
+    // The connection reads the same parameters.
+    let (client, connection) = connect_raw("user=postgres").await.unwrap();
+    assert_eq!(
+        connection.parameter("server_version"),
+        client.server_parameter("server_version")
+    );
+}
+
+#[tokio::test]
+async fn typeinfo_ranges_and_domains() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TYPE pg_temp.color AS ENUM ('red', 'blue');
+             CREATE DOMAIN pg_temp.primary_color AS pg_temp.color;
+             CREATE TYPE pg_temp.floatrange AS RANGE (subtype = float8, multirange_type_name = pg_temp.floatmultirange);
+             CREATE DOMAIN pg_temp.positive_range AS pg_temp.floatrange CHECK (lower(VALUE) > 0);",
+        )
+        .await
+        .unwrap();
+
+    let stmt = client
+        .prepare(
+            "SELECT '[1,2)'::pg_temp.floatrange,
+                    '{[1,2)}'::pg_temp.floatmultirange,
+                    '{[1,2)}'::INT4MULTIRANGE",
+        )
+        .await
+        .unwrap();
+    let types = stmt
+        .columns()
+        .iter()
+        .map(|c| c.type_().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(types[0].name(), "floatrange");
+    assert_eq!(types[0].kind(), &Kind::Range(Type::FLOAT8));
+    assert_eq!(types[1].name(), "floatmultirange");
+    assert_eq!(types[1].kind(), &Kind::Multirange(Type::FLOAT8));
+    assert_eq!(types[2], Type::INT4MULTI_RANGE);
+
+    // Result columns report the base type of a domain, so domains are looked up by name.
+    let primary_color = client.type_by_name(None, "primary_color").await.unwrap();
+    let color = match primary_color.kind() {
+        Kind::Domain(color) => color,
+        kind => panic!("unexpected kind {:?}", kind),
+    };
+    assert_eq!(color.name(), "color");
+    assert_eq!(
+        color.kind(),
+        &Kind::Enum(vec!["red".to_string(), "blue".to_string()])
+    );
+
+    let positive_range = client.type_by_name(None, "positive_range").await.unwrap();
+    assert_eq!(positive_range.kind(), &Kind::Domain(types[0].clone()));
+}
+
+#[tokio::test]
+async fn register_type() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy')")
+        .await
+        .unwrap();
+    let oid = client
+        .query_one("SELECT 'pg_temp.mood'::regtype::oid", &[])
+        .await
+        .unwrap()
+        .get::<_, u32>(0);
+    assert_eq!(client.cached_type(oid), None);
+
+    // The registered definition is used in place of the one in the database.
+    let registered = Type::new(
+        "registered_mood".to_string(),
+        oid,
+        Kind::Enum(vec!["sad".to_string(), "happy".to_string()]),
+        "pg_temp".to_string(),
+    );
+    client.register_type(registered.clone());
+    assert_eq!(client.cached_type(oid), Some(registered.clone()));
+
+    let stmt = client
+        .prepare("SELECT 'happy'::pg_temp.mood")
+        .await
+        .unwrap();
+    assert_eq!(stmt.columns()[0].type_(), &registered);
+
+    client.clear_type_cache();
+    assert_eq!(client.cached_type(oid), None);
+    let stmt = client
+        .prepare("SELECT 'happy'::pg_temp.mood")
+        .await
+        .unwrap();
+    assert_eq!(stmt.columns()[0].type_().name(), "mood");
+    assert_eq!(client.cached_type(oid).unwrap().name(), "mood");
+
+    assert_eq!(client.cached_type(Type::INT4.oid()), None);
 }
 
 #[tokio::test]
-async fn custom_enum() {
+async fn prepare_param_type_schemas() {
     let client = connect("user=postgres").await;
 
     client
         .batch_execute(
-            "CREATE TYPE pg_temp.mood AS ENUM (
-                'sad',
-                'ok',
-                'happy'
-            )",
+            "CREATE SCHEMA IF NOT EXISTS param_schema;
+             DROP TYPE IF EXISTS param_schema.color;
+             CREATE TYPE param_schema.color AS ENUM ('red', 'green')",
         )
         .await
         .unwrap();
 
-    let select = client.prepare("SELECT $1::mood").await.unwrap();
-
-    let ty = &select.params()[0];
-    assert_eq!("mood", ty.name());
-    assert_eq!(
-        &Kind::Enum(vec![
-            "sad".to_string(),
-            "ok".to_string(),
-            "happy".to_string(),
-        ]),
-        ty.kind(),
-    );
+    let stmt = client
+        .prepare("SELECT $1::param_schema.color, $2::INT4")
+        .await
+        .unwrap();
+    let params = stmt.params();
+    assert_eq!(params[0].schema(), "param_schema");
+    assert_eq!(params[0].qualified_name(), "param_schema.color");
+    assert_eq!(params[0].to_string(), "param_schema.color");
+    assert_eq!(params[1].qualified_name(), "pg_catalog.int4");
+    assert_eq!(params[1].to_string(), "int4");
+
+    // The type is cached, schema included, for later statements.
+    let stmt = client
+        .prepare("SELECT $1::param_schema.color[]")
+        .await
+        .unwrap();
+    match stmt.params()[0].kind() {
+        Kind::Array(member) => assert_eq!(member.qualified_name(), "param_schema.color"),
+        kind => panic!("unexpected kind {:?}", kind),
+    }
 }
 
 #[tokio::test]
@@ -359,6 +1677,162 @@ async fn simple_query() {
     assert_eq!(messages.len(), 6);
 }
 
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn simple_query_in() {
+    use tokio_postgres::arena::simple_query::SimpleQueryMessage;
+
+    let client = connect("user=postgres").await;
+
+    let arena = bumpalo::Bump::new();
+    let messages = client
+        .simple_query_in(
+            "CREATE TEMPORARY TABLE foo (
+                id SERIAL,
+                name TEXT
+            );
+            INSERT INTO foo (name) VALUES ('steven'), (NULL);
+            SELECT * FROM foo ORDER BY id;",
+            &arena,
+        )
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    match messages[0] {
+        SimpleQueryMessage::CommandComplete(0) => {}
+        _ => panic!("unexpected message"),
+    }
+    match messages[1] {
+        SimpleQueryMessage::CommandComplete(2) => {}
+        _ => panic!("unexpected message"),
+    }
+    match messages[2] {
+        SimpleQueryMessage::RowDescription(columns) => {
+            assert_eq!(columns.len(), 2);
+            assert_eq!(columns[0].name(), "id");
+            assert_eq!(columns[1].name(), "name");
+        }
+        _ => panic!("unexpected message"),
+    }
+    let names = messages[3..5]
+        .iter()
+        .map(|message| match message {
+            SimpleQueryMessage::Row(row) => {
+                assert_eq!(row.columns()[1].name(), "name");
+                row.get("name")
+            }
+            _ => panic!("unexpected message"),
+        })
+        .collect::<Vec<_>>();
+    match messages[5] {
+        SimpleQueryMessage::CommandComplete(2) => {}
+        _ => panic!("unexpected message"),
+    }
+    assert_eq!(messages.len(), 6);
+
+    // values borrow from the arena rather than from the rows
+    drop(messages);
+    assert_eq!(names, [Some("steven"), None]);
+}
+
+#[tokio::test]
+async fn batch_execute_statement_index() {
+    let client = connect("user=postgres").await;
+
+    let err = client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT);
+            INSERT INTO foo VALUES (1);
+            SELECT * FROM foo;
+            INSERT INTO foo VALUES ('bogus');
+            INSERT INTO foo VALUES (2);",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_TEXT_REPRESENTATION));
+    assert_eq!(err.statement_index(), Some(3));
+
+    let err = client.batch_execute("SELECT 1/0").await.unwrap_err();
+    assert_eq!(err.statement_index(), Some(0));
+
+    let err = client.query("SELECT 1/0", &[]).await.unwrap_err();
+    assert_eq!(err.statement_index(), None);
+}
+
+#[tokio::test]
+async fn error_retryable() {
+    let client = connect("user=postgres").await;
+
+    for (code, retryable) in [
+        ("40001", true),
+        ("40P01", true),
+        ("57P01", true),
+        ("23505", false),
+        ("22012", false),
+    ] {
+        let err = client
+            .batch_execute(&format!(
+                "DO $$ BEGIN RAISE EXCEPTION 'failed' USING ERRCODE = '{}'; END $$",
+                code
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(err.retryable(), retryable, "{}", code);
+    }
+
+    let err = client.batch_execute("SELEKT 1").await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::SYNTAX_ERROR));
+    assert!(!err.retryable());
+}
+
+#[tokio::test]
+async fn db_error_display() {
+    let client = connect("user=postgres").await;
+
+    let err = client
+        .batch_execute("SELECT 1 FROM nonexistent_table")
+        .await
+        .unwrap_err();
+    let db = err.as_db_error().unwrap();
+    assert_eq!(db.position(), Some(&ErrorPosition::Original(15)));
+    assert!(err.to_string().ends_with("\nPOSITION: 15"), "{}", err);
+
+    let err = client
+        .batch_execute("DO $$ BEGIN EXECUTE 'SELECT missing_column FROM pg_class'; END $$")
+        .await
+        .unwrap_err();
+    let db = err.as_db_error().unwrap();
+    assert_eq!(
+        db.position(),
+        Some(&ErrorPosition::Internal {
+            position: 8,
+            query: "SELECT missing_column FROM pg_class".to_string(),
+        })
+    );
+    assert!(
+        err.to_string().contains(
+            "\nINTERNAL QUERY: SELECT missing_column FROM pg_class\nINTERNAL POSITION: 8"
+        ),
+        "{}",
+        err
+    );
+
+    let err = client
+        .batch_execute("SELECT abs('a'::TEXT)")
+        .await
+        .unwrap_err();
+    let db = err.as_db_error().unwrap();
+    let hint = db.hint().unwrap();
+    assert!(
+        err.to_string().contains(&format!("\nHINT: {}", hint)),
+        "{}",
+        err
+    );
+}
+
 #[tokio::test]
 async fn cancel_query_raw() {
     let client = connect("user=postgres").await;
@@ -431,6 +1905,101 @@ async fn transaction_rollback() {
     assert_eq!(rows.len(), 0);
 }
 
+#[tokio::test]
+async fn with_transaction() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo(
+                id SERIAL,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let count = client
+        .with_transaction(|transaction| {
+            Box::pin(async move {
+                transaction
+                    .execute("INSERT INTO foo (name) VALUES ('steven')", &[])
+                    .await
+            })
+        })
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    client
+        .with_transaction(|transaction| {
+            Box::pin(async move {
+                transaction
+                    .execute("INSERT INTO foo (name) VALUES ('joe')", &[])
+                    .await?;
+                transaction.execute("SELECT 1/0", &[]).await
+            })
+        })
+        .await
+        .unwrap_err();
+
+    let rows = client.query("SELECT name FROM foo", &[]).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, &str>(0), "steven");
+    assert!(!in_transaction(&client).await);
+}
+
+#[tokio::test]
+async fn execute_all_in_transaction() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo(
+                id INT PRIMARY KEY,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let counts = client
+        .execute_all_in_transaction(&[
+            (
+                "INSERT INTO foo (id, name) VALUES ($1, $2), ($3, $4)",
+                &[&1i32, &"steven", &2i32, &"joe"],
+            ),
+            ("UPDATE foo SET name = upper(name)", &[]),
+            ("DELETE FROM foo WHERE id = $1", &[&3i32]),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(counts, [2, 2, 0]);
+    assert!(!in_transaction(&client).await);
+
+    let err = client
+        .execute_all_in_transaction(&[
+            ("DELETE FROM foo WHERE id = $1", &[&1i32]),
+            (
+                "INSERT INTO foo (id, name) VALUES ($1, $2)",
+                &[&2i32, &"bob"],
+            ),
+        ])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNIQUE_VIOLATION));
+    assert_eq!(err.statement_index(), Some(1));
+    assert!(!in_transaction(&client).await);
+
+    // The first statement was rolled back along with the failing one.
+    let rows = client
+        .query("SELECT name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    let names = rows.iter().map(|r| r.get::<_, &str>(0)).collect::<Vec<_>>();
+    assert_eq!(names, ["STEVEN", "JOE"]);
+}
+
 #[tokio::test]
 async fn transaction_future_cancellation() {
     let mut client = connect("user=postgres").await;
@@ -529,42 +2098,234 @@ async fn transaction_rollback_drop() {
     let stmt = client.prepare("SELECT name FROM foo").await.unwrap();
     let rows = client.query(&stmt, &[]).await.unwrap();
 
-    assert_eq!(rows.len(), 0);
+    assert_eq!(rows.len(), 0);
+}
+
+#[tokio::test]
+async fn transaction_drop_behavior() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo(
+                id SERIAL,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let mut transaction = client
+        .build_transaction()
+        .drop_behavior(DropBehavior::Commit)
+        .start()
+        .await
+        .unwrap();
+    assert_eq!(transaction.drop_behavior(), DropBehavior::Commit);
+    transaction
+        .batch_execute("INSERT INTO foo (name) VALUES ('steven')")
+        .await
+        .unwrap();
+    let nested = transaction.transaction().await.unwrap();
+    assert_eq!(nested.drop_behavior(), DropBehavior::Commit);
+    nested
+        .batch_execute("INSERT INTO foo (name) VALUES ('joe')")
+        .await
+        .unwrap();
+    drop(nested);
+    drop(transaction);
+
+    let transaction = client
+        .build_transaction()
+        .drop_behavior(DropBehavior::Panic)
+        .start()
+        .await
+        .unwrap();
+    transaction
+        .batch_execute("INSERT INTO foo (name) VALUES ('bob')")
+        .await
+        .unwrap();
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(transaction)));
+    assert!(panic.is_err());
+
+    let rows = client
+        .query("SELECT name FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    let names = rows.iter().map(|r| r.get(0)).collect::<Vec<&str>>();
+    assert_eq!(names, ["steven", "joe"]);
+}
+
+#[tokio::test]
+async fn transaction_builder() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo(
+                id SERIAL,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let transaction = client
+        .build_transaction()
+        .isolation_level(IsolationLevel::Serializable)
+        .read_only(true)
+        .deferrable(true)
+        .start()
+        .await
+        .unwrap();
+    transaction
+        .batch_execute("INSERT INTO foo (name) VALUES ('steven')")
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+
+    let stmt = client.prepare("SELECT name FROM foo").await.unwrap();
+    let rows = client.query(&stmt, &[]).await.unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, &str>(0), "steven");
+}
+
+#[tokio::test]
+async fn transaction_snapshot() {
+    let mut exporter = connect("user=postgres").await;
+    let mut importer = connect("user=postgres").await;
+    let writer = connect("user=postgres").await;
+
+    writer
+        .batch_execute(
+            "DROP TABLE IF EXISTS snapshot_test;
+             CREATE TABLE snapshot_test (id INT);
+             INSERT INTO snapshot_test VALUES (1)",
+        )
+        .await
+        .unwrap();
+
+    let transaction = exporter
+        .build_transaction()
+        .isolation_level(IsolationLevel::RepeatableRead)
+        .start()
+        .await
+        .unwrap();
+    let snapshot = transaction.export_snapshot().await.unwrap();
+
+    writer
+        .batch_execute("INSERT INTO snapshot_test VALUES (2)")
+        .await
+        .unwrap();
+
+    let imported = importer
+        .build_transaction()
+        .isolation_level(IsolationLevel::RepeatableRead)
+        .snapshot(&*snapshot)
+        .start()
+        .await
+        .unwrap();
+
+    let query = "SELECT array_agg(id ORDER BY id) FROM snapshot_test";
+    let exported_ids = transaction.query_one(query, &[]).await.unwrap();
+    let imported_ids = imported.query_one(query, &[]).await.unwrap();
+    assert_eq!(exported_ids.get::<_, Vec<i32>>(0), [1]);
+    assert_eq!(imported_ids.get::<_, Vec<i32>>(0), [1]);
+
+    imported.rollback().await.unwrap();
+    transaction.rollback().await.unwrap();
+    writer
+        .batch_execute("DROP TABLE snapshot_test")
+        .await
+        .unwrap();
 }
 
 #[tokio::test]
-async fn transaction_builder() {
+async fn query_in_schema() {
     let mut client = connect("user=postgres").await;
 
     client
         .batch_execute(
-            "CREATE TEMPORARY TABLE foo(
-                id SERIAL,
-                name TEXT
-            )",
+            "DROP SCHEMA IF EXISTS tenant_a CASCADE;
+             DROP SCHEMA IF EXISTS tenant_b CASCADE;
+             CREATE SCHEMA tenant_a;
+             CREATE SCHEMA tenant_b;
+             CREATE TABLE tenant_a.items (name TEXT);
+             CREATE TABLE tenant_b.items (name TEXT);",
         )
         .await
         .unwrap();
+    let original = client
+        .query_one("SHOW search_path", &[])
+        .await
+        .unwrap()
+        .get::<_, String>(0);
 
-    let transaction = client
-        .build_transaction()
-        .isolation_level(IsolationLevel::Serializable)
-        .read_only(true)
-        .deferrable(true)
-        .start()
+    let rows = client
+        .query_in_schema(
+            "tenant_a",
+            "INSERT INTO items (name) VALUES ($1) RETURNING name",
+            &[&"alice"],
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "alice");
+    let rows = client
+        .query_in_schema("tenant_b", "SELECT name FROM items", &[])
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
+    let rows = client
+        .query_in_schema("tenant_a", "SELECT name FROM items", &[])
         .await
         .unwrap();
+    assert_eq!(rows.len(), 1);
+
+    let current = client
+        .query_one("SHOW search_path", &[])
+        .await
+        .unwrap()
+        .get::<_, String>(0);
+    assert_eq!(current, original);
+
+    let transaction = client.transaction().await.unwrap();
     transaction
-        .batch_execute("INSERT INTO foo (name) VALUES ('steven')")
+        .batch_execute("SET LOCAL search_path TO tenant_b")
         .await
         .unwrap();
+    transaction
+        .query_in_schema("tenant_a", "INSERT INTO items (name) VALUES ('bob')", &[])
+        .await
+        .unwrap();
+    let rows = transaction
+        .query("SELECT name FROM items", &[])
+        .await
+        .unwrap();
+    assert!(rows.is_empty());
     transaction.commit().await.unwrap();
 
-    let stmt = client.prepare("SELECT name FROM foo").await.unwrap();
-    let rows = client.query(&stmt, &[]).await.unwrap();
+    let rows = client
+        .query("SELECT name FROM tenant_a.items ORDER BY name", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
 
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0].get::<_, &str>(0), "steven");
+    client
+        .query_in_schema("tenant_a", "SELECT missing FROM items", &[])
+        .await
+        .unwrap_err();
+    let current = client
+        .query_one("SHOW search_path", &[])
+        .await
+        .unwrap()
+        .get::<_, String>(0);
+    assert_eq!(current, original);
+
+    client
+        .batch_execute("DROP SCHEMA tenant_a CASCADE; DROP SCHEMA tenant_b CASCADE")
+        .await
+        .unwrap();
 }
 
 #[tokio::test]
@@ -607,6 +2368,107 @@ async fn copy_in() {
     assert_eq!(rows[1].get::<_, &str>(1), "joe");
 }
 
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn copy_in_in() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER, name TEXT)")
+        .await
+        .unwrap();
+
+    let arena = bumpalo::Bump::new();
+    let sink = client
+        .copy_in_in("COPY foo FROM STDIN", &arena)
+        .await
+        .unwrap();
+    pin_mut!(sink);
+    sink.as_mut().set_flush_threshold(64);
+
+    // the buffer is reused between flushes, so the arena stops growing once it has been sized
+    let mut allocated = 0;
+    for i in 0..1_000 {
+        sink.feed(Bytes::from(format!("{0}\tname{0}\n", i)))
+            .await
+            .unwrap();
+        if i % 7 == 0 {
+            sink.as_mut().flush().await.unwrap();
+        }
+        if i == 100 {
+            allocated = arena.allocated_bytes();
+        }
+    }
+    let large = (1_000..2_000)
+        .map(|i| format!("{0}\tname{0}\n", i))
+        .collect::<String>();
+    sink.feed(Bytes::from(large)).await.unwrap();
+    assert_eq!(arena.allocated_bytes(), allocated);
+
+    let rows = sink.finish().await.unwrap();
+    assert_eq!(rows, 2_000);
+
+    let row = client
+        .query_one("SELECT count(*), sum(id) FROM foo", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 2_000);
+    assert_eq!(row.get::<_, i64>(1), 1_999_000);
+}
+
+#[tokio::test]
+async fn copy_in_flush() {
+    async fn wait_for_tuples(observer: &Client, pid: i32, expected: i64) {
+        for _ in 0..500 {
+            let processed = observer
+                .query_one(
+                    "SELECT tuples_processed FROM pg_stat_progress_copy WHERE pid = $1",
+                    &[&pid],
+                )
+                .await
+                .unwrap()
+                .get::<_, i64>(0);
+            if processed == expected {
+                return;
+            }
+            assert!(processed < expected);
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("server did not receive {} rows", expected);
+    }
+
+    let client = connect("user=postgres").await;
+    let observer = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER, name TEXT)")
+        .await
+        .unwrap();
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+
+    let sink = client.copy_in("COPY foo FROM STDIN").await.unwrap();
+    pin_mut!(sink);
+
+    // small writes are buffered until flushed
+    sink.feed(Bytes::from_static(b"1\tjim\n")).await.unwrap();
+    time::sleep(Duration::from_millis(50)).await;
+    wait_for_tuples(&observer, pid, 0).await;
+    sink.as_mut().flush().await.unwrap();
+    wait_for_tuples(&observer, pid, 1).await;
+
+    // with no threshold, each write is sent immediately
+    sink.as_mut().set_flush_threshold(0);
+    sink.feed(Bytes::from_static(b"2\tjoe\n")).await.unwrap();
+    wait_for_tuples(&observer, pid, 2).await;
+
+    let rows = sink.finish().await.unwrap();
+    assert_eq!(rows, 2);
+}
+
 #[tokio::test]
 async fn copy_in_large() {
     let client = connect("user=postgres").await;
@@ -670,6 +2532,39 @@ async fn copy_in_error() {
     assert_eq!(rows.len(), 0);
 }
 
+#[tokio::test]
+async fn copy_in_error_progress() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INTEGER,
+                name TEXT
+            )",
+        )
+        .await
+        .unwrap();
+
+    let mut stream = stream::iter(
+        vec![
+            Bytes::from_static(b"1\tjim\n"),
+            Bytes::from_static(b"x\tjoe\n"),
+        ]
+        .into_iter()
+        .map(Ok::<_, Error>),
+    );
+    let sink = client.copy_in("COPY foo FROM STDIN").await.unwrap();
+    pin_mut!(sink);
+    sink.send_all(&mut stream).await.unwrap();
+    let err = sink.finish().await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_TEXT_REPRESENTATION));
+
+    let progress = err.copy_progress().unwrap();
+    assert_eq!(progress.bytes_sent(), 12);
+    assert_eq!(progress.rows_sent(), 2);
+}
+
 #[tokio::test]
 async fn copy_out() {
     let client = connect("user=postgres").await;
@@ -700,6 +2595,256 @@ async fn copy_out() {
     assert_eq!(&data[..], b"1\tjim\n2\tjoe\n");
 }
 
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn copy_out_in() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+            id SERIAL,
+            name TEXT
+        );
+
+        INSERT INTO foo (name) VALUES ('jim'), ('joe');",
+        )
+        .await
+        .unwrap();
+
+    let arena = bumpalo::Bump::new();
+    let stream = client
+        .copy_out_in("COPY foo TO STDOUT", &arena)
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    let mut data = vec![];
+    while let Some(chunk) = stream.try_next().await.unwrap() {
+        data.extend_from_slice(chunk);
+    }
+    assert_eq!(&data[..], b"1\tjim\n2\tjoe\n");
+    assert_eq!(stream.rows_affected(), Some(2));
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn copy_out_in_for_each_in() {
+    let client = connect("user=postgres").await;
+    let arena = bumpalo::Bump::new();
+    let mut scratch = bumpalo::Bump::new();
+
+    let stream = client
+        .copy_out_in(
+            "COPY (SELECT i, 'row ' || i FROM generate_series(1, 10000) i) TO STDOUT",
+            &arena,
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    let arena_bytes = arena.allocated_bytes();
+    let mut sum = 0;
+    stream
+        .as_mut()
+        .for_each_in(&mut scratch, |chunk, scratch| {
+            let line = std::str::from_utf8(chunk).unwrap();
+            let fields =
+                bumpalo::collections::Vec::from_iter_in(line.trim_end().split('\t'), scratch);
+            let i = fields[0].parse::<i64>().unwrap();
+            assert_eq!(fields[1], format!("row {}", i));
+            sum += i;
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(sum, 50_005_000);
+    assert_eq!(stream.rows_affected(), Some(10000));
+    assert!(arena.allocated_bytes() - arena_bytes < 1024);
+    assert!(scratch.allocated_bytes() < 8192);
+}
+
+#[cfg(feature = "digest")]
+#[tokio::test]
+async fn copy_out_digest() {
+    use sha2::{Digest, Sha256};
+
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+            id SERIAL,
+            name TEXT
+        );
+
+        INSERT INTO foo (name) VALUES ('jim'), ('joe');",
+        )
+        .await
+        .unwrap();
+
+    let stmt = client.prepare("COPY foo TO STDOUT").await.unwrap();
+    let (stream, handle) = client
+        .copy_out(&stmt)
+        .await
+        .unwrap()
+        .with_digest::<Sha256>();
+    pin_mut!(stream);
+
+    let mut data = BytesMut::new();
+    while let Some(chunk) = stream.try_next().await.unwrap() {
+        assert!(handle.digest().is_none());
+        data.extend_from_slice(&chunk);
+    }
+    assert_eq!(&data[..], b"1\tjim\n2\tjoe\n");
+    assert_eq!(handle.digest(), Some(Sha256::digest(&data)));
+}
+
+#[cfg(feature = "with-arrow-57")]
+#[tokio::test]
+async fn copy_out_arrow() {
+    use arrow_array_57::cast::AsArray;
+    use arrow_array_57::types::{Float64Type, Int32Type, Int64Type, TimestampMicrosecondType};
+    use arrow_array_57::Array;
+    use arrow_schema_57::{DataType, Field, Schema, TimeUnit};
+    use std::error::Error as _;
+    use std::sync::Arc;
+    use tokio_postgres::types::WrongType;
+
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (
+                id INT4 NOT NULL,
+                big INT8,
+                score FLOAT8,
+                name TEXT,
+                active BOOL,
+                created TIMESTAMP
+            );
+
+            INSERT INTO foo VALUES
+                (1, 10, 1.5, 'jim', true, '1970-01-01 00:00:01'),
+                (2, NULL, NULL, NULL, NULL, NULL),
+                (3, -30, -2.25, 'joe', false, '2000-01-01 00:00:00');",
+        )
+        .await
+        .unwrap();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("big", DataType::Int64, true),
+        Field::new("score", DataType::Float64, true),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("active", DataType::Boolean, true),
+        Field::new(
+            "created",
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            true,
+        ),
+    ]));
+
+    let batches = client
+        .copy_out_arrow("SELECT * FROM foo ORDER BY id", schema.clone(), 2)
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].num_rows(), 2);
+    assert_eq!(batches[1].num_rows(), 1);
+    assert_eq!(batches[0].schema(), schema);
+
+    let batch = &batches[0];
+    let ids = batch.column(0).as_primitive::<Int32Type>();
+    assert_eq!(ids.values(), &[1, 2]);
+    let bigs = batch.column(1).as_primitive::<Int64Type>();
+    assert_eq!(bigs.value(0), 10);
+    assert!(bigs.is_null(1));
+    let scores = batch.column(2).as_primitive::<Float64Type>();
+    assert_eq!(scores.value(0), 1.5);
+    assert!(scores.is_null(1));
+    let names = batch.column(3).as_string::<i32>();
+    assert_eq!(names.value(0), "jim");
+    assert!(names.is_null(1));
+    let active = batch.column(4).as_boolean();
+    assert!(active.value(0));
+    assert!(active.is_null(1));
+    let created = batch.column(5).as_primitive::<TimestampMicrosecondType>();
+    assert_eq!(created.value(0), 1_000_000);
+    assert!(created.is_null(1));
+
+    let batch = &batches[1];
+    assert_eq!(batch.column(1).as_primitive::<Int64Type>().value(0), -30);
+    assert_eq!(batch.column(3).as_string::<i32>().value(0), "joe");
+    assert!(!batch.column(4).as_boolean().value(0));
+    assert_eq!(
+        batch
+            .column(5)
+            .as_primitive::<TimestampMicrosecondType>()
+            .value(0),
+        946_684_800_000_000
+    );
+
+    let schema = Arc::new(Schema::new(vec![Field::new("big", DataType::Int64, false)]));
+    let err = client
+        .copy_out_arrow("SELECT big FROM foo ORDER BY id", schema, 2)
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unexpected NULL"), "{}", err);
+
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::UInt32, false)]));
+    let err = client
+        .copy_out_arrow("SELECT id FROM foo", schema, 2)
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("not supported"), "{}", err);
+
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, false)]));
+    let err = client
+        .copy_out_arrow("SELECT id FROM foo", schema, 2)
+        .await
+        .err()
+        .unwrap();
+    let wrong_type = err.source().unwrap().downcast_ref::<WrongType>().unwrap();
+    assert_eq!(wrong_type.postgres_type(), &Type::INT4);
+    assert_eq!(wrong_type.column(), Some("id"));
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("name", DataType::Utf8, true),
+    ]));
+    let err = client
+        .copy_out_arrow("SELECT id FROM foo", schema, 2)
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().contains("expected 2 columns but got 1"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn copy_not_copy_statement() {
+    let client = connect("user=postgres").await;
+
+    let err = client.copy_in::<_, Bytes>("SELECT 1").await.err().unwrap();
+    assert!(err.to_string().contains("COPY"), "{}", err);
+
+    let err = client.copy_out("SELECT 1").await.err().unwrap();
+    assert!(err.to_string().contains("COPY"), "{}", err);
+
+    let row = client.query_one("SELECT 2", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 2);
+}
+
 #[tokio::test]
 async fn notices() {
     let long_name = "x".repeat(65);
@@ -740,6 +2885,58 @@ async fn notices() {
     );
 }
 
+#[tokio::test]
+async fn query_with_notices() {
+    let (client, mut connection) = connect_raw("user=postgres").await.unwrap();
+
+    let (tx, rx) = mpsc::unbounded();
+    let stream =
+        stream::poll_fn(move |cx| connection.poll_message(cx)).map_err(|e| panic!("{}", e));
+    let connection = stream.forward(tx).map(|r| r.unwrap());
+    tokio::spawn(connection);
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT);
+             INSERT INTO foo VALUES (1), (2);
+             CREATE FUNCTION pg_temp.noisy(id INT) RETURNS INT AS $$
+             BEGIN
+                 RAISE NOTICE 'visiting %', id;
+                 RETURN id;
+             END
+             $$ LANGUAGE plpgsql",
+        )
+        .await
+        .unwrap();
+
+    let (rows, notices) = client
+        .query_with_notices("SELECT pg_temp.noisy(id) FROM foo ORDER BY id", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+    let messages = notices.iter().map(|n| n.message()).collect::<Vec<_>>();
+    assert_eq!(messages, ["visiting 1", "visiting 2"]);
+
+    let (rows, notices) = client.query_with_notices("SELECT 1", &[]).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(notices.is_empty());
+
+    // Notices from other queries are still returned by the connection.
+    client.query("SELECT pg_temp.noisy(3)", &[]).await.unwrap();
+    drop(client);
+
+    let notices = rx
+        .filter_map(|m| match m {
+            AsyncMessage::Notice(n) => future::ready(Some(n)),
+            _ => future::ready(None),
+        })
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].message(), "visiting 3");
+}
+
 #[tokio::test]
 async fn notifications() {
     let (client, mut connection) = connect_raw("user=postgres").await.unwrap();
@@ -775,6 +2972,66 @@ async fn notifications() {
     assert_eq!(notifications[1].payload(), "world");
 }
 
+#[tokio::test]
+async fn notification_stream() {
+    let listener = connect("user=postgres").await;
+    let notifier = connect("user=postgres").await;
+
+    let mut notifications = listener.notifications();
+    listener
+        .batch_execute("LISTEN test_notification_stream")
+        .await
+        .unwrap();
+
+    notifier
+        .batch_execute("NOTIFY test_notification_stream, 'hello'")
+        .await
+        .unwrap();
+    let process_id = notifier
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+
+    let notification = notifications.next().await.unwrap();
+    assert_eq!(notification.channel(), "test_notification_stream");
+    assert_eq!(notification.payload(), "hello");
+    assert_eq!(notification.process_id(), process_id);
+
+    // The stream ends once the connection closes.
+    drop(listener);
+    assert!(notifications.next().await.is_none());
+}
+
+#[tokio::test]
+async fn notification_stream_max_buffered() {
+    let socket = TcpStream::connect("127.0.0.1:5433").await.unwrap();
+    let mut config = "user=postgres".parse::<Config>().unwrap();
+    config.max_buffered_notifications(2);
+    let (client, connection) = config.connect_raw(socket, NoTls).await.unwrap();
+    tokio::spawn(connection.map(|r| r.unwrap()));
+
+    let mut notifications = client.notifications();
+    client
+        .batch_execute(
+            "LISTEN test_notification_stream_max_buffered;
+             NOTIFY test_notification_stream_max_buffered, 'a';
+             NOTIFY test_notification_stream_max_buffered, 'b';
+             NOTIFY test_notification_stream_max_buffered, 'c';",
+        )
+        .await
+        .unwrap();
+    drop(client);
+
+    // The oldest notification was discarded to make room for the last one.
+    let mut payloads = vec![];
+    while let Some(notification) = notifications.next().await {
+        payloads.push(notification.payload().to_string());
+    }
+    assert_eq!(payloads, ["b", "c"]);
+    assert_eq!(notifications.dropped(), 1);
+}
+
 #[tokio::test]
 async fn query_portal() {
     let mut client = connect("user=postgres").await;
@@ -818,6 +3075,99 @@ async fn query_portal() {
     assert_eq!(r3.len(), 0);
 }
 
+#[tokio::test]
+async fn query_named_portals() {
+    let mut client = connect("user=postgres").await;
+
+    let stmt = client
+        .prepare("SELECT i FROM generate_series($1::INT, 6, 2) i")
+        .await
+        .unwrap();
+
+    let transaction = client.transaction().await.unwrap();
+
+    let odds = transaction.bind_named("odds", &stmt, &[&1]).await.unwrap();
+    let evens = transaction.bind_named("evens", &stmt, &[&2]).await.unwrap();
+
+    let names = transaction
+        .query(
+            "SELECT name FROM pg_cursors WHERE name IN ('odds', 'evens') ORDER BY name",
+            &[],
+        )
+        .await
+        .unwrap();
+    let names = names.iter().map(|r| r.get(0)).collect::<Vec<&str>>();
+    assert_eq!(names, ["evens", "odds"]);
+
+    let mut values = vec![];
+    for _ in 0..3 {
+        for portal in [&odds, &evens] {
+            let rows = transaction.query_portal(portal, 1).await.unwrap();
+            values.push(rows[0].get::<_, i32>(0));
+        }
+    }
+    assert_eq!(values, [1, 2, 3, 4, 5, 6]);
+
+    let err = transaction
+        .bind_named("odds", &stmt, &[&1])
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::DUPLICATE_CURSOR));
+
+    let err = transaction
+        .bind_named("", &stmt, &[&1])
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("portal name must not be empty"));
+}
+
+#[tokio::test]
+async fn fetch_all_cursor() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE FUNCTION pg_temp.open_cursors() RETURNS SETOF refcursor AS $$
+            DECLARE
+                a refcursor := 'numbers';
+                b refcursor;
+            BEGIN
+                OPEN a FOR SELECT generate_series(1, 3);
+                RETURN NEXT a;
+                OPEN b FOR SELECT 'alice'::TEXT UNION ALL SELECT 'bob';
+                RETURN NEXT b;
+            END;
+            $$ LANGUAGE plpgsql",
+        )
+        .await
+        .unwrap();
+
+    let transaction = client.transaction().await.unwrap();
+    let cursors = transaction
+        .query("SELECT * FROM pg_temp.open_cursors()", &[])
+        .await
+        .unwrap();
+    assert_eq!(cursors.len(), 2);
+    assert_eq!(cursors[0].columns()[0].type_(), &Type::REFCURSOR);
+
+    let name = cursors[0].get::<_, &str>(0);
+    assert_eq!(name, "numbers");
+    let rows = transaction.fetch_all_cursor(name).await.unwrap();
+    let values = rows.iter().map(|r| r.get(0)).collect::<Vec<i32>>();
+    assert_eq!(values, [1, 2, 3]);
+
+    let rows = transaction
+        .fetch_all_cursor(cursors[1].get(0))
+        .await
+        .unwrap();
+    let values = rows.iter().map(|r| r.get(0)).collect::<Vec<&str>>();
+    assert_eq!(values, ["alice", "bob"]);
+
+    assert!(transaction.fetch_all_cursor(name).await.unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn require_channel_binding() {
     connect_raw("user=postgres channel_binding=require")
@@ -960,6 +3310,425 @@ async fn deferred_constraint() {
         .unwrap_err();
 }
 
+#[tokio::test]
+async fn query_raw_text_params() {
+    #[derive(Debug)]
+    struct TextPoint(&'static str);
+
+    impl ToSql for TextPoint {
+        fn to_sql(
+            &self,
+            _: &Type,
+            out: &mut BytesMut,
+        ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            out.extend_from_slice(self.0.as_bytes());
+            Ok(IsNull::No)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            *ty == Type::POINT || *ty == Type::INT4
+        }
+
+        to_sql_checked!();
+    }
+
+    let client = connect("user=postgres").await;
+
+    let rows = client
+        .query_raw_text_params(
+            "SELECT ($1::POINT)[0] AS x, $2::INT4 AS i",
+            [TextPoint("(1.5,2)"), TextPoint("42")],
+        )
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, f64>("x"), 1.5);
+    assert_eq!(rows[0].get::<_, i32>("i"), 42);
+}
+
+#[tokio::test]
+async fn query_raw_retained_and_dropped_rows() {
+    let client = connect("user=postgres").await;
+
+    let stream = client
+        .query_raw(
+            "SELECT i, CASE WHEN i % 2 = 0 THEN NULL ELSE 'v' || i END
+            FROM generate_series(1, 100) i",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap();
+    pin_mut!(stream);
+
+    let mut kept = vec![];
+    while let Some(row) = stream.try_next().await.unwrap() {
+        let i = row.get::<_, i32>(0);
+        let value = row.get::<_, Option<&str>>(1);
+        assert_eq!(
+            value,
+            Some(format!("v{}", i)).filter(|_| i % 2 != 0).as_deref()
+        );
+        if i % 10 == 3 || i % 10 == 4 {
+            kept.push(row.clone());
+            kept.push(row);
+        }
+    }
+
+    assert_eq!(kept.len(), 40);
+    for pair in kept.chunks(2) {
+        let i = pair[0].get::<_, i32>(0);
+        assert_eq!(pair[1].get::<_, i32>(0), i);
+        let expected = Some(format!("v{}", i)).filter(|_| i % 2 != 0);
+        assert_eq!(pair[0].get::<_, Option<&str>>(1), expected.as_deref());
+        assert_eq!(pair[1].get::<_, Option<&str>>(1), expected.as_deref());
+    }
+}
+
+#[tokio::test]
+async fn query_raw_prefetch() {
+    let client = connect("user=postgres").await;
+
+    let stream = client
+        .query_raw(
+            "SELECT i FROM generate_series(1, 1000) i",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap()
+        .with_prefetch(16);
+    pin_mut!(stream);
+
+    let mut expected = 1;
+    while let Some(row) = stream.try_next().await.unwrap() {
+        assert_eq!(row.get::<_, i32>(0), expected);
+        expected += 1;
+        if expected % 100 == 0 {
+            time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+    assert_eq!(expected, 1001);
+    assert_eq!(stream.rows_affected(), Some(1000));
+    assert!(stream.next().await.is_none());
+
+    let stream = client
+        .query_raw(
+            "SELECT 10 / (5 - i) FROM generate_series(1, 10) i",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap()
+        .with_prefetch(16);
+    pin_mut!(stream);
+
+    for _ in 0..4 {
+        stream.next().await.unwrap().unwrap();
+    }
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::DIVISION_BY_ZERO));
+    assert!(stream.next().await.is_none());
+
+    client.batch_execute("SELECT 1").await.unwrap();
+}
+
+#[tokio::test]
+async fn query_raw_split_at_command_boundary() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INT)")
+        .await
+        .unwrap();
+
+    let stream = client
+        .query_raw(
+            "SELECT i FROM generate_series(1, 3) i",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap()
+        .split_at_command_boundary();
+    pin_mut!(stream);
+
+    for expected in 1..=3 {
+        match stream.try_next().await.unwrap() {
+            Some(ResultSetMessage::Row(row)) => assert_eq!(row.get::<_, i32>(0), expected),
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+    match stream.try_next().await.unwrap() {
+        Some(ResultSetMessage::ResultSetEnd(3)) => {}
+        message => panic!("unexpected message {:?}", message),
+    }
+    assert!(stream.try_next().await.unwrap().is_none());
+    assert_eq!(stream.rows_affected(), Some(3));
+
+    let stream = client
+        .query_raw("INSERT INTO foo VALUES (1), (2)", std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .split_at_command_boundary();
+    let messages = stream.try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert!(matches!(messages[0], ResultSetMessage::ResultSetEnd(2)));
+}
+
+#[tokio::test]
+async fn explain() {
+    let mut client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT, name TEXT);
+            INSERT INTO foo SELECT i, 'n' || i FROM generate_series(1, 10) i;",
+        )
+        .await
+        .unwrap();
+
+    let plan = client
+        .explain(
+            "SELECT name FROM foo WHERE id = $1",
+            &[&3i32],
+            ExplainOptions::new(),
+        )
+        .await
+        .unwrap();
+    assert!(plan.starts_with("Seq Scan on foo"), "{}", plan);
+    assert!(plan.contains('\n'), "{}", plan);
+    assert!(!plan.contains("actual time"), "{}", plan);
+
+    let plan = client
+        .explain(
+            "SELECT name FROM foo WHERE id = $1",
+            &[&3i32],
+            ExplainOptions::new()
+                .analyze(true)
+                .buffers(true)
+                .verbose(true),
+        )
+        .await
+        .unwrap();
+    assert!(plan.contains("actual time"), "{}", plan);
+    assert!(plan.contains("Output: name"), "{}", plan);
+    assert!(plan.contains("Rows Removed by Filter: 9"), "{}", plan);
+
+    let plan = client
+        .explain(
+            "SELECT name FROM foo",
+            &[],
+            ExplainOptions::new().format(ExplainFormat::Json),
+        )
+        .await
+        .unwrap();
+    assert!(plan.starts_with('['), "{}", plan);
+    assert!(plan.contains("\"Node Type\": \"Seq Scan\""), "{}", plan);
+
+    let transaction = client.transaction().await.unwrap();
+    transaction
+        .explain("DELETE FROM foo", &[], ExplainOptions::new().analyze(true))
+        .await
+        .unwrap();
+    transaction.rollback().await.unwrap();
+
+    let row = client
+        .query_one("SELECT count(*) FROM foo", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 10);
+}
+
+#[tokio::test]
+async fn row_index() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT 1::INT4 AS id, 'hi'::TEXT AS name", &[])
+        .await
+        .unwrap();
+
+    assert_eq!(&row[0], &[0, 0, 0, 1]);
+    assert_eq!(&row["name"], b"hi");
+    assert_eq!(&row["NAME"], b"hi");
+}
+
+#[tokio::test]
+#[should_panic(expected = "column missing is NULL")]
+async fn row_index_null() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT NULL::TEXT AS missing", &[])
+        .await
+        .unwrap();
+    let _ = &row["missing"];
+}
+
+#[tokio::test]
+#[should_panic(expected = "invalid column `1`")]
+async fn row_index_out_of_bounds() {
+    let client = connect("user=postgres").await;
+
+    let row = client.query_one("SELECT 1", &[]).await.unwrap();
+    let _ = &row[1];
+}
+
+#[tokio::test]
+async fn collect_columnar() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (id INT, name TEXT, score FLOAT8);
+            INSERT INTO foo SELECT i, 'n' || i, NULLIF(i % 3, 0) * 1.5 FROM generate_series(1, 10) i;",
+        )
+        .await
+        .unwrap();
+
+    let store = client
+        .query_raw(
+            "SELECT id, name, score FROM foo ORDER BY id",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap()
+        .collect_columnar(&[Type::INT4, Type::TEXT, Type::FLOAT8])
+        .await
+        .unwrap();
+
+    assert_eq!(store.len(), 10);
+    let columns = store.columns();
+    assert_eq!(columns[0].name(), "id");
+    assert_eq!(columns[0].data(), &ColumnData::Int4((1..=10).collect()));
+    assert_eq!(columns[0].null_count(), 0);
+    assert_eq!(
+        columns[1].data(),
+        &ColumnData::Text((1..=10).map(|i| format!("n{}", i)).collect())
+    );
+    assert_eq!(
+        columns[2].data(),
+        &ColumnData::Float8(vec![1.5, 3.0, 0.0, 1.5, 3.0, 0.0, 1.5, 3.0, 0.0, 1.5])
+    );
+    assert_eq!(columns[2].null_count(), 3);
+    assert!(columns[2].is_valid(1));
+    assert!(!columns[2].is_valid(2));
+    assert!(!columns[2].is_valid(8));
+    assert_eq!(columns[2].validity(), &[0b1101_1011, 0b10]);
+
+    let err = client
+        .query_raw("SELECT id FROM foo", std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .collect_columnar(&[Type::INT8])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("column 0"), "{}", err);
+
+    let err = client
+        .query_raw("SELECT id FROM foo", std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .collect_columnar(&[Type::INT4, Type::TEXT])
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "expected 1 columns but got 2");
+}
+
+#[cfg(feature = "csv")]
+#[tokio::test]
+async fn write_csv() {
+    let client = connect("user=postgres").await;
+
+    let query = r#"SELECT * FROM (VALUES
+            (1, 'plain', true, 1.5::FLOAT8, '\x00ff'::BYTEA, '{"a": [1, 2]}'::JSONB),
+            (2, 'has,comma', false, 'Infinity', NULL, NULL),
+            (3, 'has "quotes"', NULL, -0.1, '\x', 'null'),
+            (4, E'line\nbreak', true, 'NaN', NULL, '[]'),
+            (5, '', NULL, NULL, NULL, NULL),
+            (6, NULL, NULL, NULL, NULL, NULL)
+        ) AS t (id, "the name", flag, score, data, doc)
+        ORDER BY id"#;
+
+    let mut csv = vec![];
+    let rows = client
+        .query_raw(query, std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .write_csv(&mut csv, true)
+        .await
+        .unwrap();
+    assert_eq!(rows, 6);
+
+    // Postgres's own CSV output should be identical
+    let stream = client
+        .copy_out(&*format!(
+            "COPY ({}) TO STDOUT WITH (FORMAT csv, HEADER)",
+            query
+        ))
+        .await
+        .unwrap();
+    let expected = stream
+        .try_fold(vec![], |mut buf, chunk| async move {
+            buf.extend_from_slice(&chunk);
+            Ok(buf)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+
+    // Floats switch to exponent notation at the same points as Postgres's own output
+    let special = "'NaN', 'Infinity', '-Infinity', 0, -0.0, 1.5, 0.0001, 0.00001, 1.5e-5";
+    let queries = [
+        format!(
+            "SELECT x FROM unnest(ARRAY[{}, 1e15, 123456789012345.6, 1.2345678901234567e15, 1e16, 1e300, -2.5e-300]::FLOAT8[]) x",
+            special
+        ),
+        format!(
+            "SELECT x FROM unnest(ARRAY[{}, 123456, 1234567, 1e6, 3.4e38, 1.5e-38]::FLOAT4[]) x",
+            special
+        ),
+    ];
+    for query in &queries {
+        let mut csv = vec![];
+        client
+            .query_raw(query, std::iter::empty::<i32>())
+            .await
+            .unwrap()
+            .write_csv(&mut csv, false)
+            .await
+            .unwrap();
+        let stream = client
+            .copy_out(&*format!("COPY ({}) TO STDOUT WITH (FORMAT csv)", query))
+            .await
+            .unwrap();
+        let expected = stream
+            .try_fold(vec![], |mut buf, chunk| async move {
+                buf.extend_from_slice(&chunk);
+                Ok(buf)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+    }
+
+    let err = client
+        .query_raw("SELECT now()", std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .write_csv(&mut vec![], false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("column 0"), "{}", err);
+}
+
 #[tokio::test]
 async fn query_typed_no_transaction() {
     let client = connect("user=postgres").await;
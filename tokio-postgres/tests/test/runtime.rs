@@ -1,8 +1,11 @@
-use futures_util::{join, FutureExt};
-use std::time::Duration;
+use bytes::Bytes;
+use futures_util::{join, pin_mut, FutureExt, SinkExt, StreamExt, TryStreamExt};
+use std::time::{Duration, Instant};
 use tokio::time;
 use tokio_postgres::error::SqlState;
-use tokio_postgres::{Client, NoTls};
+use tokio_postgres::{
+    Client, Config, NoTls, ReconnectingClient, ResultSetMessage, SimpleQueryMessage,
+};
 
 async fn connect(s: &str) -> Client {
     let (client, connection) = tokio_postgres::connect(s, NoTls).await.unwrap();
@@ -66,6 +69,34 @@ async fn target_session_attrs_err() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn target_session_attrs_prefer_standby() {
+    smoke_test(
+        "host=localhost port=5433 user=postgres target_session_attrs=prefer-standby
+         options='-c default_transaction_read_only=on'",
+    )
+    .await;
+    // falls back to a read-write server
+    smoke_test("host=localhost port=5433 user=postgres target_session_attrs=prefer-standby").await;
+}
+
+#[tokio::test]
+async fn validation_query() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+
+    config.validation_query("SELECT NOT pg_is_in_recovery()");
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client.simple_query("SELECT 1").await.unwrap();
+
+    for query in ["SELECT false", "SELECT true WHERE false", "SELECT 1/0"] {
+        config.validation_query(query);
+        config.connect(NoTls).await.err().unwrap();
+    }
+}
+
 #[tokio::test]
 async fn host_only_ok() {
     let _ = tokio_postgres::connect(
@@ -118,6 +149,35 @@ async fn hostaddr_host_both_missing() {
     .unwrap();
 }
 
+#[tokio::test]
+async fn connect_retries_refused() {
+    let mut config = "host=localhost port=1 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config
+        .connect_retries(2)
+        .connect_retry_backoff(Duration::from_millis(50));
+
+    let start = Instant::now();
+    config.connect(NoTls).await.err().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn connect_retries_skip_auth_failure() {
+    let mut config = "host=localhost port=5433 user=pass_user password=foo dbname=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config
+        .connect_retries(3)
+        .connect_retry_backoff(Duration::from_secs(10));
+
+    let start = Instant::now();
+    let e = config.connect(NoTls).await.err().unwrap();
+    assert_eq!(e.code(), Some(&SqlState::INVALID_PASSWORD));
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
 #[tokio::test]
 async fn cancel_query() {
     let client = connect("host=localhost port=5433 user=postgres").await;
@@ -133,3 +193,955 @@ async fn cancel_query() {
         t => panic!("unexpected return: {:?}", t),
     }
 }
+
+#[tokio::test]
+async fn cancel_query_handle() {
+    let client = connect("host=localhost port=5433 user=postgres").await;
+    let cancel_token = client.cancel_token();
+
+    let stream = client
+        .query_raw("SELECT 1", std::iter::empty::<i32>())
+        .await
+        .unwrap();
+    let finished = stream.query_handle();
+    stream.try_collect::<Vec<_>>().await.unwrap();
+    assert!(finished.is_complete());
+
+    // cancelling a finished query leaves the next one alone
+    let cancel = cancel_token.cancel_query_handle(&finished, NoTls);
+    let cancel = time::sleep(Duration::from_millis(100)).then(|()| cancel);
+    let sleep = client.batch_execute("SELECT pg_sleep(0.5)");
+    let (sleep, cancel) = join!(sleep, cancel);
+    sleep.unwrap();
+    cancel.unwrap();
+
+    // enough rows are returned before the sleep that the stream is available while the query is still running
+    let stream = client
+        .query_raw(
+            "SELECT pg_sleep(CASE WHEN i = 10000 THEN 100 ELSE 0 END)
+             FROM generate_series(1, 10000) i",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap();
+    let handle = stream.query_handle();
+    assert!(!handle.is_complete());
+    cancel_token
+        .cancel_query_handle(&handle, NoTls)
+        .await
+        .unwrap();
+    let err = stream.try_collect::<Vec<_>>().await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+    assert!(handle.is_complete());
+}
+
+#[tokio::test]
+async fn query_deadline() {
+    let client = connect("host=localhost port=5433 user=postgres").await;
+
+    let deadline = time::Instant::now() + Duration::from_secs(10);
+    let rows = client
+        .query_deadline("SELECT 1::INT", &[], deadline, NoTls)
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+
+    let start = Instant::now();
+    let deadline = time::Instant::now() + Duration::from_millis(100);
+    let e = client
+        .query_deadline("SELECT pg_sleep(100)", &[], deadline, NoTls)
+        .await
+        .unwrap_err();
+    assert!(e.is_timeout());
+    assert!(e.retryable());
+    assert_eq!(e.code(), None);
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    let rows = client.query("SELECT 2::INT", &[]).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+}
+
+#[tokio::test]
+async fn row_timeout() {
+    let client = connect("host=localhost port=5433 user=postgres").await;
+
+    let stream = client
+        .query_raw("SELECT generate_series(1, 3)", std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .with_row_timeout(Duration::from_secs(10), client.cancel_token(), NoTls);
+    let rows = stream.try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(rows.len(), 3);
+
+    // Enough rows to fill the server's output buffer arrive before the stall, so the stream is already running.
+    let start = Instant::now();
+    let mut stream = client
+        .query_raw(
+            "SELECT repeat('a', 1000) FROM generate_series(1, 100)
+             UNION ALL
+             SELECT repeat('b', 1000) FROM pg_sleep(100)",
+            std::iter::empty::<i32>(),
+        )
+        .await
+        .unwrap()
+        .with_row_timeout(Duration::from_millis(500), client.cancel_token(), NoTls)
+        .boxed();
+
+    let mut rows = 0;
+    let e = loop {
+        match stream.try_next().await {
+            Ok(Some(_)) => rows += 1,
+            Ok(None) => panic!("stream ended without a timeout"),
+            Err(e) => break e,
+        }
+    };
+    assert!(rows > 0);
+    assert!(e.is_row_timeout());
+    assert!(!e.is_timeout());
+    assert!(stream.try_next().await.unwrap().is_none());
+    drop(stream);
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    let rows = client.query("SELECT 2::INT", &[]).await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn keepalive_socket_options() {
+    use socket2::SockRef;
+    use std::os::fd::BorrowedFd;
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config
+        .keepalives_idle(Duration::from_secs(4321))
+        .keepalives_interval(Duration::from_secs(17))
+        .keepalives_count(7);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client.simple_query("SELECT 1").await.unwrap();
+
+    // The socket is owned by the connection, so find it among the process's open file descriptors.
+    let found = std::fs::read_dir("/proc/self/fd").unwrap().any(|entry| {
+        let fd = match entry.unwrap().file_name().to_str().unwrap().parse() {
+            Ok(fd) => fd,
+            Err(_) => return false,
+        };
+        // SAFETY: the descriptor is only borrowed for the duration of this check.
+        let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+        let socket = SockRef::from(&fd);
+        matches!(socket.peer_addr().map(|a| a.as_socket()), Ok(Some(a)) if a.port() == 5433)
+            && socket.keepalive().unwrap_or(false)
+            && socket.keepalive_time().ok() == Some(Duration::from_secs(4321))
+            && socket.keepalive_interval().ok() == Some(Duration::from_secs(17))
+            && socket.keepalive_retries().ok() == Some(7)
+    });
+    assert!(found);
+}
+
+#[cfg(feature = "message-tracing")]
+#[tokio::test]
+async fn message_tracer() {
+    use std::sync::{Arc, Mutex};
+    use tokio_postgres::{Direction, MessageTag};
+
+    let messages = Arc::new(Mutex::new(vec![]));
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.message_tracer({
+        let messages = messages.clone();
+        Arc::new(move |direction, tag, len| messages.lock().unwrap().push((direction, tag, len)))
+    });
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    client.simple_query("SELECT 1").await.unwrap();
+
+    let messages = messages.lock().unwrap();
+    assert_eq!(messages[0].0, Direction::Frontend);
+    assert_eq!(messages[0].1, MessageTag(0));
+    // 'Q', the length header, and the nul-terminated query.
+    assert!(messages.contains(&(Direction::Frontend, MessageTag(b'Q'), 14)));
+    let backend = messages
+        .iter()
+        .filter(|m| m.0 == Direction::Backend)
+        .map(|m| m.1 .0)
+        .collect::<Vec<_>>();
+    assert_eq!(backend.first(), Some(&b'R'));
+    assert!(backend.ends_with(b"TDCZ"));
+}
+
+#[tokio::test]
+async fn reconnecting_client() {
+    let config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    let (client, connection) = ReconnectingClient::connect(config, NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let query = "SELECT $1::INT, pg_backend_pid()";
+    let row = client.query_one(query, &[&1i32]).await.unwrap();
+    let pid = row.get::<_, i32>(1);
+
+    let old = client.client().await.unwrap();
+    let killer = connect("host=localhost port=5433 user=postgres").await;
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+    while !old.is_closed() {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Work issued on the old connection's client fails rather than moving to the new connection.
+    assert!(old.batch_execute("SELECT 1").await.unwrap_err().is_closed());
+
+    let row = client.query_one(query, &[&2i32]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 2);
+    assert_ne!(row.get::<_, i32>(1), pid);
+
+    // The cached statement was prepared again on the new connection.
+    let prepared = client
+        .query_one(
+            "SELECT count(*) FROM pg_prepared_statements WHERE statement = $1",
+            &[&query],
+        )
+        .await
+        .unwrap();
+    assert_eq!(prepared.get::<_, i64>(0), 1);
+}
+
+#[tokio::test]
+async fn reconnecting_client_lost_transaction() {
+    let config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    let (client, connection) = ReconnectingClient::connect(config, NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    client.batch_execute("BEGIN").await.unwrap();
+    let row = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap();
+    let pid = row.get::<_, i32>(0);
+
+    let old = client.client().await.unwrap();
+    let killer = connect("host=localhost port=5433 user=postgres").await;
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+    while !old.is_closed() {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The rest of the transaction fails rather than running outside of it on the new connection.
+    let err = client.execute("SELECT 1", &[]).await.unwrap_err();
+    assert!(err.is_closed());
+    let err = client.batch_execute("COMMIT").await.unwrap_err();
+    assert!(err.is_closed());
+
+    // Once the transaction has been ended, statements run on the new connection.
+    let row = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap();
+    let new_pid = row.get::<_, i32>(0);
+    assert_ne!(new_pid, pid);
+
+    client.batch_execute("BEGIN").await.unwrap();
+    let old = client.client().await.unwrap();
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&new_pid])
+        .await
+        .unwrap();
+    while !old.is_closed() {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert!(client.query("SELECT 1", &[]).await.unwrap_err().is_closed());
+    client.batch_execute("ROLLBACK").await.unwrap();
+    client.query("SELECT 1", &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn query_rewriter() {
+    use std::borrow::Cow;
+    use std::sync::{Arc, Mutex};
+    use tokio_postgres::types::Type;
+
+    let seen = Arc::new(Mutex::new(vec![]));
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.query_rewriter({
+        let seen = seen.clone();
+        Arc::new(move |query| {
+            seen.lock().unwrap().push(query.to_string());
+            Cow::Owned(query.replace("__answer__", "42"))
+        })
+    });
+    let (mut client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    client
+        .batch_execute("CREATE TYPE pg_temp.mood AS ENUM ('happy', '__answer__')")
+        .await
+        .unwrap();
+
+    let row = client
+        .query_one("SELECT __answer__, 'happy'::mood::TEXT", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, i32>(0), 42);
+
+    let rows = client
+        .query_typed("SELECT $1::INT + __answer__", &[(&1i32, Type::INT4)])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 43);
+
+    // Only user queries sent with the extended protocol are rewritten, not simple queries or the client's own type
+    // lookups for the enum.
+    let row = client.query_one("SELECT 'happy'::mood", &[]).await.unwrap();
+    assert_eq!(row.columns()[0].type_().name(), "mood");
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            "SELECT __answer__, 'happy'::mood::TEXT",
+            "SELECT $1::INT + __answer__",
+            "SELECT 'happy'::mood",
+        ]
+    );
+
+    // Nor are the queries a transaction builds itself.
+    seen.lock().unwrap().clear();
+    let transaction = client.transaction().await.unwrap();
+    transaction.export_snapshot().await.unwrap();
+    let rows = transaction
+        .query_in_schema("pg_catalog", "SELECT current_schema()", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "pg_catalog");
+    transaction
+        .batch_execute("DECLARE answer CURSOR FOR SELECT 42")
+        .await
+        .unwrap();
+    let rows = transaction.fetch_all_cursor("answer").await.unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 42);
+    transaction.rollback().await.unwrap();
+    assert_eq!(*seen.lock().unwrap(), ["SELECT current_schema()"]);
+}
+
+#[tokio::test]
+async fn max_result_rows() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.max_result_rows(10);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let rows = client
+        .query("SELECT generate_series(1, 10)", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 10);
+
+    let err = client
+        .query("SELECT generate_series(1, 100000)", &[])
+        .await
+        .unwrap_err();
+    assert!(err.is_result_too_large());
+    assert_eq!(err.to_string(), "query returned more than 10 rows");
+
+    // The rest of the result is discarded, leaving the connection usable.
+    let row = client.query_one("SELECT 1", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+
+    // Inside of a transaction, the query is not cancelled, which would abort the transaction.
+    client.batch_execute("BEGIN").await.unwrap();
+    let err = client
+        .query(
+            "SELECT repeat('x', 10000), pg_sleep(0.01) FROM generate_series(1, 30)",
+            &[],
+        )
+        .await
+        .unwrap_err();
+    assert!(err.is_result_too_large());
+    let row = client.query_one("SELECT 1", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+    client.batch_execute("COMMIT").await.unwrap();
+
+    // Streaming is not limited.
+    let rows = client
+        .query_raw("SELECT generate_series(1, 20)", std::iter::empty::<i32>())
+        .await
+        .unwrap()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 20);
+}
+
+#[tokio::test]
+async fn max_result_rows_cancels_query() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.max_result_rows(5);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    // Each row fills the server's send buffer, so it arrives as soon as it is produced. Producing them all takes 10s.
+    let start = Instant::now();
+    let err = client
+        .query(
+            "SELECT repeat('x', 10000), pg_sleep(0.01) FROM generate_series(1, 1000)",
+            &[],
+        )
+        .await
+        .unwrap_err();
+    assert!(err.is_result_too_large());
+
+    // The server stopped the query rather than running it to completion before the next one.
+    let row = client.query_one("SELECT 1", &[]).await.unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn fetch_count() {
+    async fn open_cursors(client: &Client) -> String {
+        match &client
+            .simple_query("SELECT count(*) FROM pg_cursors")
+            .await
+            .unwrap()[1]
+        {
+            SimpleQueryMessage::Row(row) => row.get(0).unwrap().to_string(),
+            _ => panic!("expected a row"),
+        }
+    }
+
+    async fn prepared_statements(client: &Client) -> String {
+        match &client
+            .simple_query("SELECT count(*) FROM pg_prepared_statements")
+            .await
+            .unwrap()[1]
+        {
+            SimpleQueryMessage::Row(row) => row.get(0).unwrap().to_string(),
+            _ => panic!("expected a row"),
+        }
+    }
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.fetch_count(3);
+    let (mut client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let values = client
+        .query("SELECT generate_series(1, 10)", &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.get::<_, i32>(0))
+        .collect::<Vec<_>>();
+    assert_eq!(values, (1..=10).collect::<Vec<_>>());
+    assert_eq!(open_cursors(&client).await, "0");
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+    let observer = connect("host=localhost port=5433 user=postgres").await;
+
+    // The rows are read through a portal which stays open between batches, in a transaction of its own.
+    {
+        let stream = client
+            .query_raw("SELECT generate_series(1, 10)", std::iter::empty::<i32>())
+            .await
+            .unwrap();
+        pin_mut!(stream);
+        assert_eq!(
+            stream.try_next().await.unwrap().unwrap().get::<_, i32>(0),
+            1
+        );
+        let row = observer
+            .query_one("SELECT state FROM pg_stat_activity WHERE pid = $1", &[&pid])
+            .await
+            .unwrap();
+        assert_eq!(row.get::<_, &str>(0), "idle in transaction");
+
+        // Other requests are refused rather than being run inside of that transaction.
+        let err = client.simple_query("SELECT 1").await.unwrap_err();
+        assert!(err.is_cursor_transaction());
+        let mut rows = 1;
+        while stream.try_next().await.unwrap().is_some() {
+            rows += 1;
+        }
+        assert_eq!(rows, 10);
+        assert_eq!(stream.rows_affected(), Some(10));
+    }
+    assert_eq!(open_cursors(&client).await, "0");
+    assert_eq!(prepared_statements(&client).await, "0");
+
+    // The batches form a single result set.
+    {
+        let messages = client
+            .query_raw("SELECT generate_series(1, 10)", std::iter::empty::<i32>())
+            .await
+            .unwrap()
+            .split_at_command_boundary()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 11);
+        assert!(matches!(messages[10], ResultSetMessage::ResultSetEnd(10)));
+    }
+
+    // Dropping the stream early ends the transaction holding the portal.
+    {
+        let stream = client
+            .query_raw("SELECT generate_series(1, 10)", std::iter::empty::<i32>())
+            .await
+            .unwrap();
+        pin_mut!(stream);
+        stream.try_next().await.unwrap().unwrap();
+    }
+    assert_eq!(open_cursors(&client).await, "0");
+    // The statement is closed once the transaction has ended, rather than being refused while it is still open.
+    assert_eq!(prepared_statements(&client).await, "0");
+
+    let err = client.query("SELECT 1 / 0", &[]).await.unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::DIVISION_BY_ZERO));
+    assert_eq!(open_cursors(&client).await, "0");
+
+    // Within a transaction, the portal is created in that transaction rather than a new one.
+    let transaction = client.transaction().await.unwrap();
+    transaction
+        .batch_execute(
+            "CREATE TEMPORARY TABLE fetch_count (id INT); INSERT INTO fetch_count VALUES (1), (2)",
+        )
+        .await
+        .unwrap();
+    let rows = transaction
+        .query("SELECT id FROM fetch_count", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    transaction.rollback().await.unwrap();
+
+    let err = client
+        .query("SELECT id FROM fetch_count", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_TABLE));
+
+    // So is a transaction begun by executing `BEGIN` directly, which is left open.
+    client
+        .batch_execute(
+            "BEGIN; CREATE TEMPORARY TABLE fetch_count (id INT); INSERT INTO fetch_count VALUES (1)",
+        )
+        .await
+        .unwrap();
+    let rows = client
+        .query("SELECT id FROM fetch_count", &[])
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    client.batch_execute("ROLLBACK").await.unwrap();
+
+    let err = client
+        .query("SELECT id FROM fetch_count", &[])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::UNDEFINED_TABLE));
+
+    // Statements which don't return rows are executed as usual.
+    client.execute("SET TIME ZONE UTC", &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn statement_cache() {
+    async fn prepared(client: &Client, query: &str) -> String {
+        let query = format!(
+            "SELECT count(*) FROM pg_prepared_statements WHERE statement = '{}'",
+            query
+        );
+        match &client.simple_query(&query).await.unwrap()[1] {
+            SimpleQueryMessage::Row(row) => row.get(0).unwrap().to_string(),
+            _ => panic!("expected a row"),
+        }
+    }
+
+    let client = connect("host=localhost port=5433 user=postgres").await;
+    client.query("SELECT $1::INT", &[&1i32]).await.unwrap();
+    assert_eq!(prepared(&client, "SELECT $1::INT").await, "0");
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.statement_cache_capacity(2);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    // Repeated string queries are only prepared once.
+    for i in 0..3 {
+        let row = client.query_one("SELECT $1::INT", &[&i]).await.unwrap();
+        assert_eq!(row.get::<_, i32>(0), i);
+    }
+    client.execute("SELECT $1::INT", &[&3i32]).await.unwrap();
+    assert_eq!(prepared(&client, "SELECT $1::INT").await, "1");
+
+    let statement = client.prepare_cached("SELECT $1::INT").await.unwrap();
+    assert_eq!(statement.params(), [tokio_postgres::types::Type::INT4]);
+    drop(statement);
+
+    // The least recently used statement is evicted once the cache is full.
+    client.prepare_cached("SELECT 2").await.unwrap();
+    client.prepare_cached("SELECT 3").await.unwrap();
+    assert_eq!(prepared(&client, "SELECT $1::INT").await, "0");
+    assert_eq!(prepared(&client, "SELECT 2").await, "1");
+    assert_eq!(prepared(&client, "SELECT 3").await, "1");
+
+    client.clear_statement_cache();
+    assert_eq!(prepared(&client, "SELECT 2").await, "0");
+    assert_eq!(prepared(&client, "SELECT 3").await, "0");
+}
+
+#[tokio::test]
+async fn type_cache_capacity() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    config.type_cache_capacity(1);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    client
+        .batch_execute(
+            "CREATE TYPE pg_temp.first AS ENUM ('a');
+             CREATE TYPE pg_temp.second AS ENUM ('b');",
+        )
+        .await
+        .unwrap();
+    let row = client
+        .query_one(
+            "SELECT 'pg_temp.first'::regtype::oid, 'pg_temp.second'::regtype::oid",
+            &[],
+        )
+        .await
+        .unwrap();
+    let (first, second) = (row.get::<_, u32>(0), row.get::<_, u32>(1));
+
+    client.prepare("SELECT 'a'::pg_temp.first").await.unwrap();
+    assert_eq!(client.cached_type(first).unwrap().name(), "first");
+
+    // The least recently used type is evicted once the cache is full.
+    client.prepare("SELECT 'b'::pg_temp.second").await.unwrap();
+    assert_eq!(client.cached_type(first), None);
+    assert_eq!(client.cached_type(second).unwrap().name(), "second");
+
+    client.clear_type(second);
+    assert_eq!(client.cached_type(second), None);
+}
+
+#[tokio::test]
+async fn response_buffer_capacity() {
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+    assert_eq!(config.get_response_buffer_capacity(), 1);
+    config.response_buffer_capacity(16);
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    tokio::spawn(connection);
+
+    let stream = client
+        .query_raw("SELECT generate_series(1, 100000)", &[] as &[i32])
+        .await
+        .unwrap();
+    pin_mut!(stream);
+    let mut sum = 0;
+    while let Some(row) = stream.try_next().await.unwrap() {
+        sum += i64::from(row.get::<_, i32>(0));
+    }
+    assert_eq!(sum, 5_000_050_000);
+}
+
+#[cfg(feature = "gssapi")]
+#[tokio::test]
+async fn gss_auth() {
+    use std::error::Error;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_postgres::config::SslMode;
+    use tokio_postgres::{AuthMethod, GssContext};
+
+    struct MockContext {
+        steps: usize,
+    }
+
+    impl GssContext for MockContext {
+        fn step(
+            &mut self,
+            token: Option<&[u8]>,
+        ) -> Result<Option<Vec<u8>>, Box<dyn Error + Sync + Send>> {
+            self.steps += 1;
+            match (self.steps, token) {
+                (1, None) => Ok(Some(b"initial".to_vec())),
+                (2, Some(b"challenge")) => Ok(Some(b"response".to_vec())),
+                (3, Some(b"mutual")) => Ok(None),
+                _ => Err("bad token".into()),
+            }
+        }
+    }
+
+    async fn read_message(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let tag = stream.read_u8().await.unwrap();
+        let len = stream.read_i32().await.unwrap();
+        let mut body = vec![0; len as usize - 4];
+        stream.read_exact(&mut body).await.unwrap();
+        (tag, body)
+    }
+
+    async fn write_auth(stream: &mut TcpStream, code: i32, data: &[u8]) {
+        stream.write_u8(b'R').await.unwrap();
+        stream.write_i32(8 + data.len() as i32).await.unwrap();
+        stream.write_i32(code).await.unwrap();
+        stream.write_all(data).await.unwrap();
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        // The startup message has no type byte.
+        let len = stream.read_i32().await.unwrap();
+        let mut startup = vec![0; len as usize - 4];
+        stream.read_exact(&mut startup).await.unwrap();
+
+        write_auth(&mut stream, 7, &[]).await;
+        assert_eq!(read_message(&mut stream).await, (b'p', b"initial".to_vec()));
+        write_auth(&mut stream, 8, b"challenge").await;
+        assert_eq!(
+            read_message(&mut stream).await,
+            (b'p', b"response".to_vec())
+        );
+        write_auth(&mut stream, 8, b"mutual").await;
+        write_auth(&mut stream, 0, &[]).await;
+        stream.write_all(b"Z\0\0\0\x05I").await.unwrap();
+
+        // Wait for the client to hang up.
+        let _ = stream.read_u8().await;
+    });
+
+    let principals = Arc::new(Mutex::new(vec![]));
+    let mut config = Config::new();
+    config
+        .host("localhost")
+        .hostaddr("127.0.0.1".parse().unwrap())
+        .port(port)
+        .user("postgres")
+        .ssl_mode(SslMode::Disable)
+        .krbsrvname("pg");
+    config.gss_provider(Arc::new({
+        let principals = principals.clone();
+        move |principal| {
+            principals.lock().unwrap().push(principal.to_string());
+            Ok(Box::new(MockContext { steps: 0 }))
+        }
+    }));
+
+    let (client, connection) = config.connect(NoTls).await.unwrap();
+    assert_eq!(client.auth_info().method(), AuthMethod::Gss);
+    assert_eq!(*principals.lock().unwrap(), ["pg@localhost"]);
+
+    drop(client);
+    connection.await.unwrap();
+    server.await.unwrap();
+}
+
+#[cfg(feature = "gssapi")]
+#[tokio::test]
+async fn gss_auth_errors() {
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio_postgres::config::SslMode;
+
+    // Requests GSSAPI authentication from each client and then waits for it to give up.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let len = stream.read_i32().await.unwrap();
+                let mut startup = vec![0; len as usize - 4];
+                stream.read_exact(&mut startup).await.unwrap();
+                stream.write_all(b"R\0\0\0\x08\0\0\0\x07").await.unwrap();
+                let _ = stream.read_to_end(&mut vec![]).await;
+            });
+        }
+    });
+
+    let mut config = Config::new();
+    config
+        .host("localhost")
+        .hostaddr("127.0.0.1".parse().unwrap())
+        .port(port)
+        .user("postgres")
+        .ssl_mode(SslMode::Disable);
+
+    let err = config.connect(NoTls).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "authentication error: server requested GSSAPI authentication but no provider is configured"
+    );
+
+    config.gss_provider(Arc::new(|_| Err("no credentials cache found".into())));
+    let err = config.connect(NoTls).await.err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "authentication error: could not create GSSAPI context: no credentials cache found"
+    );
+}
+
+#[tokio::test]
+async fn copy_in_send_error_progress() {
+    let (client, connection) =
+        tokio_postgres::connect("host=localhost port=5433 user=postgres", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(connection);
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER)")
+        .await
+        .unwrap();
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+
+    let sink = client.copy_in("COPY foo FROM STDIN").await.unwrap();
+    pin_mut!(sink);
+    sink.as_mut().set_flush_threshold(0);
+    sink.send(Bytes::from_static(b"1\n")).await.unwrap();
+
+    let killer = connect("host=localhost port=5433 user=postgres").await;
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+    while !client.is_closed() {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The failed item is not counted.
+    let err = sink.send(Bytes::from_static(b"2\n")).await.unwrap_err();
+    assert!(err.is_closed());
+    let progress = err.copy_progress().unwrap();
+    assert_eq!(progress.bytes_sent(), 2);
+    assert_eq!(progress.rows_sent(), 1);
+}
+
+#[cfg(feature = "bumpalo")]
+#[tokio::test]
+async fn copy_in_in_error_progress() {
+    let (client, connection) =
+        tokio_postgres::connect("host=localhost port=5433 user=postgres", NoTls)
+            .await
+            .unwrap();
+    tokio::spawn(connection);
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER)")
+        .await
+        .unwrap();
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+
+    let arena = bumpalo::Bump::new();
+    let sink = client
+        .copy_in_in("COPY foo FROM STDIN", &arena)
+        .await
+        .unwrap();
+    pin_mut!(sink);
+    sink.send(Bytes::from_static(b"1\n")).await.unwrap();
+    sink.feed(Bytes::from_static(b"2\n")).await.unwrap();
+
+    let killer = connect("host=localhost port=5433 user=postgres").await;
+    killer
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .await
+        .unwrap();
+    while !client.is_closed() {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The item still buffered in the sink is not counted.
+    let err = sink.finish().await.unwrap_err();
+    assert!(err.is_closed());
+    let progress = err.copy_progress().unwrap();
+    assert_eq!(progress.bytes_sent(), 2);
+    assert_eq!(progress.rows_sent(), 1);
+}
+
+#[tokio::test]
+async fn copy_in_flush_interval() {
+    let client = connect("host=localhost port=5433 user=postgres").await;
+    let monitor = connect("host=localhost port=5433 user=postgres").await;
+
+    client
+        .batch_execute("CREATE TEMPORARY TABLE foo (id INTEGER)")
+        .await
+        .unwrap();
+    let pid = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await
+        .unwrap()
+        .get::<_, i32>(0);
+
+    let sink = client.copy_in("COPY foo FROM STDIN").await.unwrap();
+    pin_mut!(sink);
+    sink.as_mut()
+        .set_flush_interval(Some(Duration::from_millis(50)));
+    sink.feed(Bytes::from_static(b"1\n")).await.unwrap();
+    time::sleep(Duration::from_millis(100)).await;
+    // The first item has been buffered for longer than the interval, so it is sent before the second is buffered.
+    sink.feed(Bytes::from_static(b"2\n")).await.unwrap();
+
+    let mut bytes = 0;
+    for _ in 0..100 {
+        bytes = monitor
+            .query_one(
+                "SELECT bytes_processed FROM pg_stat_progress_copy WHERE pid = $1",
+                &[&pid],
+            )
+            .await
+            .unwrap()
+            .get::<_, i64>(0);
+        if bytes == 2 {
+            break;
+        }
+        time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(bytes, 2);
+
+    assert_eq!(sink.finish().await.unwrap(), 2);
+}
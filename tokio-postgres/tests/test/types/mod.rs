@@ -1,4 +1,5 @@
 use postgres_types::to_sql_checked;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::error::Error;
 use std::f32;
@@ -8,7 +9,10 @@ use std::net::IpAddr;
 use std::result;
 use std::str::FromStr;
 use std::time::{Duration, UNIX_EPOCH};
-use tokio_postgres::types::{FromSql, FromSqlOwned, IsNull, Kind, PgLsn, ToSql, Type, WrongType};
+use tokio_postgres::types::{
+    FromSql, FromSqlOwned, IsNull, Kind, PgBox, PgCircle, PgJsonPath, PgLine, PgLseg, PgLsn,
+    PgMoney, PgPath, PgPoint, PgPolygon, ToSql, Type, WrongType,
+};
 
 use crate::connect;
 use bytes::BytesMut;
@@ -78,6 +82,37 @@ async fn test_i8_params() {
     test_type("\"char\"", &[(Some('a' as i8), "'a'"), (None, "NULL")]).await;
 }
 
+#[tokio::test]
+async fn test_char_params() {
+    test_type("\"char\"", &[(Some('a'), "'a'"), (None, "NULL")]).await;
+}
+
+#[tokio::test]
+async fn char_relkind() {
+    let client = connect("user=postgres").await;
+
+    let relkind = client
+        .query_one(
+            "SELECT relkind FROM pg_catalog.pg_class WHERE relname = 'pg_class'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get::<_, char>(0);
+    assert_eq!(relkind, 'r');
+
+    let err = client
+        .query_one("SELECT $1::\"char\"", &[&'é'])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("ASCII"));
+
+    // CHAR(n) is a string type
+    let row = client.query_one("SELECT 'a'::CHAR(1)", &[]).await.unwrap();
+    assert!(row.try_get::<_, char>(0).is_err());
+    assert_eq!(row.get::<_, &str>(0), "a");
+}
+
 #[tokio::test]
 async fn test_name_params() {
     test_type(
@@ -94,6 +129,32 @@ async fn test_name_params() {
     .await;
 }
 
+#[tokio::test]
+async fn test_name_catalog() {
+    let client = connect("user=postgres").await;
+
+    let stmt = client
+        .prepare("SELECT relname FROM pg_catalog.pg_class WHERE relname = 'pg_class'")
+        .await
+        .unwrap();
+    assert_eq!(stmt.columns()[0].type_(), &Type::NAME);
+
+    let row = client.query_one(&stmt, &[]).await.unwrap();
+    assert_eq!(row.get::<_, &str>(0), "pg_class");
+    assert_eq!(row.get::<_, String>(0), "pg_class");
+    assert!(matches!(
+        row.get::<_, Cow<'_, str>>(0),
+        Cow::Borrowed("pg_class")
+    ));
+
+    // Identifiers longer than NAMEDATALEN - 1 bytes are truncated by the server.
+    let row = client
+        .query_one("SELECT repeat('a', 100)::NAME", &[])
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, &str>(0), "a".repeat(63));
+}
+
 #[tokio::test]
 async fn test_i16_params() {
     test_type(
@@ -152,12 +213,230 @@ async fn test_lsn_params() {
         "PG_LSN",
         &[
             (Some(PgLsn::from_str("2B/1757980").unwrap()), "'2B/1757980'"),
+            (Some(PgLsn::from(u64::MAX)), "'FFFFFFFF/FFFFFFFF'"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_lsn_wal_functions() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT pg_current_wal_lsn(), pg_current_wal_lsn()::TEXT",
+            &[],
+        )
+        .await
+        .unwrap();
+    let lsn = row.get::<_, PgLsn>(0);
+    assert_eq!(lsn.to_string(), row.get::<_, &str>(1));
+    assert_eq!(row.get::<_, &str>(1).parse::<PgLsn>().unwrap(), lsn);
+
+    let offset = client
+        .query_one("SELECT ($1 - '0/0'::PG_LSN)::TEXT", &[&lsn])
+        .await
+        .unwrap();
+    assert_eq!(offset.get::<_, &str>(0), u64::from(lsn).to_string());
+}
+
+#[test]
+fn test_lsn_parse() {
+    assert_eq!(
+        "16/B374D848".parse::<PgLsn>().unwrap(),
+        PgLsn::from(0x16_B374_D848)
+    );
+    assert_eq!("0/0".parse::<PgLsn>().unwrap(), PgLsn::from(0));
+    assert_eq!(PgLsn::from(0x16_B374_D848).to_string(), "16/B374D848");
+
+    for invalid in [
+        "",
+        "16",
+        "16/",
+        "/B374D848",
+        "16/B374D848/0",
+        "G/0",
+        "100000000/0",
+    ] {
+        let err = invalid.parse::<PgLsn>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid LSN");
+    }
+}
+
+#[tokio::test]
+async fn test_money_params() {
+    test_type(
+        "MONEY",
+        &[
+            (Some(PgMoney(1234)), "'12.34'"),
+            (Some(PgMoney(-5)), "'-0.05'"),
             (None, "NULL"),
         ],
     )
     .await
 }
 
+#[tokio::test]
+async fn test_jsonpath_params() {
+    test_type(
+        "JSONPATH",
+        &[
+            (
+                Some(PgJsonPath("$.\"a\"[*]?(@ > 1)".to_string())),
+                "'$.a[*] ? (@ > 1)'",
+            ),
+            (
+                Some(PgJsonPath("strict $.\"b\"".to_string())),
+                "'strict $.b'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_geometric_params() {
+    test_type(
+        "POINT",
+        &[(Some(PgPoint::new(1.5, -2.)), "'(1.5,-2)'"), (None, "NULL")],
+    )
+    .await;
+    test_type(
+        "BOX",
+        &[
+            (
+                Some(PgBox {
+                    high: PgPoint::new(3., 4.),
+                    low: PgPoint::new(1., 2.),
+                }),
+                "'((1,2),(3,4))'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "LINE",
+        &[
+            (
+                Some(PgLine {
+                    a: 1.,
+                    b: -1.,
+                    c: 0.,
+                }),
+                "'{1,-1,0}'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "LSEG",
+        &[
+            (
+                Some(PgLseg {
+                    start: PgPoint::new(1., 2.),
+                    end: PgPoint::new(3., 4.),
+                }),
+                "'[(1,2),(3,4)]'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "PATH",
+        &[
+            (
+                Some(PgPath {
+                    closed: false,
+                    points: vec![
+                        PgPoint::new(0., 0.),
+                        PgPoint::new(1., 1.),
+                        PgPoint::new(2., 0.),
+                    ],
+                }),
+                "'[(0,0),(1,1),(2,0)]'",
+            ),
+            (
+                Some(PgPath {
+                    closed: true,
+                    points: vec![PgPoint::new(0., 0.)],
+                }),
+                "'((0,0))'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "POLYGON",
+        &[
+            (
+                Some(PgPolygon {
+                    points: vec![
+                        PgPoint::new(0., 0.),
+                        PgPoint::new(0., 1.),
+                        PgPoint::new(1., 0.),
+                    ],
+                }),
+                "'((0,0),(0,1),(1,0))'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+    test_type(
+        "CIRCLE",
+        &[
+            (
+                Some(PgCircle {
+                    center: PgPoint::new(1., 2.),
+                    radius: 3.,
+                }),
+                "'<(1,2),3>'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn geometric_wrong_type() {
+    let client = connect("user=postgres").await;
+
+    let err = client
+        .query_one("SELECT '(1,2)'::point", &[])
+        .await
+        .unwrap()
+        .try_get::<_, PgCircle>(0)
+        .unwrap_err();
+    assert!(err.source().unwrap().is::<WrongType>());
+}
+
+#[tokio::test]
+async fn wrong_type_message() {
+    let client = connect("user=postgres").await;
+
+    let row = client
+        .query_one("SELECT 'a'::TEXT AS x", &[])
+        .await
+        .unwrap();
+    let err = row.try_get::<_, i64>("x").unwrap_err();
+    let wrong_type = err.source().unwrap().downcast_ref::<WrongType>().unwrap();
+    assert_eq!(wrong_type.column(), Some("x"));
+    assert_eq!(wrong_type.postgres_type(), &Type::TEXT);
+    assert_eq!(wrong_type.rust_type(), "i64");
+    assert_eq!(
+        wrong_type.to_string(),
+        "cannot convert column \"x\" of type text to i64"
+    );
+}
+
 #[tokio::test]
 async fn test_f32_params() {
     test_type(
@@ -228,6 +507,16 @@ async fn test_borrowed_text() {
     assert_eq!(s, "foo");
 }
 
+#[tokio::test]
+async fn test_cow_text() {
+    let client = connect("user=postgres").await;
+
+    let stmt = client.prepare("SELECT 'foo'").await.unwrap();
+    let rows = client.query(&stmt, &[]).await.unwrap();
+    let s: Cow<'_, str> = rows[0].get(0);
+    assert!(matches!(s, Cow::Borrowed("foo")));
+}
+
 #[tokio::test]
 async fn test_bpchar_params() {
     let client = connect("user=postgres").await;
@@ -624,6 +913,37 @@ async fn system_time() {
     .await;
 }
 
+#[tokio::test]
+async fn system_time_timestamptz() {
+    test_type(
+        "TIMESTAMPTZ",
+        &[
+            (
+                Some(UNIX_EPOCH + Duration::from_micros(1_010_001)),
+                "'1970-01-01 00:00:01.010001+00'",
+            ),
+            (
+                Some(UNIX_EPOCH - Duration::from_millis(946_684_800 * 1000 + 1_010)),
+                "'1940-01-01 23:59:58.99+00'",
+            ),
+            (None, "NULL"),
+        ],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn system_time_overflow() {
+    let client = connect("user=postgres").await;
+
+    let time = UNIX_EPOCH + Duration::from_secs(i64::MAX as u64 / 2);
+    let err = client
+        .query_one("SELECT $1::TIMESTAMPTZ", &[&time])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("value too large to transmit"));
+}
+
 #[tokio::test]
 async fn inet() {
     test_type(
@@ -763,3 +1083,38 @@ async fn int2vector() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn catalog_vectors() {
+    let client = connect("user=postgres").await;
+
+    client
+        .batch_execute(
+            "CREATE TEMPORARY TABLE foo (a INT, b TEXT, c INT);
+             CREATE INDEX foo_idx ON foo (c, a);",
+        )
+        .await
+        .unwrap();
+
+    let row = client
+        .query_one(
+            "SELECT indkey, indclass FROM pg_index WHERE indexrelid = 'foo_idx'::regclass",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(row.get::<_, Vec<i16>>(0), [3, 1]);
+    assert_eq!(row.get::<_, Vec<u32>>(1).len(), 2);
+
+    let row = client
+        .query_one(
+            "SELECT proargtypes FROM pg_proc WHERE oid = 'int4pl'::regproc",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        row.get::<_, Vec<u32>>(0),
+        [Type::INT4.oid(), Type::INT4.oid()]
+    );
+}
@@ -39,3 +39,27 @@ async fn test_jsonb_params() {
     )
     .await
 }
+
+#[tokio::test]
+async fn test_json_value() {
+    let client = crate::connect("user=postgres").await;
+
+    let row = client
+        .query_one(
+            "SELECT '{\"a\": [1, 2]}'::JSON, '{\"b\": null}'::JSONB, NULL::JSONB",
+            &[],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        row.json_value(0).unwrap(),
+        Some(serde_json_1::json!({"a": [1, 2]}))
+    );
+    assert_eq!(
+        row.json_value(1).unwrap(),
+        Some(serde_json_1::json!({"b": null}))
+    );
+    assert_eq!(row.json_value(2).unwrap(), None);
+    row.json_value("missing").unwrap_err();
+}
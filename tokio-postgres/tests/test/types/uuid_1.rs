@@ -16,3 +16,22 @@ async fn test_uuid_params() {
     )
     .await
 }
+
+#[tokio::test]
+async fn test_uuid_array_params() {
+    test_type(
+        "UUID[]",
+        &[
+            (
+                Some(vec![
+                    Some(Uuid::parse_str("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").unwrap()),
+                    None,
+                ]),
+                "ARRAY['a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::UUID, NULL]",
+            ),
+            (Some(vec![]), "ARRAY[]::UUID[]"),
+            (None, "NULL"),
+        ],
+    )
+    .await
+}